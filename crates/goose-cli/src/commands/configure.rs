@@ -1,6 +1,7 @@
 use cliclack::spinner;
 use console::style;
-use goose::agents::{extension::Envs, ExtensionConfig};
+use goose::agents::extension::{Envs, ExtensionAuth};
+use goose::agents::ExtensionConfig;
 use goose::config::{Config, ConfigError, ExperimentManager, ExtensionEntry, ExtensionManager};
 use goose::message::Message;
 use goose::providers::{create, providers};
@@ -566,34 +567,81 @@ pub fn configure_extensions_dialog() -> Result<(), Box<dyn Error>> {
                 })
                 .interact()?;
 
-            let add_env =
-                cliclack::confirm("Would you like to add environment variables?").interact()?;
+            let add_headers =
+                cliclack::confirm("Would you like to add HTTP headers?").interact()?;
 
-            let mut envs = HashMap::new();
-            if add_env {
+            let mut headers = HashMap::new();
+            if add_headers {
                 loop {
-                    let key: String = cliclack::input("Environment variable name:")
-                        .placeholder("API_KEY")
+                    let key: String = cliclack::input("Header name:")
+                        .placeholder("X-API-Key")
                         .interact()?;
 
-                    let value: String = cliclack::password("Environment variable value:")
-                        .mask('▪')
-                        .interact()?;
+                    let value: String = cliclack::password("Header value:").mask('▪').interact()?;
 
-                    envs.insert(key, value);
+                    headers.insert(key, value);
 
-                    if !cliclack::confirm("Add another environment variable?").interact()? {
+                    if !cliclack::confirm("Add another header?").interact()? {
                         break;
                     }
                 }
             }
 
+            let auth_type = cliclack::select("How does the server authenticate you?")
+                .item("none", "No authentication", "Headers are enough, if any")
+                .item(
+                    "bearer",
+                    "Bearer token",
+                    "Store a token in the keyring and send it with every request",
+                )
+                .item("oauth", "OAuth", "Sign in through the browser")
+                .interact()?;
+
+            let auth = match auth_type {
+                "bearer" => {
+                    let token: String = cliclack::password("Bearer token:").mask('▪').interact()?;
+
+                    // Keep the token out of the config file, in the keyring
+                    let secret = format!(
+                        "{}_BEARER_TOKEN",
+                        name.to_uppercase()
+                            .replace(|c: char| !c.is_alphanumeric(), "_")
+                    );
+                    Config::global().set_secret(&secret, Value::String(token))?;
+                    Some(ExtensionAuth::Bearer { secret })
+                }
+                "oauth" => {
+                    let client_id: String = cliclack::input("OAuth client ID:").interact()?;
+
+                    let redirect_url: String = cliclack::input("OAuth redirect URL:")
+                        .default_input("http://localhost:8020")
+                        .interact()?;
+
+                    let scopes: String = cliclack::input("OAuth scopes (comma separated):")
+                        .required(false)
+                        .interact()?;
+
+                    Some(ExtensionAuth::OAuth {
+                        client_id,
+                        redirect_url,
+                        scopes: scopes
+                            .split(',')
+                            .map(|scope| scope.trim().to_string())
+                            .filter(|scope| !scope.is_empty())
+                            .collect(),
+                    })
+                }
+                _ => None,
+            };
+
             ExtensionManager::set(ExtensionEntry {
                 enabled: true,
                 config: ExtensionConfig::Sse {
                     name: name.clone(),
                     uri,
-                    envs: Envs::new(envs),
+                    envs: Envs::default(),
+                    headers,
+                    auth,
                     timeout: Some(timeout),
                 },
             })?;
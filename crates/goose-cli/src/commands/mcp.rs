@@ -1,10 +1,5 @@
 use anyhow::Result;
-use goose_mcp::{
-    ComputerControllerRouter, DeveloperRouter, GoogleDriveRouter, JetBrainsRouter, MemoryRouter,
-    TutorialRouter,
-};
-use mcp_server::router::RouterService;
-use mcp_server::{BoundedService, ByteTransport, Server};
+use mcp_server::{ByteTransport, Peer, Server};
 use tokio::io::{stdin, stdout};
 
 pub async fn run_server(name: &str) -> Result<()> {
@@ -13,21 +8,14 @@ pub async fn run_server(name: &str) -> Result<()> {
 
     tracing::info!("Starting MCP server");
 
-    let router: Option<Box<dyn BoundedService>> = match name {
-        "developer" => Some(Box::new(RouterService(DeveloperRouter::new()))),
-        "computercontroller" => Some(Box::new(RouterService(ComputerControllerRouter::new()))),
-        "jetbrains" => Some(Box::new(RouterService(JetBrainsRouter::new()))),
-        "google_drive" | "googledrive" => {
-            let router = GoogleDriveRouter::new().await;
-            Some(Box::new(RouterService(router)))
-        }
-        "memory" => Some(Box::new(RouterService(MemoryRouter::new()))),
-        "tutorial" => Some(Box::new(RouterService(TutorialRouter::new()))),
-        _ => None,
-    };
+    // Routers reach the client, e.g. to send notifications or list its roots, through the peer
+    let (peer, notifications) = Peer::new();
+
+    let router = goose_mcp::builtin_router(name, peer.clone()).await;
 
     // Create and run the server
-    let server = Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)));
+    let server = Server::new(router.unwrap_or_else(|| panic!("Unknown server requested {}", name)))
+        .with_peer(peer, notifications);
     let transport = ByteTransport::new(stdin(), stdout());
 
     tracing::info!("Server initialized and ready to handle requests");
@@ -3,4 +3,5 @@ pub mod bench;
 pub mod configure;
 pub mod info;
 pub mod mcp;
+pub mod session;
 pub mod update;
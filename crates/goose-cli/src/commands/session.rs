@@ -0,0 +1,317 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use console::style;
+use goose::session::{
+    self, ExportFormat, ExportOptions, Identifier, ImportFormat, SessionInfo, SessionMetadata,
+    StoredSession,
+};
+use std::path::{Path, PathBuf};
+
+/// Open a session of the default store by name, failing if it does not exist
+fn open_existing(name: &str) -> Result<StoredSession> {
+    let stored = StoredSession::open(Identifier::Name(name.to_string()))?;
+    if !stored.exists()? {
+        return Err(anyhow::anyhow!("No session named '{}'", name));
+    }
+    Ok(stored)
+}
+
+/// Ask before doing something that cannot be undone, unless `yes` was given
+fn confirm(prompt: String, yes: bool) -> Result<bool> {
+    if yes {
+        return Ok(true);
+    }
+    Ok(cliclack::confirm(prompt).initial_value(false).interact()?)
+}
+
+/// Parse an age like `30d`, with a unit of `m`inutes, `h`ours, `d`ays or `w`eeks
+pub fn parse_age(age: &str) -> Result<Duration> {
+    let age = age.trim();
+    let split = age.len() - age.chars().last().map_or(0, |c| c.len_utf8());
+    let (amount, unit) = age.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .ok()
+        .filter(|&amount| amount > 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid age '{}', expected e.g. '30d'", age))?;
+
+    let duration = match unit {
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => {
+            return Err(anyhow::anyhow!(
+                "Invalid age '{}', the unit must be one of m, h, d or w",
+                age
+            ))
+        }
+    };
+    duration.ok_or_else(|| anyhow::anyhow!("Invalid age '{}', it is too long", age))
+}
+
+fn render_session(info: &SessionInfo) {
+    let metadata = &info.metadata;
+    let tokens = metadata
+        .total_tokens
+        .map_or_else(|| "-".to_string(), |tokens| tokens.to_string());
+    let tags = if metadata.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", metadata.tags.join(", "))
+    };
+
+    println!(
+        "{} {} {}{}",
+        style(&info.id).cyan().bold(),
+        style(info.modified.format("%Y-%m-%d %H:%M")).dim(),
+        metadata.description,
+        style(tags).green()
+    );
+    println!(
+        "    {} messages, {} tokens, in {}",
+        metadata.message_count,
+        tokens,
+        style(metadata.working_dir.display()).dim()
+    );
+}
+
+pub fn handle_session_list(archived: bool, tag: Option<String>, format: &str) -> Result<()> {
+    let sessions: Vec<SessionInfo> = session::default_store()?
+        .list()?
+        .into_iter()
+        .filter(|info| info.metadata.archived == archived)
+        .filter(|info| {
+            tag.as_ref()
+                .is_none_or(|tag| info.metadata.tags.contains(tag))
+        })
+        .collect();
+
+    match format {
+        "json" => {
+            let sessions: Vec<_> = sessions
+                .iter()
+                .map(|info| serde_json::json!({ "id": info.id, "metadata": info.metadata }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&sessions)?);
+        }
+        "text" => {
+            if sessions.is_empty() {
+                println!("No sessions found");
+            }
+            for info in &sessions {
+                render_session(info);
+            }
+        }
+        other => {
+            return Err(anyhow::anyhow!(
+                "Unknown format '{}', expected 'text' or 'json'",
+                other
+            ))
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_session_rename(name: &str, new_name: &str) -> Result<()> {
+    let mut stored = open_existing(name)?;
+    stored.rename(new_name)?;
+    println!("Renamed session {} to {}", name, style(new_name).cyan());
+    Ok(())
+}
+
+pub fn handle_session_delete(names: &[String], yes: bool) -> Result<()> {
+    let sessions = names
+        .iter()
+        .map(|name| open_existing(name))
+        .collect::<Result<Vec<_>>>()?;
+
+    if !confirm(format!("Delete {} session(s)?", sessions.len()), yes)? {
+        return Ok(());
+    }
+    for stored in sessions {
+        stored.delete()?;
+        println!("Deleted session {}", stored.id());
+    }
+    Ok(())
+}
+
+pub fn handle_session_prune(older_than: &str, dry_run: bool, yes: bool) -> Result<()> {
+    let cutoff = Utc::now()
+        .checked_sub_signed(parse_age(older_than)?)
+        .ok_or_else(|| anyhow::anyhow!("Invalid age '{}', it is too long", older_than))?;
+    let store = session::default_store()?;
+    let old: Vec<SessionInfo> = store
+        .list()?
+        .into_iter()
+        .filter(|info| !info.metadata.archived && info.modified < cutoff)
+        .collect();
+
+    if old.is_empty() {
+        println!("No sessions older than {}", older_than);
+        return Ok(());
+    }
+    for info in &old {
+        render_session(info);
+    }
+    if dry_run || !confirm(format!("Delete these {} session(s)?", old.len()), yes)? {
+        return Ok(());
+    }
+
+    for info in &old {
+        store.delete(&info.id)?;
+    }
+    println!("Deleted {} session(s)", old.len());
+    Ok(())
+}
+
+pub fn handle_session_archive(names: &[String], restore: bool) -> Result<()> {
+    for name in names {
+        let stored = open_existing(name)?;
+        let mut metadata = stored.read_metadata()?;
+        metadata.archived = !restore;
+        stored.update_metadata(&metadata)?;
+        if restore {
+            println!("Restored session {}", name);
+        } else {
+            println!("Archived session {}", name);
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_session_tag(name: &str, tags: &[String], remove: bool) -> Result<()> {
+    let stored = open_existing(name)?;
+    let mut metadata = stored.read_metadata()?;
+    if remove {
+        metadata.tags.retain(|tag| !tags.contains(tag));
+    } else {
+        for tag in tags {
+            if !metadata.tags.contains(tag) {
+                metadata.tags.push(tag.clone());
+            }
+        }
+    }
+    stored.update_metadata(&metadata)?;
+    println!("Tags of {}: {}", name, metadata.tags.join(", "));
+    Ok(())
+}
+
+pub fn handle_session_doctor(name: &str, dry_run: bool, yes: bool) -> Result<()> {
+    let stored = open_existing(name)?;
+    let (mut metadata, messages) = stored
+        .read_metadata()
+        .and_then(|metadata| Ok((metadata, stored.read_messages()?)))
+        .map_err(|e| anyhow::anyhow!("Session {} cannot be read: {}", name, e))?;
+
+    let problems = session::diagnose(&messages);
+    if problems.is_empty() {
+        println!("No problems found in session {}", name);
+        return Ok(());
+    }
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    if dry_run || !confirm(format!("Repair {} problem(s)?", problems.len()), yes)? {
+        return Ok(());
+    }
+
+    let repaired = session::repair(&messages);
+    stored.replace_messages(&repaired)?;
+    metadata.message_count = repaired.len();
+    stored.update_metadata(&metadata)?;
+    println!("Repaired session {}", name);
+    Ok(())
+}
+
+pub fn handle_session_search(query: &str, limit: usize) -> Result<()> {
+    let hits = session::default_store()?.search(query, limit)?;
+    if hits.is_empty() {
+        println!("No sessions match '{}'", query);
+        return Ok(());
+    }
+
+    for hit in hits {
+        let location = match hit.message_index {
+            Some(index) => format!("message {}", index),
+            None => "description".to_string(),
+        };
+        println!(
+            "{} {} {}",
+            style(&hit.session_id).cyan().bold(),
+            style(location).dim(),
+            hit.description
+        );
+        println!("    {}", hit.snippet);
+    }
+    Ok(())
+}
+
+pub fn handle_session_export(
+    name: String,
+    format: &str,
+    thinking: bool,
+    truncate: usize,
+    output: Option<PathBuf>,
+) -> Result<()> {
+    let format: ExportFormat = format.parse()?;
+    let options = ExportOptions {
+        thinking,
+        // Zero keeps tool results whole
+        max_result_length: (truncate > 0).then_some(truncate),
+    };
+    let stored = open_existing(&name)?;
+
+    let exported = session::export(&stored, format, &options)?;
+    match output {
+        Some(path) => {
+            std::fs::write(&path, exported)?;
+            println!("Exported session {} to {}", name, path.display());
+        }
+        None => print!("{}", exported),
+    }
+    Ok(())
+}
+
+pub fn handle_session_import(
+    file: &Path,
+    format: Option<&str>,
+    name: Option<String>,
+) -> Result<()> {
+    let format = format.map(str::parse::<ImportFormat>).transpose()?;
+    let conversation = std::fs::read_to_string(file)?;
+    let messages = session::parse_conversation(&conversation, format)
+        .map_err(|e| anyhow::anyhow!("Cannot import {}: {}", file.display(), e))?;
+
+    let name = name.unwrap_or_else(session::generate_session_id);
+    let mut metadata = SessionMetadata::new(std::env::current_dir()?);
+    if let Some(stem) = file.file_stem() {
+        metadata.description = format!("Imported from {}", stem.to_string_lossy());
+    }
+    session::import(&session::default_store()?, &name, &messages, metadata)?;
+    println!(
+        "Imported {} messages into session {}, resume it with `goose session --resume --name {}`",
+        messages.len(),
+        name,
+        name
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_age("2w").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_age("12h").unwrap(), Duration::hours(12));
+        assert!(parse_age("30").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("").is_err());
+        assert!(parse_age("0d").is_err());
+        assert!(parse_age("-5d").is_err());
+        assert!(parse_age(&format!("{}w", i64::MAX)).is_err());
+    }
+}
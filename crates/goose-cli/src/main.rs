@@ -8,6 +8,11 @@ use goose_cli::commands::bench::{list_suites, run_benchmark};
 use goose_cli::commands::configure::handle_configure;
 use goose_cli::commands::info::handle_info;
 use goose_cli::commands::mcp::run_server;
+use goose_cli::commands::session::{
+    handle_session_archive, handle_session_delete, handle_session_doctor, handle_session_export,
+    handle_session_import, handle_session_list, handle_session_prune, handle_session_rename,
+    handle_session_search, handle_session_tag,
+};
 use goose_cli::logging::setup_logging;
 use goose_cli::session;
 use goose_cli::session::build_session;
@@ -53,6 +58,178 @@ fn extract_identifier(identifier: Identifier) -> session::Identifier {
     }
 }
 
+#[derive(Subcommand)]
+enum SessionCommand {
+    /// List sessions with their details
+    #[command(about = "List sessions with their details")]
+    List {
+        #[arg(long, help = "List archived sessions instead")]
+        archived: bool,
+
+        #[arg(long, value_name = "TAG", help = "Only list sessions with this tag")]
+        tag: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            help = "Output format (text, json)",
+            default_value = "text"
+        )]
+        format: String,
+    },
+
+    /// Rename a session
+    #[command(about = "Rename a session")]
+    Rename {
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        #[arg(value_name = "NEW_NAME")]
+        new_name: String,
+    },
+
+    /// Delete sessions
+    #[command(about = "Delete sessions")]
+    Delete {
+        #[arg(required = true, value_name = "NAME")]
+        names: Vec<String>,
+
+        #[arg(short, long, help = "Delete without asking for confirmation")]
+        yes: bool,
+    },
+
+    /// Delete sessions not used for a while
+    #[command(about = "Delete sessions not used for a while")]
+    Prune {
+        #[arg(
+            long,
+            value_name = "AGE",
+            help = "Delete sessions last used longer ago than this (e.g. 30d, 12h, 2w)",
+            long_help = "Delete sessions last used longer ago than this. The age is a number with a unit of m (minutes), h (hours), d (days) or w (weeks). Archived sessions are kept."
+        )]
+        older_than: String,
+
+        #[arg(long, help = "Only show the sessions that would be deleted")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Delete without asking for confirmation")]
+        yes: bool,
+    },
+
+    /// Archive sessions to hide them from the list without deleting them
+    #[command(about = "Archive sessions, hiding them from the list")]
+    Archive {
+        #[arg(required = true, value_name = "NAME")]
+        names: Vec<String>,
+
+        #[arg(long, help = "Bring archived sessions back to the list")]
+        restore: bool,
+    },
+
+    /// Tag a session to find it later
+    #[command(about = "Add or remove tags of a session")]
+    Tag {
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        #[arg(required = true, value_name = "TAG")]
+        tags: Vec<String>,
+
+        #[arg(long, help = "Remove the tags instead of adding them")]
+        remove: bool,
+    },
+
+    /// Check a session for problems and repair them
+    #[command(about = "Check a session for problems and repair them")]
+    Doctor {
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        #[arg(long, help = "Only show the problems found")]
+        dry_run: bool,
+
+        #[arg(short, long, help = "Repair without asking for confirmation")]
+        yes: bool,
+    },
+
+    /// Search the messages and descriptions of all sessions
+    #[command(about = "Search the history of all sessions")]
+    Search {
+        /// Words to search for
+        #[arg(required = true, value_name = "QUERY")]
+        query: Vec<String>,
+
+        /// Maximum number of matches to show
+        #[arg(
+            short,
+            long,
+            default_value = "20",
+            help = "Maximum number of matches to show"
+        )]
+        limit: usize,
+    },
+
+    /// Export a session as a transcript
+    #[command(about = "Export a session to Markdown, HTML or JSON")]
+    Export {
+        /// Name of the session to export
+        #[arg(value_name = "NAME")]
+        name: String,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FORMAT",
+            help = "Output format (md, html, json)",
+            default_value = "md"
+        )]
+        format: String,
+
+        #[arg(long, help = "Include the thinking of the model")]
+        thinking: bool,
+
+        #[arg(
+            long,
+            value_name = "CHARS",
+            help = "Cut tool results longer than this, 0 keeps them whole",
+            default_value = "1000"
+        )]
+        truncate: usize,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Write the export to a file instead of stdout"
+        )]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a conversation from another tool as a new session
+    #[command(about = "Import an OpenAI or Anthropic conversation as a session")]
+    Import {
+        /// JSON file with the conversation
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        #[arg(
+            short,
+            long,
+            value_name = "FORMAT",
+            help = "Format of the conversation (openai, anthropic), detected if not given"
+        )]
+        format: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "NAME",
+            help = "Name of the new session, generated if not given"
+        )]
+        name: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Configure Goose settings
@@ -74,9 +251,13 @@ enum Command {
     /// Start or resume interactive chat sessions
     #[command(
         about = "Start or resume interactive chat sessions",
-        visible_alias = "s"
+        visible_alias = "s",
+        args_conflicts_with_subcommands = true
     )]
     Session {
+        #[command(subcommand)]
+        command: Option<SessionCommand>,
+
         /// Identifier for the chat session
         #[command(flatten)]
         identifier: Option<Identifier>,
@@ -299,6 +480,59 @@ async fn main() -> Result<()> {
             let _ = run_server(&name).await;
         }
         Some(Command::Session {
+            command: Some(command),
+            ..
+        }) => {
+            match command {
+                SessionCommand::List {
+                    archived,
+                    tag,
+                    format,
+                } => {
+                    handle_session_list(archived, tag, &format)?;
+                }
+                SessionCommand::Rename { name, new_name } => {
+                    handle_session_rename(&name, &new_name)?;
+                }
+                SessionCommand::Delete { names, yes } => {
+                    handle_session_delete(&names, yes)?;
+                }
+                SessionCommand::Prune {
+                    older_than,
+                    dry_run,
+                    yes,
+                } => {
+                    handle_session_prune(&older_than, dry_run, yes)?;
+                }
+                SessionCommand::Archive { names, restore } => {
+                    handle_session_archive(&names, restore)?;
+                }
+                SessionCommand::Tag { name, tags, remove } => {
+                    handle_session_tag(&name, &tags, remove)?;
+                }
+                SessionCommand::Doctor { name, dry_run, yes } => {
+                    handle_session_doctor(&name, dry_run, yes)?;
+                }
+                SessionCommand::Search { query, limit } => {
+                    handle_session_search(&query.join(" "), limit)?;
+                }
+                SessionCommand::Export {
+                    name,
+                    format,
+                    thinking,
+                    truncate,
+                    output,
+                } => {
+                    handle_session_export(name, &format, thinking, truncate, output)?;
+                }
+                SessionCommand::Import { file, format, name } => {
+                    handle_session_import(&file, format.as_deref(), name)?;
+                }
+            }
+            return Ok(());
+        }
+        Some(Command::Session {
+            command: None,
             identifier,
             resume,
             debug,
@@ -314,7 +548,7 @@ async fn main() -> Result<()> {
             )
             .await;
 
-            setup_logging(session.session_file().file_stem().and_then(|s| s.to_str()))?;
+            setup_logging(Some(session.session_id()))?;
             let _ = session.interactive(None).await;
             return Ok(());
         }
@@ -354,7 +588,7 @@ async fn main() -> Result<()> {
                 debug,
             )
             .await;
-            setup_logging(session.session_file().file_stem().and_then(|s| s.to_str()))?;
+            setup_logging(Some(session.session_id()))?;
 
             if interactive {
                 session.interactive(Some(contents)).await?;
@@ -431,7 +665,7 @@ async fn main() -> Result<()> {
             } else {
                 // Run session command by default
                 let mut session = build_session(None, false, vec![], vec![], false).await;
-                setup_logging(session.session_file().file_stem().and_then(|s| s.to_str()))?;
+                setup_logging(Some(session.session_id()))?;
                 let _ = session.interactive(None).await;
                 return Ok(());
             }
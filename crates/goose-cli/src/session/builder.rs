@@ -3,9 +3,10 @@ use goose::agents::extension::ExtensionError;
 use goose::agents::AgentFactory;
 use goose::config::{Config, ExtensionManager};
 use goose::session;
-use goose::session::Identifier;
+use goose::session::{Identifier, StoredSession};
 use mcp_client::transport::Error as McpClientError;
 use std::process;
+use std::sync::Arc;
 
 use super::output;
 use super::Session;
@@ -35,43 +36,54 @@ pub async fn build_session(
     let mut agent = AgentFactory::create(&AgentFactory::configured_version(), provider)
         .expect("Failed to create agent");
 
-    // Handle session file resolution and resuming
-    let session_file = if resume {
-        if let Some(identifier) = identifier {
-            let session_file = session::get_path(identifier);
-            if !session_file.exists() {
-                output::render_error(&format!(
-                    "Cannot resume session {} - no such session exists",
-                    style(session_file.display()).cyan()
-                ));
-                process::exit(1);
-            }
+    // Builtin extensions run in this process, unless configured to run as their own process
+    agent
+        .set_builtin_extensions(Arc::new(|name, peer| {
+            Box::pin(async move { goose_mcp::builtin_router(&name, peer).await })
+        }))
+        .await;
 
-            session_file
-        } else {
-            // Try to resume most recent session
-            match session::get_most_recent_session() {
-                Ok(file) => file,
-                Err(_) => {
-                    output::render_error("Cannot resume - no previous sessions found");
-                    process::exit(1);
+    // Handle session resolution and resuming
+    let identifier = if resume {
+        match identifier {
+            Some(identifier) => identifier,
+            None => {
+                // Try to resume most recent session
+                let most_recent = session::default_store()
+                    .and_then(|store| store.list())
+                    .ok()
+                    .and_then(|sessions| sessions.into_iter().next());
+                match most_recent {
+                    Some(info) => Identifier::Name(info.id),
+                    None => {
+                        output::render_error("Cannot resume - no previous sessions found");
+                        process::exit(1);
+                    }
                 }
             }
         }
     } else {
         // Create new session with provided name/path or generated name
-        let id = match identifier {
-            Some(identifier) => identifier,
-            None => Identifier::Name(session::generate_session_id()),
-        };
-
-        // Just get the path - file will be created when needed
-        session::get_path(id)
+        identifier.unwrap_or_else(|| Identifier::Name(session::generate_session_id()))
     };
 
+    // The session is only written once there are messages
+    let stored = StoredSession::open(identifier.clone()).unwrap_or_else(|e| {
+        output::render_error(&format!("Failed to open session: {}", e));
+        process::exit(1);
+    });
+
+    if resume && !stored.exists().unwrap_or(false) {
+        output::render_error(&format!(
+            "Cannot resume session {} - no such session exists",
+            style(stored.id()).cyan()
+        ));
+        process::exit(1);
+    }
+
     if resume {
         // Read the session metadata
-        let metadata = session::read_metadata(&session_file).unwrap_or_else(|e| {
+        let metadata = stored.read_metadata().unwrap_or_else(|e| {
             output::render_error(&format!("Failed to read session metadata: {}", e));
             process::exit(1);
         });
@@ -112,7 +124,8 @@ pub async fn build_session(
     }
 
     // Create new session
-    let mut session = Session::new(agent, session_file.clone(), debug);
+    let session_path = stored.path();
+    let mut session = Session::new(agent, identifier, stored, debug);
 
     // Add extensions if provided
     for extension_str in extensions {
@@ -133,6 +146,8 @@ pub async fn build_session(
     // Add CLI-specific system prompt extension
     session
         .agent
+        .write()
+        .await
         .extend_system_prompt(super::prompt::get_cli_prompt())
         .await;
 
@@ -141,9 +156,14 @@ pub async fn build_session(
     if let Some(ref path) = system_prompt_file {
         let override_prompt =
             std::fs::read_to_string(path).expect("Failed to read system prompt file");
-        session.agent.override_system_prompt(override_prompt).await;
+        session
+            .agent
+            .write()
+            .await
+            .override_system_prompt(override_prompt)
+            .await;
     }
 
-    output::display_session_info(resume, &provider_name, &model, &session_file);
+    output::display_session_info(resume, &provider_name, &model, &session_path);
     session
 }
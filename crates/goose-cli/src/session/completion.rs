@@ -1,3 +1,4 @@
+use goose::agents::Agent;
 use rustyline::completion::{Completer, Pair};
 use rustyline::highlight::{CmdKind, Highlighter};
 use rustyline::hint::Hinter;
@@ -5,18 +6,73 @@ use rustyline::validate::Validator;
 use rustyline::{Helper, Result};
 use std::borrow::Cow;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Handle;
+use tokio::sync::RwLock;
 
 use super::CompletionCache;
 
+// How long to wait for an extension to suggest argument values before giving up
+const ARGUMENT_VALUE_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Completer for Goose CLI commands
 pub struct GooseCompleter {
     completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
+    // The agent and the runtime it runs on, used to ask extensions for argument values
+    agent: Option<(Arc<RwLock<Box<dyn Agent>>>, Handle)>,
 }
 
 impl GooseCompleter {
     /// Create a new GooseCompleter with a reference to the Session's completion cache
     pub fn new(completion_cache: Arc<std::sync::RwLock<CompletionCache>>) -> Self {
-        Self { completion_cache }
+        Self {
+            completion_cache,
+            agent: None,
+        }
+    }
+
+    /// Complete prompt argument values with suggestions from the extension owning the prompt.
+    /// Must be called from within the tokio runtime the agent runs on.
+    pub fn with_agent(mut self, agent: Arc<RwLock<Box<dyn Agent>>>) -> Self {
+        self.agent = Some((agent, Handle::current()));
+        self
+    }
+
+    /// Ask the agent for values of a prompt argument, without suggestions on errors or timeouts
+    fn fetch_argument_values(&self, prompt_name: &str, argument: &str, value: &str) -> Vec<String> {
+        let Some((agent, handle)) = &self.agent else {
+            return vec![];
+        };
+
+        // Completion runs synchronously inside the readline call, which itself runs on a
+        // runtime worker thread, so hand the thread over to the runtime while we wait
+        tokio::task::block_in_place(|| {
+            handle.block_on(async {
+                let agent = agent.read().await;
+                tokio::time::timeout(
+                    ARGUMENT_VALUE_TIMEOUT,
+                    agent.complete_prompt_argument(prompt_name, argument, value),
+                )
+                .await
+            })
+        })
+        .ok()
+        .and_then(|result| result.ok())
+        .unwrap_or_default()
+    }
+
+    /// Complete the value of an argument, typed as `name=value`
+    fn complete_argument_value(&self, prompt_name: &str, argument: &str, value: &str) -> Vec<Pair> {
+        self.fetch_argument_values(prompt_name, argument, value)
+            .into_iter()
+            .map(|suggestion| Pair {
+                // Quote suggestions with spaces so the arguments parse back the same way
+                replacement: shlex::try_quote(&suggestion)
+                    .map(|quoted| quoted.into_owned())
+                    .unwrap_or_else(|_| suggestion.clone()),
+                display: suggestion,
+            })
+            .collect()
     }
 
     /// Complete prompt names for the /prompt command
@@ -129,6 +185,7 @@ impl GooseCompleter {
             "/prompts",
             "/prompt",
             "/mode",
+            "/rewind",
         ];
 
         // Find commands that match the prefix
@@ -188,6 +245,21 @@ impl GooseCompleter {
                         }
                     }
 
+                    // If the last part is a known argument followed by '=', complete its value
+                    // with the extension's suggestions, if it has any
+                    if let Some((argument, value)) = last_part.split_once('=') {
+                        if !line.ends_with(char::is_whitespace)
+                            && args.iter().any(|arg| arg.name == argument)
+                        {
+                            let candidates =
+                                self.complete_argument_value(prompt_name, argument, value);
+                            if !candidates.is_empty() {
+                                // The position is the start of the value
+                                return Ok((line.len() - value.len(), candidates));
+                            }
+                        }
+                    }
+
                     // If the last part doesn't contain '=', it might be a partial argument name
                     if !last_part.contains('=') {
                         // Find arguments that match the prefix
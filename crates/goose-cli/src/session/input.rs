@@ -15,6 +15,7 @@ pub enum InputResult {
     ListPrompts(Option<String>),
     PromptCommand(PromptCommandOptions),
     GooseMode(String),
+    Rewind(usize),
 }
 
 #[derive(Debug)]
@@ -72,6 +73,7 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
     const CMD_EXTENSION: &str = "/extension ";
     const CMD_BUILTIN: &str = "/builtin ";
     const CMD_MODE: &str = "/mode ";
+    const CMD_REWIND: &str = "/rewind ";
 
     match input {
         "/exit" | "/quit" => Some(InputResult::Exit),
@@ -81,6 +83,7 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         }
         "/t" => Some(InputResult::ToggleTheme),
         "/prompts" => Some(InputResult::ListPrompts(None)),
+        "/rewind" => Some(InputResult::Rewind(1)),
         s if s.starts_with(CMD_PROMPTS) => {
             // Parse arguments for /prompts command
             let args = s.strip_prefix(CMD_PROMPTS).unwrap_or_default();
@@ -111,6 +114,11 @@ fn handle_slash_command(input: &str) -> Option<InputResult> {
         s if s.starts_with(CMD_MODE) => {
             Some(InputResult::GooseMode(s[CMD_MODE.len()..].to_string()))
         }
+        s if s.starts_with(CMD_REWIND) => s[CMD_REWIND.len()..]
+            .trim()
+            .parse()
+            .ok()
+            .map(InputResult::Rewind),
         _ => None,
     }
 }
@@ -178,6 +186,7 @@ fn print_help() {
 /prompts [--extension <name>] - List all available prompts, optionally filtered by extension
 /prompt <n> [--info] [key=value...] - Get prompt info or execute a prompt
 /mode <name> - Set the goose mode to use ('auto', 'approve', 'chat')
+/rewind [n] - Remove the last n user messages (1 by default) and everything goose did for them
 /? or /help - Display this help message
 
 Navigation:
@@ -235,6 +244,17 @@ mod tests {
 
         // Test unknown commands
         assert!(handle_slash_command("/unknown").is_none());
+
+        // Test rewind commands
+        assert!(matches!(
+            handle_slash_command("/rewind"),
+            Some(InputResult::Rewind(1))
+        ));
+        assert!(matches!(
+            handle_slash_command("/rewind 3"),
+            Some(InputResult::Rewind(3))
+        ));
+        assert!(handle_slash_command("/rewind many").is_none());
     }
 
     #[test]
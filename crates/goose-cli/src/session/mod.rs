@@ -16,6 +16,7 @@ use goose::agents::extension::{Envs, ExtensionConfig};
 use goose::agents::{Agent, SessionConfig};
 use goose::config::Config;
 use goose::message::{Message, MessageContent};
+use goose::providers::base::Provider;
 use goose::session;
 use mcp_core::handler::ToolError;
 use mcp_core::prompt::PromptMessage;
@@ -23,17 +24,19 @@ use mcp_core::prompt::PromptMessage;
 use rand::{distributions::Alphanumeric, Rng};
 use serde_json::Value;
 use std::collections::HashMap;
-use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 use tokio;
+use tokio::sync::RwLock;
 
 use crate::log_usage::log_usage;
 
 pub struct Session {
-    agent: Box<dyn Agent>,
+    // Shared with the completer, which asks extensions for argument values
+    agent: Arc<RwLock<Box<dyn Agent>>>,
     messages: Vec<Message>,
-    session_file: PathBuf,
+    identifier: Identifier,
+    stored: session::StoredSession,
     // Cache for completion data - using std::sync for thread safety without async
     completion_cache: Arc<std::sync::RwLock<CompletionCache>>,
     debug: bool, // New field for debug mode
@@ -57,8 +60,13 @@ impl CompletionCache {
 }
 
 impl Session {
-    pub fn new(agent: Box<dyn Agent>, session_file: PathBuf, debug: bool) -> Self {
-        let messages = match session::read_messages(&session_file) {
+    pub fn new(
+        agent: Box<dyn Agent>,
+        identifier: Identifier,
+        stored: session::StoredSession,
+        debug: bool,
+    ) -> Self {
+        let messages = match stored.read_messages() {
             Ok(msgs) => msgs,
             Err(e) => {
                 eprintln!("Warning: Failed to load message history: {}", e);
@@ -67,9 +75,10 @@ impl Session {
         };
 
         Session {
-            agent,
+            agent: Arc::new(RwLock::new(agent)),
             messages,
-            session_file,
+            identifier,
+            stored,
             completion_cache: Arc::new(std::sync::RwLock::new(CompletionCache::new())),
             debug,
         }
@@ -116,6 +125,8 @@ impl Session {
         };
 
         self.agent
+            .write()
+            .await
             .add_extension(config)
             .await
             .map_err(|e| anyhow::anyhow!("Failed to start extension: {}", e))?;
@@ -138,6 +149,8 @@ impl Session {
                 timeout: Some(goose::config::DEFAULT_EXTENSION_TIMEOUT),
             };
             self.agent
+                .write()
+                .await
                 .add_extension(config)
                 .await
                 .map_err(|e| anyhow::anyhow!("Failed to start builtin extension: {}", e))?;
@@ -153,7 +166,7 @@ impl Session {
         &mut self,
         extension: Option<String>,
     ) -> Result<HashMap<String, Vec<String>>> {
-        let prompts = self.agent.list_extension_prompts().await;
+        let prompts = self.agent.read().await.list_extension_prompts().await;
 
         // Early validation if filtering by extension
         if let Some(filter) = &extension {
@@ -174,7 +187,7 @@ impl Session {
     }
 
     pub async fn get_prompt_info(&mut self, name: &str) -> Result<Option<output::PromptInfo>> {
-        let prompts = self.agent.list_extension_prompts().await;
+        let prompts = self.agent.read().await.list_extension_prompts().await;
 
         // Find which extension has this prompt
         for (extension, prompt_list) in prompts {
@@ -192,18 +205,25 @@ impl Session {
     }
 
     pub async fn get_prompt(&mut self, name: &str, arguments: Value) -> Result<Vec<PromptMessage>> {
-        let result = self.agent.get_prompt(name, arguments).await?;
+        let result = self.agent.read().await.get_prompt(name, arguments).await?;
         Ok(result.messages)
     }
 
+    /// Save the conversation, telling the user about secrets redacted from it
+    async fn persist(&self, provider: Option<Arc<Box<dyn Provider>>>) -> Result<()> {
+        let redactions = self.stored.persist(&self.messages, provider).await?;
+        output::render_redactions(&redactions);
+        Ok(())
+    }
+
     /// Process a single message and get the response
     async fn process_message(&mut self, message: String) -> Result<()> {
         self.messages.push(Message::user().with_text(&message));
         // Get the provider from the agent for description generation
-        let provider = self.agent.provider().await;
+        let provider = self.agent.read().await.provider().await;
 
         // Persist messages with provider for automatic description generation
-        session::persist_messages(&self.session_file, &self.messages, Some(provider)).await?;
+        self.persist(Some(provider)).await?;
 
         self.process_agent_response(false).await?;
         Ok(())
@@ -229,7 +249,8 @@ impl Session {
             )?;
 
         // Set up the completer with a reference to the completion cache
-        let completer = GooseCompleter::new(self.completion_cache.clone());
+        let completer =
+            GooseCompleter::new(self.completion_cache.clone()).with_agent(self.agent.clone());
         editor.set_helper(Some(completer));
 
         // Create and use a global history file in ~/.config/goose directory
@@ -270,11 +291,10 @@ impl Session {
                     self.messages.push(Message::user().with_text(&content));
 
                     // Get the provider from the agent for description generation
-                    let provider = self.agent.provider().await;
+                    let provider = self.agent.read().await.provider().await;
 
                     // Persist messages with provider for automatic description generation
-                    session::persist_messages(&self.session_file, &self.messages, Some(provider))
-                        .await?;
+                    self.persist(Some(provider)).await?;
 
                     output::show_thinking();
                     self.process_agent_response(true).await?;
@@ -348,6 +368,21 @@ impl Session {
                     println!("Goose mode set to '{}'", mode);
                     continue;
                 }
+                input::InputResult::Rewind(turns) => {
+                    save_history(&mut editor);
+
+                    let (keep, turns) = session::rewind_point(&self.messages, turns);
+                    let removed = self.messages.len() - keep;
+                    self.messages.truncate(keep);
+
+                    // No need for description update here
+                    self.persist(None).await?;
+                    println!(
+                        "Removed the last {} user turn(s), {} messages in total",
+                        turns, removed
+                    );
+                    continue;
+                }
                 input::InputResult::PromptCommand(opts) => {
                     save_history(&mut editor);
 
@@ -414,15 +449,15 @@ impl Session {
 
         // Log usage and cleanup
         if let Ok(home_dir) = choose_app_strategy(crate::APP_STRATEGY.clone()) {
-            let usage = self.agent.usage().await;
+            let usage = self.agent.read().await.usage().await;
             log_usage(
                 home_dir,
-                self.session_file.to_string_lossy().to_string(),
+                self.stored.path().to_string_lossy().to_string(),
                 usage,
             );
             println!(
                 "\nClosing session. Recorded to {}",
-                self.session_file.display()
+                self.stored.path().display()
             );
         }
         Ok(())
@@ -434,9 +469,10 @@ impl Session {
     }
 
     async fn process_agent_response(&mut self, interactive: bool) -> Result<()> {
-        let session_id = session::Identifier::Path(self.session_file.clone());
-        let mut stream = self
-            .agent
+        let session_id = self.identifier.clone();
+        let agent = self.agent.clone();
+        let agent = agent.read().await;
+        let mut stream = agent
             .reply(
                 &self.messages,
                 Some(SessionConfig {
@@ -462,17 +498,18 @@ impl Session {
 
                                 // Get confirmation from user
                                 let confirmed = cliclack::confirm(prompt).initial_value(true).interact()?;
-                                self.agent.handle_confirmation(confirmation.id.clone(), confirmed).await;
+                                agent.handle_confirmation(confirmation.id.clone(), confirmed).await;
                             }
                             // otherwise we have a model/tool to render
                             else {
                                 self.messages.push(message.clone());
 
                                 // No need to update description on assistant messages
-                                session::persist_messages(&self.session_file, &self.messages, None).await?;
+                                let redactions = self.stored.persist(&self.messages, None).await?;
 
                                 if interactive {output::hide_thinking()};
                                 output::render_message(&message, self.debug);
+                                output::render_redactions(&redactions);
                                 if interactive {output::show_thinking()};
                             }
                         }
@@ -547,7 +584,7 @@ impl Session {
             self.messages.push(response_message);
 
             // No need for description update here
-            session::persist_messages(&self.session_file, &self.messages, None).await?;
+            self.persist(None).await?;
 
             let prompt = format!(
                 "The existing call to {} was interrupted. How would you like to proceed?",
@@ -556,7 +593,7 @@ impl Session {
             self.messages.push(Message::assistant().with_text(&prompt));
 
             // No need for description update here
-            session::persist_messages(&self.session_file, &self.messages, None).await?;
+            self.persist(None).await?;
 
             output::render_message(&Message::assistant().with_text(&prompt), self.debug);
         } else {
@@ -570,8 +607,7 @@ impl Session {
                             self.messages.push(Message::assistant().with_text(prompt));
 
                             // No need for description update here
-                            session::persist_messages(&self.session_file, &self.messages, None)
-                                .await?;
+                            self.persist(None).await?;
 
                             output::render_message(
                                 &Message::assistant().with_text(prompt),
@@ -595,15 +631,15 @@ impl Session {
         Ok(())
     }
 
-    pub fn session_file(&self) -> PathBuf {
-        self.session_file.clone()
+    pub fn session_id(&self) -> &str {
+        self.stored.id()
     }
 
     /// Update the completion cache with fresh data
     /// This should be called before the interactive session starts
     pub async fn update_completion_cache(&mut self) -> Result<()> {
         // Get fresh data
-        let prompts = self.agent.list_extension_prompts().await;
+        let prompts = self.agent.read().await.list_extension_prompts().await;
 
         // Update the cache with write lock
         let mut cache = self.completion_cache.write().unwrap();
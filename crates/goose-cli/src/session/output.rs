@@ -2,6 +2,8 @@ use bat::WrappingMode;
 use console::style;
 use goose::config::Config;
 use goose::message::{Message, MessageContent, ToolRequest, ToolResponse};
+use goose::redaction::Redactions;
+use goose::session::export::{is_for_user, split_tool_name};
 use mcp_core::prompt::PromptArgument;
 use mcp_core::tool::ToolCall;
 use serde_json::Value;
@@ -107,6 +109,10 @@ pub fn render_message(message: &Message, debug: bool) {
             MessageContent::Image(image) => {
                 println!("Image: [data: {}, type: {}]", image.data, image.mime_type);
             }
+            MessageContent::Audio(audio) => {
+                println!("Audio: [type: {}]", audio.mime_type);
+            }
+            MessageContent::ResourceLink(link) => render_resource_link(link),
             MessageContent::Thinking(thinking) => {
                 if std::env::var("GOOSE_CLI_SHOW_THINKING").is_ok() {
                     println!("\n{}", style("Thinking:").dim().italic());
@@ -143,10 +149,8 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
     match &resp.tool_result {
         Ok(contents) => {
             for content in contents {
-                if let Some(audience) = content.audience() {
-                    if !audience.contains(&mcp_core::role::Role::User) {
-                        continue;
-                    }
+                if !is_for_user(content) {
+                    continue;
                 }
 
                 let min_priority = config
@@ -164,8 +168,17 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
 
                 if debug {
                     println!("{:#?}", content);
-                } else if let mcp_core::content::Content::Text(text) = content {
-                    print_markdown(&text.text, theme);
+                } else {
+                    match content {
+                        mcp_core::content::Content::Text(text) => print_markdown(&text.text, theme),
+                        mcp_core::content::Content::Audio(audio) => {
+                            println!("Audio: [type: {}]", audio.mime_type)
+                        }
+                        mcp_core::content::Content::ResourceLink(link) => {
+                            render_resource_link(link)
+                        }
+                        _ => {}
+                    }
                 }
             }
         }
@@ -173,10 +186,29 @@ fn render_tool_response(resp: &ToolResponse, theme: Theme, debug: bool) {
     }
 }
 
+fn render_resource_link(link: &mcp_core::content::ResourceLink) {
+    println!("{} {}", style(&link.name).cyan(), style(&link.uri).dim());
+    if let Some(description) = &link.description {
+        println!("  {}", style(description).dim());
+    }
+}
+
 pub fn render_error(message: &str) {
     println!("\n  {} {}\n", style("error:").red().bold(), message);
 }
 
+pub fn render_redactions(redactions: &Redactions) {
+    if redactions.is_empty() {
+        return;
+    }
+    println!(
+        "  {} {} secret(s) before saving: {}",
+        style("redacted").yellow(),
+        redactions.total(),
+        style(redactions).dim()
+    );
+}
+
 pub fn render_prompts(prompts: &HashMap<String, Vec<String>>) {
     println!();
     for (extension, prompts) in prompts {
@@ -314,18 +346,11 @@ fn render_default_request(call: &ToolCall, debug: bool) {
 // Helper functions
 
 fn print_tool_header(call: &ToolCall) {
-    let parts: Vec<_> = call.name.rsplit("__").collect();
+    let (extension, tool) = split_tool_name(&call.name);
     let tool_header = format!(
         "─── {} | {} ──────────────────────────",
-        style(parts.first().unwrap_or(&"unknown")),
-        style(
-            parts
-                .split_first()
-                .map(|(_, s)| s.iter().rev().copied().collect::<Vec<_>>().join("__"))
-                .unwrap_or_else(|| "unknown".to_string())
-        )
-        .magenta()
-        .dim(),
+        style(tool),
+        style(extension).magenta().dim(),
     );
     println!();
     println!("{}", tool_header);
@@ -13,11 +13,12 @@ use mcp_core::{
     prompt::Prompt,
     protocol::ServerCapabilities,
     resource::Resource,
-    tool::Tool,
+    tool::ToolOutput,
     Content,
 };
+use mcp_macros::router;
 use mcp_server::router::CapabilitiesBuilder;
-use mcp_server::Router;
+use mcp_server::{Peer, Router};
 
 mod docx_tool;
 mod pdf_tool;
@@ -27,16 +28,101 @@ mod xlsx_tool;
 mod platform;
 use platform::{create_system_automation, SystemAutomation};
 
+/// The computer control tool description, which differs per OS
+fn computer_control_description() -> &'static str {
+    match std::env::consts::OS {
+        "windows" => indoc! {r#"
+            Control the computer using Windows system automation.
+
+            Features available:
+            - PowerShell automation for system control
+            - UI automation through PowerShell
+            - File and system management
+            - Windows-specific features and settings
+
+            Can be combined with screenshot tool for visual task assistance.
+        "#},
+        "macos" => indoc! {r#"
+            Control the computer using AppleScript (macOS only). Automate applications and system features.
+
+            Key capabilities:
+            - Control Applications: Launch, quit, manage apps (Mail, Safari, iTunes, etc)
+                - Interact with app-specific feature: (e.g, edit documents, process photos)
+                - Perform tasks in third-party apps that support AppleScript
+            - UI Automation: Simulate user interactions like, clicking buttons, select menus, type text, filling out forms
+            - System Control: Manage settings (volume, brightness, wifi), shutdown/restart, monitor events
+            - Web & Email: Open URLs, web automation, send/organize emails, handle attachments
+            - Media: Manage music libraries, photo collections, playlists
+            - File Operations: Organize files/folders
+            - Integration: Calendar, reminders, messages
+            - Data: Interact with spreadsheets and documents
+
+            Can be combined with screenshot tool for visual task assistance.
+        "#},
+        _ => indoc! {r#"
+            Control the computer using Linux system automation.
+
+            Features available:
+            - Shell scripting for system control
+            - X11/Wayland window management
+            - D-Bus for system services
+            - File and system management
+            - Desktop environment control (GNOME, KDE, etc.)
+            - Process management and monitoring
+            - System settings and configurations
+
+            Can be combined with screenshot tool for visual task assistance.
+        "#},
+    }
+}
+
+/// The result of an xlsx operation other than get_range, as text and as the `message` of
+/// the structured content the output schema of the tool asks for
+fn xlsx_message(text: String) -> ToolOutput {
+    ToolOutput {
+        content: vec![Content::text(text.clone())],
+        structured_content: Some(json!({ "message": text })),
+    }
+}
+
+/// The automation script tool description, which differs per OS
+fn quick_script_description() -> &'static str {
+    match std::env::consts::OS {
+        "windows" => indoc! {r#"
+            Create and run small PowerShell or Batch scripts for automation tasks.
+            PowerShell is recommended for most tasks.
+
+            The script is saved to a temporary file and executed.
+            Some examples:
+            - Sort unique lines: Get-Content file.txt | Sort-Object -Unique
+            - Extract CSV column: Import-Csv file.csv | Select-Object -ExpandProperty Column2
+            - Find text: Select-String -Pattern "pattern" -Path file.txt
+        "#},
+        _ => indoc! {r#"
+            Create and run small scripts for automation tasks.
+            Supports Shell and Ruby (on macOS).
+
+            The script is saved to a temporary file and executed.
+            Consider using shell script (bash) for most simple tasks first.
+            Ruby is useful for text processing or when you need more sophisticated scripting capabilities.
+            Some examples of shell:
+                - create a sorted list of unique lines: sort file.txt | uniq
+                - extract 2nd column in csv: awk -F "," '{ print $2}'
+                - pattern matching: grep pattern file.txt
+        "#},
+    }
+}
+
 /// An extension designed for non-developers to help them with common tasks like
 /// web scraping, data processing, and automation.
 #[derive(Clone)]
 pub struct ComputerControllerRouter {
-    tools: Vec<Tool>,
     cache_dir: PathBuf,
     active_resources: Arc<Mutex<HashMap<String, Resource>>>,
     http_client: Client,
     instructions: String,
     system_automation: Arc<Box<dyn SystemAutomation + Send + Sync>>,
+    peer: Option<Peer>,
 }
 
 impl Default for ComputerControllerRouter {
@@ -47,422 +133,6 @@ impl Default for ComputerControllerRouter {
 
 impl ComputerControllerRouter {
     pub fn new() -> Self {
-        // Create tools for the system
-        let web_search_tool = Tool::new(
-            "web_search",
-            indoc! {r#"
-                Search the web for a single word (proper noun ideally) using DuckDuckGo's API. Returns results in JSON format.
-                The results are cached locally for future reference.
-                Be sparing as there is a limited number of api calls allowed.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["query"],
-                "properties": {
-                    "query": {
-                        "type": "string",
-                        "description": "A single word to search for, a topic, propernoun, brand name that you may not know about"
-                    }
-                }
-            }),
-        );
-
-        let web_scrape_tool = Tool::new(
-            "web_scrape",
-            indoc! {r#"
-                Fetch and save content from a web page. The content can be saved as:
-                - text (for HTML pages)
-                - json (for API responses)
-                - binary (for images and other files)
-
-                The content is cached locally and can be accessed later using the cache_path
-                returned in the response.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["url"],
-                "properties": {
-                    "url": {
-                        "type": "string",
-                        "description": "The URL to fetch content from"
-                    },
-                    "save_as": {
-                        "type": "string",
-                        "enum": ["text", "json", "binary"],
-                        "default": "text",
-                        "description": "How to interpret and save the content"
-                    }
-                }
-            }),
-        );
-
-        let computer_control_desc = match std::env::consts::OS {
-            "windows" => indoc! {r#"
-                Control the computer using Windows system automation.
-
-                Features available:
-                - PowerShell automation for system control
-                - UI automation through PowerShell
-                - File and system management
-                - Windows-specific features and settings
-
-                Can be combined with screenshot tool for visual task assistance.
-            "#},
-            "macos" => indoc! {r#"
-                Control the computer using AppleScript (macOS only). Automate applications and system features.
-
-                Key capabilities:
-                - Control Applications: Launch, quit, manage apps (Mail, Safari, iTunes, etc)
-                    - Interact with app-specific feature: (e.g, edit documents, process photos)
-                    - Perform tasks in third-party apps that support AppleScript
-                - UI Automation: Simulate user interactions like, clicking buttons, select menus, type text, filling out forms
-                - System Control: Manage settings (volume, brightness, wifi), shutdown/restart, monitor events
-                - Web & Email: Open URLs, web automation, send/organize emails, handle attachments
-                - Media: Manage music libraries, photo collections, playlists
-                - File Operations: Organize files/folders
-                - Integration: Calendar, reminders, messages
-                - Data: Interact with spreadsheets and documents
-
-                Can be combined with screenshot tool for visual task assistance.
-            "#},
-            _ => indoc! {r#"
-                Control the computer using Linux system automation.
-
-                Features available:
-                - Shell scripting for system control
-                - X11/Wayland window management
-                - D-Bus for system services
-                - File and system management
-                - Desktop environment control (GNOME, KDE, etc.)
-                - Process management and monitoring
-                - System settings and configurations
-
-                Can be combined with screenshot tool for visual task assistance.
-            "#},
-        };
-
-        let computer_control_tool = Tool::new(
-            "computer_control",
-            computer_control_desc.to_string(),
-            json!({
-                "type": "object",
-                "required": ["script"],
-                "properties": {
-                    "script": {
-                        "type": "string",
-                        "description": "The automation script content (PowerShell for Windows, AppleScript for macOS)"
-                    },
-                    "save_output": {
-                        "type": "boolean",
-                        "default": false,
-                        "description": "Whether to save the script output to a file"
-                    }
-                }
-            }),
-        );
-
-        let quick_script_desc = match std::env::consts::OS {
-            "windows" => indoc! {r#"
-                Create and run small PowerShell or Batch scripts for automation tasks.
-                PowerShell is recommended for most tasks.
-
-                The script is saved to a temporary file and executed.
-                Some examples:
-                - Sort unique lines: Get-Content file.txt | Sort-Object -Unique
-                - Extract CSV column: Import-Csv file.csv | Select-Object -ExpandProperty Column2
-                - Find text: Select-String -Pattern "pattern" -Path file.txt
-            "#},
-            _ => indoc! {r#"
-                Create and run small scripts for automation tasks.
-                Supports Shell and Ruby (on macOS).
-
-                The script is saved to a temporary file and executed.
-                Consider using shell script (bash) for most simple tasks first.
-                Ruby is useful for text processing or when you need more sophisticated scripting capabilities.
-                Some examples of shell:
-                    - create a sorted list of unique lines: sort file.txt | uniq
-                    - extract 2nd column in csv: awk -F "," '{ print $2}'
-                    - pattern matching: grep pattern file.txt
-            "#},
-        };
-
-        let quick_script_tool = Tool::new(
-            "automation_script",
-            quick_script_desc.to_string(),
-            json!({
-                "type": "object",
-                "required": ["language", "script"],
-                "properties": {
-                    "language": {
-                        "type": "string",
-                        "enum": ["shell", "ruby", "powershell", "batch"],
-                        "description": "The scripting language to use"
-                    },
-                    "script": {
-                        "type": "string",
-                        "description": "The script content"
-                    },
-                    "save_output": {
-                        "type": "boolean",
-                        "default": false,
-                        "description": "Whether to save the script output to a file"
-                    }
-                }
-            }),
-        );
-
-        let cache_tool = Tool::new(
-            "cache",
-            indoc! {r#"
-                Manage cached files and data:
-                - list: List all cached files
-                - view: View content of a cached file
-                - delete: Delete a cached file
-                - clear: Clear all cached files
-            "#},
-            json!({
-                "type": "object",
-                "required": ["command"],
-                "properties": {
-                    "command": {
-                        "type": "string",
-                        "enum": ["list", "view", "delete", "clear"],
-                        "description": "The command to perform"
-                    },
-                    "path": {
-                        "type": "string",
-                        "description": "Path to the cached file for view/delete commands"
-                    }
-                }
-            }),
-        );
-
-        let pdf_tool = Tool::new(
-            "pdf_tool",
-            indoc! {r#"
-                Process PDF files to extract text and images.
-                Supports operations:
-                - extract_text: Extract all text content from the PDF
-                - extract_images: Extract and save embedded images to PNG files
-
-                Use this when there is a .pdf file or files that need to be processed.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["path", "operation"],
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "Path to the PDF file"
-                    },
-                    "operation": {
-                        "type": "string",
-                        "enum": ["extract_text", "extract_images"],
-                        "description": "Operation to perform on the PDF"
-                    }
-                }
-            }),
-        );
-
-        let docx_tool = Tool::new(
-            "docx_tool",
-            indoc! {r#"
-                Process DOCX files to extract text and create/update documents.
-                Supports operations:
-                - extract_text: Extract all text content and structure (headings, TOC) from the DOCX
-                - update_doc: Create a new DOCX or update existing one with provided content
-                  Modes:
-                  - append: Add content to end of document (default)
-                  - replace: Replace specific text with new content
-                  - structured: Add content with specific heading level and styling
-                  - add_image: Add an image to the document (with optional caption)
-
-                Use this when there is a .docx file that needs to be processed or created.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["path", "operation"],
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "Path to the DOCX file"
-                    },
-                    "operation": {
-                        "type": "string",
-                        "enum": ["extract_text", "update_doc"],
-                        "description": "Operation to perform on the DOCX"
-                    },
-                    "content": {
-                        "type": "string",
-                        "description": "Content to write (required for update_doc operation)"
-                    },
-                    "params": {
-                        "type": "object",
-                        "description": "Additional parameters for update_doc operation",
-                        "properties": {
-                            "mode": {
-                                "type": "string",
-                                "enum": ["append", "replace", "structured", "add_image"],
-                                "description": "Update mode (default: append)"
-                            },
-                            "old_text": {
-                                "type": "string",
-                                "description": "Text to replace (required for replace mode)"
-                            },
-                            "level": {
-                                "type": "string",
-                                "description": "Heading level for structured mode (e.g., 'Heading1', 'Heading2')"
-                            },
-                            "image_path": {
-                                "type": "string",
-                                "description": "Path to the image file (required for add_image mode)"
-                            },
-                            "width": {
-                                "type": "integer",
-                                "description": "Image width in pixels (optional)"
-                            },
-                            "height": {
-                                "type": "integer",
-                                "description": "Image height in pixels (optional)"
-                            },
-                            "style": {
-                                "type": "object",
-                                "description": "Styling options for the text",
-                                "properties": {
-                                    "bold": {
-                                        "type": "boolean",
-                                        "description": "Make text bold"
-                                    },
-                                    "italic": {
-                                        "type": "boolean",
-                                        "description": "Make text italic"
-                                    },
-                                    "underline": {
-                                        "type": "boolean",
-                                        "description": "Make text underlined"
-                                    },
-                                    "size": {
-                                        "type": "integer",
-                                        "description": "Font size in points"
-                                    },
-                                    "color": {
-                                        "type": "string",
-                                        "description": "Text color in hex format (e.g., 'FF0000' for red)"
-                                    },
-                                    "alignment": {
-                                        "type": "string",
-                                        "enum": ["left", "center", "right", "justified"],
-                                        "description": "Text alignment"
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }),
-        );
-
-        let make_presentation_tool = Tool::new(
-            "make_presentation",
-            indoc! {r#"
-                Create and manage HTML presentations with a simple, modern design.
-                Operations:
-                - create: Create new presentation with template
-                - add_slide: Add a new slide with content
-
-                Open in a browser (using a command) to show the user: open <path> 
-
-                For advanced edits, use developer tools to modify the HTML directly.
-                A template slide is included in comments for reference.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["path", "operation"],
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "Path to the presentation file"
-                    },
-                    "operation": {
-                        "type": "string",
-                        "enum": ["create", "add_slide"],
-                        "description": "Operation to perform"
-                    },
-                    "params": {
-                        "type": "object",
-                        "description": "Parameters for add_slide operation",
-                        "properties": {
-                            "content": {
-                                "type": "string",
-                                "description": "Content for the new slide"
-                            }
-                        }
-                    }
-                }
-            }),
-        );
-
-        let xlsx_tool = Tool::new(
-            "xlsx_tool",
-            indoc! {r#"
-                Process Excel (XLSX) files to read and manipulate spreadsheet data.
-                Supports operations:
-                - list_worksheets: List all worksheets in the workbook (returns name, index, column_count, row_count)
-                - get_columns: Get column names from a worksheet (returns values from the first row)
-                - get_range: Get values and formulas from a cell range (e.g., "A1:C10") (returns a 2D array organized as [row][column])
-                - find_text: Search for text in a worksheet (returns a list of (row, column) coordinates)
-                - update_cell: Update a single cell's value (returns confirmation message)
-                - get_cell: Get value and formula from a specific cell (returns both value and formula if present)
-                - save: Save changes back to the file (returns confirmation message)
-
-                Use this when working with Excel spreadsheets to analyze or modify data.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["path", "operation"],
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "Path to the XLSX file"
-                    },
-                    "operation": {
-                        "type": "string",
-                        "enum": ["list_worksheets", "get_columns", "get_range", "find_text", "update_cell", "get_cell", "save"],
-                        "description": "Operation to perform on the XLSX file"
-                    },
-                    "worksheet": {
-                        "type": "string",
-                        "description": "Worksheet name (if not provided, uses first worksheet)"
-                    },
-                    "range": {
-                        "type": "string",
-                        "description": "Cell range in A1 notation (e.g., 'A1:C10') for get_range operation"
-                    },
-                    "search_text": {
-                        "type": "string",
-                        "description": "Text to search for in find_text operation"
-                    },
-                    "case_sensitive": {
-                        "type": "boolean",
-                        "default": false,
-                        "description": "Whether search should be case-sensitive"
-                    },
-                    "row": {
-                        "type": "integer",
-                        "description": "Row number for update_cell and get_cell operations"
-                    },
-                    "col": {
-                        "type": "integer",
-                        "description": "Column number for update_cell and get_cell operations"
-                    },
-                    "value": {
-                        "type": "string",
-                        "description": "New value for update_cell operation"
-                    }
-                }
-            }),
-        );
-
         // choose_app_strategy().cache_dir()
         // - macOS/Linux: ~/.cache/goose/computer_controller/
         // - Windows:     ~\AppData\Local\Block\goose\cache\computer_controller\
@@ -584,22 +254,34 @@ impl ComputerControllerRouter {
         };
 
         Self {
-            tools: vec![
-                web_search_tool,
-                web_scrape_tool,
-                quick_script_tool,
-                computer_control_tool,
-                cache_tool,
-                pdf_tool,
-                docx_tool,
-                xlsx_tool,
-                make_presentation_tool,
-            ],
             cache_dir,
             active_resources: Arc::new(Mutex::new(HashMap::new())),
             http_client: Client::builder().user_agent("Goose/1.0").build().unwrap(),
             instructions: instructions.clone(),
             system_automation,
+            peer: None,
+        }
+    }
+
+    /// Notify the connected client through `peer` when cached resources change
+    pub fn with_peer(mut self, peer: Peer) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+
+    // Helper function to tell the client the resources changed, updated uris are only
+    // reported to clients subscribed to them
+    fn notify_resources_changed(&self, updated: &[String]) {
+        let Some(peer) = &self.peer else {
+            return;
+        };
+
+        let mut results = vec![peer.notify_resource_list_changed()];
+        results.extend(updated.iter().map(|uri| peer.notify_resource_updated(uri)));
+        for result in results {
+            if let Err(e) = result {
+                tracing::debug!("Failed to notify resource change: {}", e);
+            }
         }
     }
 
@@ -636,21 +318,49 @@ impl ComputerControllerRouter {
         )
         .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
 
-        self.active_resources.lock().unwrap().insert(uri, resource);
+        self.active_resources
+            .lock()
+            .unwrap()
+            .insert(uri.clone(), resource);
+        self.notify_resources_changed(&[uri]);
         Ok(())
     }
+}
 
-    // Implement web_search tool functionality
-    async fn web_search(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let query = params
-            .get("query")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidParameters("Missing 'query' parameter".into()))?;
+#[router]
+impl Router for ComputerControllerRouter {
+    fn name(&self) -> String {
+        "ComputerControllerExtension".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
 
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_resources(true, true)
+            .build()
+    }
+
+    // Implement web_search tool functionality
+    #[tool(
+        description = indoc! {r#"
+            Search the web for a single word (proper noun ideally) using DuckDuckGo's API. Returns results in JSON format.
+            The results are cached locally for future reference.
+            Be sparing as there is a limited number of api calls allowed.
+        "#},
+        params(
+            query = "A single word to search for, a topic, propernoun, brand name that you may not know about"
+        ),
+        read_only_hint = true
+    )]
+    async fn web_search(&self, query: String) -> Result<Vec<Content>, ToolError> {
         // Create the DuckDuckGo API URL
         let url = format!(
             "https://api.duckduckgo.com/?q={}&format=json&pretty=1",
-            urlencoding::encode(query)
+            urlencoding::encode(&query)
         );
 
         // Fetch the results
@@ -685,6 +395,33 @@ impl ComputerControllerRouter {
         ))])
     }
 
+    #[tool(
+        description = indoc! {r#"
+            Fetch and save content from a web page. The content can be saved as:
+            - text (for HTML pages)
+            - json (for API responses)
+            - binary (for images and other files)
+
+            The content is cached locally and can be accessed later using the cache_path
+            returned in the response.
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["url"],
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to fetch content from"
+                },
+                "save_as": {
+                    "type": "string",
+                    "enum": ["text", "json", "binary"],
+                    "default": "text",
+                    "description": "How to interpret and save the content"
+                }
+            }
+        })
+    )]
     async fn web_scrape(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let url = params
             .get("url")
@@ -758,6 +495,31 @@ impl ComputerControllerRouter {
     }
 
     // Implement quick_script tool functionality
+    #[tool(
+        name = "automation_script",
+        description = quick_script_description(),
+        schema = json!({
+            "type": "object",
+            "required": ["language", "script"],
+            "properties": {
+                "language": {
+                    "type": "string",
+                    "enum": ["shell", "ruby", "powershell", "batch"],
+                    "description": "The scripting language to use"
+                },
+                "script": {
+                    "type": "string",
+                    "description": "The script content"
+                },
+                "save_output": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Whether to save the script output to a file"
+                }
+            }
+        }),
+        destructive_hint = true
+    )]
     async fn quick_script(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let language = params
             .get("language")
@@ -854,6 +616,25 @@ impl ComputerControllerRouter {
     }
 
     // Implement computer control functionality
+    #[tool(
+        description = computer_control_description(),
+        schema = json!({
+            "type": "object",
+            "required": ["script"],
+            "properties": {
+                "script": {
+                    "type": "string",
+                    "description": "The automation script content (PowerShell for Windows, AppleScript for macOS)"
+                },
+                "save_output": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Whether to save the script output to a file"
+                }
+            }
+        }),
+        destructive_hint = true
+    )]
     async fn computer_control(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let script = params
             .get("script")
@@ -887,7 +668,96 @@ impl ComputerControllerRouter {
         Ok(vec![Content::text(result)])
     }
 
-    async fn xlsx_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+    #[tool(
+        description = indoc! {r#"
+            Process Excel (XLSX) files to read and manipulate spreadsheet data.
+            Supports operations:
+            - list_worksheets: List all worksheets in the workbook (returns name, index, column_count, row_count)
+            - get_columns: Get column names from a worksheet (returns values from the first row)
+            - get_range: Get values and formulas from a cell range (e.g., "A1:C10") (returns the range as structured content, with values organized as [row][column])
+            - find_text: Search for text in a worksheet (returns a list of (row, column) coordinates)
+            - update_cell: Update a single cell's value (returns confirmation message)
+            - get_cell: Get value and formula from a specific cell (returns both value and formula if present)
+            - save: Save changes back to the file (returns confirmation message)
+
+            Use this when working with Excel spreadsheets to analyze or modify data.
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["path", "operation"],
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the XLSX file"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["list_worksheets", "get_columns", "get_range", "find_text", "update_cell", "get_cell", "save"],
+                    "description": "Operation to perform on the XLSX file"
+                },
+                "worksheet": {
+                    "type": "string",
+                    "description": "Worksheet name (if not provided, uses first worksheet)"
+                },
+                "range": {
+                    "type": "string",
+                    "description": "Cell range in A1 notation (e.g., 'A1:C10') for get_range operation"
+                },
+                "search_text": {
+                    "type": "string",
+                    "description": "Text to search for in find_text operation"
+                },
+                "case_sensitive": {
+                    "type": "boolean",
+                    "default": false,
+                    "description": "Whether search should be case-sensitive"
+                },
+                "row": {
+                    "type": "integer",
+                    "description": "Row number for update_cell and get_cell operations"
+                },
+                "col": {
+                    "type": "integer",
+                    "description": "Column number for update_cell and get_cell operations"
+                },
+                "value": {
+                    "type": "string",
+                    "description": "New value for update_cell operation"
+                }
+            }
+        }),
+        output_schema = json!({
+            "type": "object",
+            "properties": {
+                "message": {
+                    "type": "string",
+                    "description": "The result of operations other than get_range, as in the text content"
+                },
+                "start_row": {"type": "integer"},
+                "end_row": {"type": "integer"},
+                "start_col": {"type": "integer"},
+                "end_col": {"type": "integer"},
+                "values": {
+                    "type": "array",
+                    "description": "The cells of get_range as [row][column], each with its value and formula",
+                    "items": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["value"],
+                            "properties": {
+                                "value": {"type": "string"},
+                                "formula": {"type": ["string", "null"]}
+                            }
+                        }
+                    }
+                }
+            }
+        }),
+        destructive_hint = true,
+        open_world_hint = false
+    )]
+    async fn xlsx_tool(&self, params: Value) -> Result<ToolOutput, ToolError> {
         let path = params
             .get("path")
             .and_then(|v| v.as_str())
@@ -905,7 +775,7 @@ impl ComputerControllerRouter {
                 let worksheets = xlsx
                     .list_worksheets()
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!("{:#?}", worksheets))])
+                Ok(xlsx_message(format!("{:#?}", worksheets)))
             }
             "get_columns" => {
                 let xlsx = xlsx_tool::XlsxTool::new(path)
@@ -921,7 +791,7 @@ impl ComputerControllerRouter {
                 let columns = xlsx
                     .get_column_names(worksheet)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!("{:#?}", columns))])
+                Ok(xlsx_message(format!("{:#?}", columns)))
             }
             "get_range" => {
                 let range = params
@@ -944,7 +814,9 @@ impl ComputerControllerRouter {
                 let range_data = xlsx
                     .get_range(worksheet, range)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!("{:#?}", range_data))])
+                let range_data = serde_json::to_value(range_data)
+                    .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+                Ok(ToolOutput::structured(range_data))
             }
             "find_text" => {
                 let search_text = params
@@ -972,10 +844,7 @@ impl ComputerControllerRouter {
                 let matches = xlsx
                     .find_in_worksheet(worksheet, search_text, case_sensitive)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!(
-                    "Found matches at: {:#?}",
-                    matches
-                ))])
+                Ok(xlsx_message(format!("Found matches at: {:#?}", matches)))
             }
             "update_cell" => {
                 let row = params.get("row").and_then(|v| v.as_u64()).ok_or_else(|| {
@@ -1004,17 +873,17 @@ impl ComputerControllerRouter {
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
                 xlsx.save(path)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!(
+                Ok(xlsx_message(format!(
                     "Updated cell ({}, {}) to '{}' in worksheet '{}'",
                     row, col, value, worksheet_name
-                ))])
+                )))
             }
             "save" => {
                 let xlsx = xlsx_tool::XlsxTool::new(path)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
                 xlsx.save(path)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text("File saved successfully.")])
+                Ok(xlsx_message("File saved successfully.".to_string()))
             }
             "get_cell" => {
                 let row = params.get("row").and_then(|v| v.as_u64()).ok_or_else(|| {
@@ -1038,7 +907,7 @@ impl ComputerControllerRouter {
                 let cell_value = xlsx
                     .get_cell_value(worksheet, row as u32, col as u32)
                     .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-                Ok(vec![Content::text(format!("{:#?}", cell_value))])
+                Ok(xlsx_message(format!("{:#?}", cell_value)))
             }
             _ => Err(ToolError::InvalidParameters(format!(
                 "Invalid operation: {}",
@@ -1048,6 +917,104 @@ impl ComputerControllerRouter {
     }
 
     // Implement cache tool functionality
+    #[tool(
+        description = indoc! {r#"
+            Process DOCX files to extract text and create/update documents.
+            Supports operations:
+            - extract_text: Extract all text content and structure (headings, TOC) from the DOCX
+            - update_doc: Create a new DOCX or update existing one with provided content
+              Modes:
+              - append: Add content to end of document (default)
+              - replace: Replace specific text with new content
+              - structured: Add content with specific heading level and styling
+              - add_image: Add an image to the document (with optional caption)
+
+            Use this when there is a .docx file that needs to be processed or created.
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["path", "operation"],
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the DOCX file"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["extract_text", "update_doc"],
+                    "description": "Operation to perform on the DOCX"
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Content to write (required for update_doc operation)"
+                },
+                "params": {
+                    "type": "object",
+                    "description": "Additional parameters for update_doc operation",
+                    "properties": {
+                        "mode": {
+                            "type": "string",
+                            "enum": ["append", "replace", "structured", "add_image"],
+                            "description": "Update mode (default: append)"
+                        },
+                        "old_text": {
+                            "type": "string",
+                            "description": "Text to replace (required for replace mode)"
+                        },
+                        "level": {
+                            "type": "string",
+                            "description": "Heading level for structured mode (e.g., 'Heading1', 'Heading2')"
+                        },
+                        "image_path": {
+                            "type": "string",
+                            "description": "Path to the image file (required for add_image mode)"
+                        },
+                        "width": {
+                            "type": "integer",
+                            "description": "Image width in pixels (optional)"
+                        },
+                        "height": {
+                            "type": "integer",
+                            "description": "Image height in pixels (optional)"
+                        },
+                        "style": {
+                            "type": "object",
+                            "description": "Styling options for the text",
+                            "properties": {
+                                "bold": {
+                                    "type": "boolean",
+                                    "description": "Make text bold"
+                                },
+                                "italic": {
+                                    "type": "boolean",
+                                    "description": "Make text italic"
+                                },
+                                "underline": {
+                                    "type": "boolean",
+                                    "description": "Make text underlined"
+                                },
+                                "size": {
+                                    "type": "integer",
+                                    "description": "Font size in points"
+                                },
+                                "color": {
+                                    "type": "string",
+                                    "description": "Text color in hex format (e.g., 'FF0000' for red)"
+                                },
+                                "alignment": {
+                                    "type": "string",
+                                    "enum": ["left", "center", "right", "justified"],
+                                    "description": "Text alignment"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }),
+        destructive_hint = true,
+        open_world_hint = false
+    )]
     async fn docx_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let path = params
             .get("path")
@@ -1068,6 +1035,33 @@ impl ComputerControllerRouter {
         .await
     }
 
+    #[tool(
+        description = indoc! {r#"
+            Process PDF files to extract text and images.
+            Supports operations:
+            - extract_text: Extract all text content from the PDF
+            - extract_images: Extract and save embedded images to PNG files
+
+            Use this when there is a .pdf file or files that need to be processed.
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["path", "operation"],
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the PDF file"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["extract_text", "extract_images"],
+                    "description": "Operation to perform on the PDF"
+                }
+            }
+        }),
+        destructive_hint = false,
+        open_world_hint = false
+    )]
     async fn pdf_tool(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let path = params
             .get("path")
@@ -1082,6 +1076,32 @@ impl ComputerControllerRouter {
         crate::computercontroller::pdf_tool::pdf_tool(path, operation, &self.cache_dir).await
     }
 
+    #[tool(
+        description = indoc! {r#"
+            Manage cached files and data:
+            - list: List all cached files
+            - view: View content of a cached file
+            - delete: Delete a cached file
+            - clear: Clear all cached files
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["command"],
+            "properties": {
+                "command": {
+                    "type": "string",
+                    "enum": ["list", "view", "delete", "clear"],
+                    "description": "The command to perform"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Path to the cached file for view/delete commands"
+                }
+            }
+        }),
+        destructive_hint = true,
+        open_world_hint = false
+    )]
     async fn cache(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let command = params
             .get("command")
@@ -1130,10 +1150,14 @@ impl ComputerControllerRouter {
 
                 // Remove from active resources if present
                 if let Ok(url) = Url::from_file_path(path) {
-                    self.active_resources
+                    let removed = self
+                        .active_resources
                         .lock()
                         .unwrap()
                         .remove(&url.to_string());
+                    if removed.is_some() {
+                        self.notify_resources_changed(&[url.to_string()]);
+                    }
                 }
 
                 Ok(vec![Content::text(format!("Deleted file: {}", path))])
@@ -1147,7 +1171,16 @@ impl ComputerControllerRouter {
                 })?;
 
                 // Clear active resources
-                self.active_resources.lock().unwrap().clear();
+                let removed: Vec<String> = self
+                    .active_resources
+                    .lock()
+                    .unwrap()
+                    .drain()
+                    .map(|(uri, _)| uri)
+                    .collect();
+                if !removed.is_empty() {
+                    self.notify_resources_changed(&removed);
+                }
 
                 Ok(vec![Content::text("Cache cleared successfully.")])
             }
@@ -1157,66 +1190,59 @@ impl ComputerControllerRouter {
             )))
         }
     }
-}
-
-impl Router for ComputerControllerRouter {
-    fn name(&self) -> String {
-        "ComputerControllerExtension".to_string()
-    }
-
-    fn instructions(&self) -> String {
-        self.instructions.clone()
-    }
 
-    fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new()
-            .with_tools(false)
-            .with_resources(false, false)
-            .build()
-    }
-
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
-
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-        Box::pin(async move {
-            match tool_name.as_str() {
-                "web_search" => this.web_search(arguments).await,
-                "web_scrape" => this.web_scrape(arguments).await,
-                "automation_script" => this.quick_script(arguments).await,
-                "computer_control" => this.computer_control(arguments).await,
-                "cache" => this.cache(arguments).await,
-                "pdf_tool" => this.pdf_tool(arguments).await,
-                "docx_tool" => this.docx_tool(arguments).await,
-                "xlsx_tool" => this.xlsx_tool(arguments).await,
-                "make_presentation" => {
-                    let path = arguments
-                        .get("path")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters("Missing 'path' parameter".into())
-                        })?;
-
-                    let operation = arguments
-                        .get("operation")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters("Missing 'operation' parameter".into())
-                        })?;
-
-                    presentation_tool::make_presentation(path, operation, arguments.get("params"))
-                        .await
+    #[tool(
+        description = indoc! {r#"
+            Create and manage HTML presentations with a simple, modern design.
+            Operations:
+            - create: Create new presentation with template
+            - add_slide: Add a new slide with content
+
+            Open in a browser (using a command) to show the user: open <path> 
+
+            For advanced edits, use developer tools to modify the HTML directly.
+            A template slide is included in comments for reference.
+        "#},
+        schema = json!({
+            "type": "object",
+            "required": ["path", "operation"],
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Path to the presentation file"
+                },
+                "operation": {
+                    "type": "string",
+                    "enum": ["create", "add_slide"],
+                    "description": "Operation to perform"
+                },
+                "params": {
+                    "type": "object",
+                    "description": "Parameters for add_slide operation",
+                    "properties": {
+                        "content": {
+                            "type": "string",
+                            "description": "Content for the new slide"
+                        }
+                    }
                 }
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
             }
-        })
+        }),
+        destructive_hint = true,
+        open_world_hint = false
+    )]
+    async fn make_presentation(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let path = params
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
+
+        let operation = params
+            .get("operation")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidParameters("Missing 'operation' parameter".into()))?;
+
+        presentation_tool::make_presentation(path, operation, params.get("params")).await
     }
 
     fn list_resources(&self) -> Vec<Resource> {
@@ -1271,6 +1297,10 @@ impl Router for ComputerControllerRouter {
         })
     }
 
+    fn peer(&self) -> Option<Peer> {
+        self.peer.clone()
+    }
+
     fn list_prompts(&self) -> Vec<Prompt> {
         vec![]
     }
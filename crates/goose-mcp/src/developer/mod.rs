@@ -5,7 +5,8 @@ use anyhow::Result;
 use base64::Engine;
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
-use serde_json::{json, Value};
+use schemars::JsonSchema;
+use serde::Deserialize;
 use std::{
     collections::HashMap,
     future::Future,
@@ -22,10 +23,10 @@ use mcp_core::{
     handler::{PromptError, ResourceError, ToolError},
     protocol::ServerCapabilities,
     resource::Resource,
-    tool::Tool,
 };
+use mcp_macros::router;
 use mcp_server::router::CapabilitiesBuilder;
-use mcp_server::Router;
+use mcp_server::{Peer, Router};
 
 use mcp_core::content::Content;
 use mcp_core::role::Role;
@@ -87,6 +88,63 @@ pub fn load_prompt_files() -> HashMap<String, Prompt> {
     prompts
 }
 
+/// The shell tool description, which differs per OS
+fn shell_tool_description() -> &'static str {
+    match std::env::consts::OS {
+        "windows" => indoc! {r#"
+            Execute a command in the shell.
+
+            This will return the output and error concatenated into a single string, as
+            you would see from running on the command line. There will also be an indication
+            of if the command succeeded or failed.
+
+            Avoid commands that produce a large amount of output, and consider piping those outputs to files.
+
+            **Important**: For searching files and code:
+
+            Preferred: Use ripgrep (`rg`) when available - it respects .gitignore and is fast:
+              - To locate a file by name: `rg --files | rg example.py`
+              - To locate content inside files: `rg 'class Example'`
+
+            Alternative Windows commands (if ripgrep is not installed):
+              - To locate a file by name: `dir /s /b example.py`
+              - To locate content inside files: `findstr /s /i "class Example" *.py`
+
+            Note: Alternative commands may show ignored/hidden files that should be excluded.
+        "#},
+        _ => indoc! {r#"
+            Execute a command in the shell.
+
+            This will return the output and error concatenated into a single string, as
+            you would see from running on the command line. There will also be an indication
+            of if the command succeeded or failed.
+
+            Avoid commands that produce a large amount of output, and consider piping those outputs to files.
+            If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
+            this tool does not run indefinitely.
+
+            **Important**: Each shell command runs in its own process. Things like directory changes or
+            sourcing files do not persist between tool calls. So you may need to repeat them each time by
+            stringing together commands, e.g. `cd example && ls` or `source env/bin/activate && pip install numpy`
+
+            **Important**: Use ripgrep - `rg` - when you need to locate a file or a code reference, other solutions
+            may show ignored or hidden files. For example *do not* use `find` or `ls -r`
+              - List files by name: `rg --files | rg <filename>`
+              - List files that contain a regex: `rg '<regex>' -l`
+        "#},
+    }
+}
+
+/// The operations of the text editor tool
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum TextEditorCommand {
+    View,
+    Write,
+    StrReplace,
+    UndoEdit,
+}
+
 #[derive(Debug)] // Add this for easy printing if needed
 struct ToolResult {
     name: String,
@@ -94,11 +152,11 @@ struct ToolResult {
     value: Vec<Content>,
 }
 pub struct DeveloperRouter {
-    tools: Vec<Tool>,
     prompts: Arc<HashMap<String, Prompt>>,
     instructions: String,
     file_history: Arc<Mutex<HashMap<PathBuf, Vec<String>>>>,
     ignore_patterns: Arc<Gitignore>,
+    peer: Option<Peer>,
 }
 
 impl Default for DeveloperRouter {
@@ -112,165 +170,6 @@ impl DeveloperRouter {
         // TODO consider rust native search tools, we could use
         // https://docs.rs/ignore/latest/ignore/
 
-        // Get OS-specific shell tool description
-        let shell_tool_desc = match std::env::consts::OS {
-            "windows" => indoc! {r#"
-                Execute a command in the shell.
-
-                This will return the output and error concatenated into a single string, as
-                you would see from running on the command line. There will also be an indication
-                of if the command succeeded or failed.
-
-                Avoid commands that produce a large amount of output, and consider piping those outputs to files.
-
-                **Important**: For searching files and code:
-
-                Preferred: Use ripgrep (`rg`) when available - it respects .gitignore and is fast:
-                  - To locate a file by name: `rg --files | rg example.py`
-                  - To locate content inside files: `rg 'class Example'`
-
-                Alternative Windows commands (if ripgrep is not installed):
-                  - To locate a file by name: `dir /s /b example.py`
-                  - To locate content inside files: `findstr /s /i "class Example" *.py`
-
-                Note: Alternative commands may show ignored/hidden files that should be excluded.
-            "#},
-            _ => indoc! {r#"
-                Execute a command in the shell.
-
-                This will return the output and error concatenated into a single string, as
-                you would see from running on the command line. There will also be an indication
-                of if the command succeeded or failed.
-
-                Avoid commands that produce a large amount of output, and consider piping those outputs to files.
-                If you need to run a long lived command, background it - e.g. `uvicorn main:app &` so that
-                this tool does not run indefinitely.
-
-                **Important**: Each shell command runs in its own process. Things like directory changes or
-                sourcing files do not persist between tool calls. So you may need to repeat them each time by
-                stringing together commands, e.g. `cd example && ls` or `source env/bin/activate && pip install numpy`
-
-                **Important**: Use ripgrep - `rg` - when you need to locate a file or a code reference, other solutions
-                may show ignored or hidden files. For example *do not* use `find` or `ls -r`
-                  - List files by name: `rg --files | rg <filename>`
-                  - List files that contain a regex: `rg '<regex>' -l`
-            "#},
-        };
-
-        let bash_tool = Tool::new(
-            "shell".to_string(),
-            shell_tool_desc.to_string(),
-            json!({
-                "type": "object",
-                "required": ["command"],
-                "properties": {
-                    "command": {"type": "string"}
-                }
-            }),
-        );
-
-        let text_editor_tool = Tool::new(
-            "text_editor".to_string(),
-            indoc! {r#"
-                Perform text editing operations on files.
-
-                The `command` parameter specifies the operation to perform. Allowed options are:
-                - `view`: View the content of a file.
-                - `write`: Create or overwrite a file with the given content
-                - `str_replace`: Replace a string in a file with a new string.
-                - `undo_edit`: Undo the last edit made to a file.
-
-                To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
-                existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
-
-                To use the str_replace command, you must specify both `old_str` and `new_str` - the `old_str` needs to exactly match one
-                unique section of the original file, including any whitespace. Make sure to include enough context that the match is not
-                ambiguous. The entire original string will be replaced with `new_str`.
-            "#}.to_string(),
-            json!({
-                "type": "object",
-                "required": ["command", "path"],
-                "properties": {
-                    "path": {
-                        "description": "Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`.",
-                        "type": "string"
-                    },
-                    "command": {
-                        "type": "string",
-                        "enum": ["view", "write", "str_replace", "undo_edit"],
-                        "description": "Allowed options are: `view`, `write`, `str_replace`, undo_edit`."
-                    },
-                    "old_str": {"type": "string"},
-                    "new_str": {"type": "string"},
-                    "file_text": {"type": "string"}
-                }
-            }),
-        );
-
-        let list_windows_tool = Tool::new(
-            "list_windows",
-            indoc! {r#"
-                List all available window titles that can be used with screen_capture.
-                Returns a list of window titles that can be used with the window_title parameter
-                of the screen_capture tool.
-            "#},
-            json!({
-                "type": "object",
-                "required": [],
-                "properties": {}
-            }),
-        );
-
-        let screen_capture_tool = Tool::new(
-            "screen_capture",
-            indoc! {r#"
-                Capture a screenshot of a specified display or window.
-                You can capture either:
-                1. A full display (monitor) using the display parameter
-                2. A specific window by its title using the window_title parameter
-
-                Only one of display or window_title should be specified.
-            "#},
-            json!({
-                "type": "object",
-                "required": [],
-                "properties": {
-                    "display": {
-                        "type": "integer",
-                        "default": 0,
-                        "description": "The display number to capture (0 is main display)"
-                    },
-                    "window_title": {
-                        "type": "string",
-                        "default": null,
-                        "description": "Optional: the exact title of the window to capture. use the list_windows tool to find the available windows."
-                    }
-                }
-            }),
-        );
-
-        let image_processor_tool = Tool::new(
-            "image_processor",
-            indoc! {r#"
-                Process an image file from disk. The image will be:
-                1. Resized if larger than max width while maintaining aspect ratio
-                2. Converted to PNG format
-                3. Returned as base64 encoded data
-
-                This allows processing image files for use in the conversation.
-            "#},
-            json!({
-                "type": "object",
-                "required": ["path"],
-                "properties": {
-                    "path": {
-                        "type": "string",
-                        "description": "Absolute path to the image file to process"
-                    }
-                }
-            }),
-        );
-
         // Get base instructions and working directory
         let cwd = std::env::current_dir().expect("should have a current working dir");
         let os = std::env::consts::OS;
@@ -398,20 +297,38 @@ impl DeveloperRouter {
         let ignore_patterns = builder.build().expect("Failed to build ignore patterns");
 
         Self {
-            tools: vec![
-                bash_tool,
-                text_editor_tool,
-                list_windows_tool,
-                screen_capture_tool,
-                image_processor_tool,
-            ],
             prompts: Arc::new(load_prompt_files()),
             instructions,
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            peer: None,
         }
     }
 
+    /// Work in the roots the connected client declares through `peer`
+    pub fn with_peer(mut self, peer: Peer) -> Self {
+        self.peer = Some(peer);
+        self
+    }
+
+    // Helper method to find the directory to run commands in: the first root of the
+    // client if it declares any, otherwise our own working directory
+    async fn working_dir(&self) -> Result<PathBuf, ToolError> {
+        if let Some(peer) = self.peer.as_ref().filter(|peer| peer.supports_roots()) {
+            match peer.list_roots().await {
+                Ok(roots) => {
+                    if let Some(dir) = roots.iter().find_map(|root| root.path()) {
+                        return Ok(dir);
+                    }
+                }
+                Err(e) => tracing::debug!("Failed to list roots: {}", e),
+            }
+        }
+        std::env::current_dir().map_err(|e| {
+            ToolError::ExecutionError(format!("Failed to get the working directory: {}", e))
+        })
+    }
+
     // Helper method to check if a path should be ignored
     fn is_ignored(&self, path: &Path) -> bool {
         self.ignore_patterns.matched(path, false).is_ignore()
@@ -435,138 +352,6 @@ impl DeveloperRouter {
         }
     }
 
-    // Shell command execution with platform-specific handling
-    async fn bash(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let command =
-            params
-                .get("command")
-                .and_then(|v| v.as_str())
-                .ok_or(ToolError::InvalidParameters(
-                    "The command string is required".to_string(),
-                ))?;
-
-        // Check if command might access ignored files and return early if it does
-        let cmd_parts: Vec<&str> = command.split_whitespace().collect();
-        for arg in &cmd_parts[1..] {
-            // Skip command flags
-            if arg.starts_with('-') {
-                continue;
-            }
-            // Skip invalid paths
-            let path = Path::new(arg);
-            if !path.exists() {
-                continue;
-            }
-
-            if self.is_ignored(path) {
-                return Err(ToolError::ExecutionError(format!(
-                    "The command attempts to access '{}' which is restricted by .gooseignore",
-                    arg
-                )));
-            }
-        }
-
-        // Get platform-specific shell configuration
-        let shell_config = get_shell_config();
-        let cmd_with_redirect = format_command_for_platform(command);
-
-        // Execute the command using platform-specific shell
-        let child = Command::new(&shell_config.executable)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .stdin(Stdio::null())
-            .kill_on_drop(true)
-            .arg(&shell_config.arg)
-            .arg(cmd_with_redirect)
-            .spawn()
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-
-        // Wait for the command to complete and get output
-        let output = child
-            .wait_with_output()
-            .await
-            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
-
-        let output_str = String::from_utf8_lossy(&output.stdout);
-
-        // Check the character count of the output
-        const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
-        let char_count = output_str.chars().count();
-        if char_count > MAX_CHAR_COUNT {
-            return Err(ToolError::ExecutionError(format!(
-                    "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
-                    command,
-                    char_count,
-                    MAX_CHAR_COUNT
-                )));
-        }
-
-        Ok(vec![
-            Content::text(output_str.clone()).with_audience(vec![Role::Assistant]),
-            Content::text(output_str)
-                .with_audience(vec![Role::User])
-                .with_priority(0.0),
-        ])
-    }
-
-    async fn text_editor(&self, params: Value) -> Result<ToolResult, ToolError> {
-        let command = params
-            .get("command")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| {
-                ToolError::InvalidParameters("Missing 'command' parameter".to_string())
-            })?;
-
-        let path_str = params
-            .get("path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
-
-        let path = self.resolve_path(path_str)?;
-
-        // Check if file is ignored before proceeding with any text editor operation
-        if self.is_ignored(&path) {
-            return Err(ToolError::ExecutionError(format!(
-                "Access to '{}' is restricted by .gooseignore",
-                path.display()
-            )));
-        }
-
-        match command {
-            "view" => self.text_editor_view(&path).await,
-            "write" => {
-                let file_text = params
-                    .get("file_text")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'file_text' parameter".into())
-                    })?;
-
-                self.text_editor_write(&path, file_text).await
-            }
-            "str_replace" => {
-                let old_str = params
-                    .get("old_str")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'old_str' parameter".into())
-                    })?;
-                let new_str = params
-                    .get("new_str")
-                    .and_then(|v| v.as_str())
-                    .ok_or_else(|| {
-                        ToolError::InvalidParameters("Missing 'new_str' parameter".into())
-                    })?;
-
-                self.text_editor_replace(&path, old_str, new_str).await
-            }
-            "undo_edit" => self.text_editor_undo(&path).await,
-            _ => Err(ToolError::InvalidParameters(format!(
-                "Unknown command '{}'",
-                command
-            ))),
-        }
-    }
     async fn text_editor_view(&self, path: &PathBuf) -> Result<ToolResult, ToolError> {
         if path.is_file() {
             // Check file size first (400KB limit)
@@ -790,32 +575,136 @@ impl DeveloperRouter {
         history.entry(path.clone()).or_default().push(content);
         Ok(())
     }
+}
 
-    async fn list_windows(&self, _params: Value) -> Result<Vec<Content>, ToolError> {
-        let windows = Window::all()
-            .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
+#[router]
+impl Router for DeveloperRouter {
+    fn name(&self) -> String {
+        "developer".to_string()
+    }
 
-        let window_titles: Vec<String> =
-            windows.into_iter().map(|w| w.title().to_string()).collect();
+    fn instructions(&self) -> String {
+        self.instructions.clone()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new()
+            .with_tools(false)
+            .with_prompts(false)
+            .build()
+    }
+
+    fn peer(&self) -> Option<Peer> {
+        self.peer.clone()
+    }
+
+    // Shell command execution with platform-specific handling
+    #[tool(
+        name = "shell",
+        description = shell_tool_description(),
+        destructive_hint = true
+    )]
+    async fn bash(&self, command: String) -> Result<Vec<Content>, ToolError> {
+        // Check if command might access ignored files and return early if it does
+        let cmd_parts: Vec<&str> = command.split_whitespace().collect();
+        for arg in &cmd_parts[1..] {
+            // Skip command flags
+            if arg.starts_with('-') {
+                continue;
+            }
+            // Skip invalid paths
+            let path = Path::new(arg);
+            if !path.exists() {
+                continue;
+            }
+
+            if self.is_ignored(path) {
+                return Err(ToolError::ExecutionError(format!(
+                    "The command attempts to access '{}' which is restricted by .gooseignore",
+                    arg
+                )));
+            }
+        }
+
+        // Get platform-specific shell configuration
+        let shell_config = get_shell_config();
+        let cmd_with_redirect = format_command_for_platform(&command);
+
+        // Execute the command using platform-specific shell
+        let child = Command::new(&shell_config.executable)
+            .current_dir(self.working_dir().await?)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .arg(&shell_config.arg)
+            .arg(cmd_with_redirect)
+            .spawn()
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        // Wait for the command to complete and get output
+        let output = child
+            .wait_with_output()
+            .await
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+
+        let output_str = String::from_utf8_lossy(&output.stdout);
+
+        // Check the character count of the output
+        const MAX_CHAR_COUNT: usize = 400_000; // 409600 chars = 400KB
+        let char_count = output_str.chars().count();
+        if char_count > MAX_CHAR_COUNT {
+            return Err(ToolError::ExecutionError(format!(
+                    "Shell output from command '{}' has too many characters ({}). Maximum character count is {}.",
+                    command,
+                    char_count,
+                    MAX_CHAR_COUNT
+                )));
+        }
 
         Ok(vec![
-            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
-                .with_audience(vec![Role::Assistant]),
-            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
+            Content::text(output_str.clone()).with_audience(vec![Role::Assistant]),
+            Content::text(output_str)
                 .with_audience(vec![Role::User])
                 .with_priority(0.0),
         ])
     }
 
-    async fn image_processor(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let path_str = params
-            .get("path")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| ToolError::InvalidParameters("Missing 'path' parameter".into()))?;
-
-        let path = self.resolve_path(path_str)?;
+    #[tool(
+        description = indoc! {r#"
+            Perform text editing operations on files.
+
+            The `command` parameter specifies the operation to perform. Allowed options are:
+            - `view`: View the content of a file.
+            - `write`: Create or overwrite a file with the given content
+            - `str_replace`: Replace a string in a file with a new string.
+            - `undo_edit`: Undo the last edit made to a file.
+
+            To use the write command, you must specify `file_text` which will become the new content of the file. Be careful with
+            existing files! This is a full overwrite, so you must include everything - not just sections you are modifying.
+
+            To use the str_replace command, you must specify both `old_str` and `new_str` - the `old_str` needs to exactly match one
+            unique section of the original file, including any whitespace. Make sure to include enough context that the match is not
+            ambiguous. The entire original string will be replaced with `new_str`.
+        "#},
+        params(
+            path = "Absolute path to file or directory, e.g. `/repo/file.py` or `/repo`.",
+            command = "Allowed options are: `view`, `write`, `str_replace`, `undo_edit`."
+        ),
+        destructive_hint = true,
+        open_world_hint = false
+    )]
+    async fn text_editor(
+        &self,
+        path: String,
+        command: TextEditorCommand,
+        old_str: Option<String>,
+        new_str: Option<String>,
+        file_text: Option<String>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let path = self.resolve_path(&path)?;
 
-        // Check if file is ignored before proceeding
+        // Check if file is ignored before proceeding with any text editor operation
         if self.is_ignored(&path) {
             return Err(ToolError::ExecutionError(format!(
                 "Access to '{}' is restricted by .gooseignore",
@@ -823,70 +712,78 @@ impl DeveloperRouter {
             )));
         }
 
-        // Check if file exists
-        if !path.exists() {
-            return Err(ToolError::ExecutionError(format!(
-                "File '{}' does not exist",
-                path.display()
-            )));
-        }
-
-        // Check file size (10MB limit for image files)
-        const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB in bytes
-        let file_size = std::fs::metadata(&path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
-            .len();
+        let result = match command {
+            TextEditorCommand::View => self.text_editor_view(&path).await,
+            TextEditorCommand::Write => {
+                let file_text = file_text.ok_or_else(|| {
+                    ToolError::InvalidParameters("Missing 'file_text' parameter".into())
+                })?;
 
-        if file_size > MAX_FILE_SIZE {
-            return Err(ToolError::ExecutionError(format!(
-                "File '{}' is too large ({:.2}MB). Maximum size is 10MB.",
-                path.display(),
-                file_size as f64 / (1024.0 * 1024.0)
-            )));
-        }
+                self.text_editor_write(&path, &file_text).await
+            }
+            TextEditorCommand::StrReplace => {
+                let old_str = old_str.ok_or_else(|| {
+                    ToolError::InvalidParameters("Missing 'old_str' parameter".into())
+                })?;
+                let new_str = new_str.ok_or_else(|| {
+                    ToolError::InvalidParameters("Missing 'new_str' parameter".into())
+                })?;
 
-        // Open and decode the image
-        let image = xcap::image::open(&path)
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to open image file: {}", e)))?;
+                self.text_editor_replace(&path, &old_str, &new_str).await
+            }
+            TextEditorCommand::UndoEdit => self.text_editor_undo(&path).await,
+        }?;
 
-        // Resize if necessary (same logic as screen_capture)
-        let mut processed_image = image;
-        let max_width = 768;
-        if processed_image.width() > max_width {
-            let scale = max_width as f32 / processed_image.width() as f32;
-            let new_height = (processed_image.height() as f32 * scale) as u32;
-            processed_image = xcap::image::DynamicImage::ImageRgba8(xcap::image::imageops::resize(
-                &processed_image,
-                max_width,
-                new_height,
-                xcap::image::imageops::FilterType::Lanczos3,
-            ));
-        }
+        Ok(result.value)
+    }
 
-        // Convert to PNG and encode as base64
-        let mut bytes: Vec<u8> = Vec::new();
-        processed_image
-            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
-            .map_err(|e| {
-                ToolError::ExecutionError(format!("Failed to write image buffer: {}", e))
-            })?;
+    #[tool(
+        description = indoc! {r#"
+            List all available window titles that can be used with screen_capture.
+            Returns a list of window titles that can be used with the window_title parameter
+            of the screen_capture tool.
+        "#},
+        read_only_hint = true,
+        open_world_hint = false
+    )]
+    async fn list_windows(&self) -> Result<Vec<Content>, ToolError> {
+        let windows = Window::all()
+            .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
 
-        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+        let window_titles: Vec<String> =
+            windows.into_iter().map(|w| w.title().to_string()).collect();
 
         Ok(vec![
-            Content::text(format!(
-                "Successfully processed image from {}",
-                path.display()
-            ))
-            .with_audience(vec![Role::Assistant]),
-            Content::image(data, "image/png").with_priority(0.0),
+            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
+                .with_audience(vec![Role::Assistant]),
+            Content::text(format!("Available windows:\n{}", window_titles.join("\n")))
+                .with_audience(vec![Role::User])
+                .with_priority(0.0),
         ])
     }
 
-    async fn screen_capture(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let mut image = if let Some(window_title) =
-            params.get("window_title").and_then(|v| v.as_str())
-        {
+    #[tool(
+        description = indoc! {r#"
+            Capture a screenshot of a specified display or window.
+            You can capture either:
+            1. A full display (monitor) using the display parameter
+            2. A specific window by its title using the window_title parameter
+
+            Only one of display or window_title should be specified.
+        "#},
+        params(
+            display = "The display number to capture (0 is main display)",
+            window_title = "Optional: the exact title of the window to capture. use the list_windows tool to find the available windows."
+        ),
+        read_only_hint = true,
+        open_world_hint = false
+    )]
+    async fn screen_capture(
+        &self,
+        display: Option<usize>,
+        window_title: Option<String>,
+    ) -> Result<Vec<Content>, ToolError> {
+        let mut image = if let Some(window_title) = window_title {
             // Try to find and capture the specified window
             let windows = Window::all()
                 .map_err(|_| ToolError::ExecutionError("Failed to list windows".into()))?;
@@ -909,7 +806,7 @@ impl DeveloperRouter {
             })?
         } else {
             // Default to display capture if no window title is specified
-            let display = params.get("display").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let display = display.unwrap_or(0);
 
             let monitors = Monitor::all()
                 .map_err(|_| ToolError::ExecutionError("Failed to access monitors".into()))?;
@@ -954,48 +851,89 @@ impl DeveloperRouter {
             Content::image(data, "image/png").with_priority(0.0),
         ])
     }
-}
 
-impl Router for DeveloperRouter {
-    fn name(&self) -> String {
-        "developer".to_string()
-    }
+    #[tool(
+        description = indoc! {r#"
+            Process an image file from disk. The image will be:
+            1. Resized if larger than max width while maintaining aspect ratio
+            2. Converted to PNG format
+            3. Returned as base64 encoded data
+
+            This allows processing image files for use in the conversation.
+        "#},
+        params(path = "Absolute path to the image file to process"),
+        read_only_hint = true,
+        open_world_hint = false
+    )]
+    async fn image_processor(&self, path: String) -> Result<Vec<Content>, ToolError> {
+        let path = self.resolve_path(&path)?;
 
-    fn instructions(&self) -> String {
-        self.instructions.clone()
-    }
+        // Check if file is ignored before proceeding
+        if self.is_ignored(&path) {
+            return Err(ToolError::ExecutionError(format!(
+                "Access to '{}' is restricted by .gooseignore",
+                path.display()
+            )));
+        }
 
-    fn capabilities(&self) -> ServerCapabilities {
-        CapabilitiesBuilder::new()
-            .with_tools(false)
-            .with_prompts(false)
-            .build()
-    }
+        // Check if file exists
+        if !path.exists() {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' does not exist",
+                path.display()
+            )));
+        }
 
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
+        // Check file size (10MB limit for image files)
+        const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10MB in bytes
+        let file_size = std::fs::metadata(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to get file metadata: {}", e)))?
+            .len();
 
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-        Box::pin(async move {
-            match tool_name.as_str() {
-                "shell" => this.bash(arguments).await,
-                "text_editor" => {
-                    let result: ToolResult = this.text_editor(arguments).await?;
-                    Ok(result.value)
-                },
-                "list_windows" => this.list_windows(arguments).await,
-                "screen_capture" => this.screen_capture(arguments).await,
-                "image_processor" => this.image_processor(arguments).await,
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
-            }
-        })
+        if file_size > MAX_FILE_SIZE {
+            return Err(ToolError::ExecutionError(format!(
+                "File '{}' is too large ({:.2}MB). Maximum size is 10MB.",
+                path.display(),
+                file_size as f64 / (1024.0 * 1024.0)
+            )));
+        }
+
+        // Open and decode the image
+        let image = xcap::image::open(&path)
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to open image file: {}", e)))?;
+
+        // Resize if necessary (same logic as screen_capture)
+        let mut processed_image = image;
+        let max_width = 768;
+        if processed_image.width() > max_width {
+            let scale = max_width as f32 / processed_image.width() as f32;
+            let new_height = (processed_image.height() as f32 * scale) as u32;
+            processed_image = xcap::image::DynamicImage::ImageRgba8(xcap::image::imageops::resize(
+                &processed_image,
+                max_width,
+                new_height,
+                xcap::image::imageops::FilterType::Lanczos3,
+            ));
+        }
+
+        // Convert to PNG and encode as base64
+        let mut bytes: Vec<u8> = Vec::new();
+        processed_image
+            .write_to(&mut Cursor::new(&mut bytes), xcap::image::ImageFormat::Png)
+            .map_err(|e| {
+                ToolError::ExecutionError(format!("Failed to write image buffer: {}", e))
+            })?;
+
+        let data = base64::prelude::BASE64_STANDARD.encode(bytes);
+
+        Ok(vec![
+            Content::text(format!(
+                "Successfully processed image from {}",
+                path.display()
+            ))
+            .with_audience(vec![Role::Assistant]),
+            Content::image(data, "image/png").with_priority(0.0),
+        ])
     }
     
     // TODO see if we can make it easy to skip implementing these
@@ -1045,11 +983,11 @@ impl Router for DeveloperRouter {
 impl Clone for DeveloperRouter {
     fn clone(&self) -> Self {
         Self {
-            tools: self.tools.clone(),
             prompts: Arc::clone(&self.prompts),
             instructions: self.instructions.clone(),
             file_history: Arc::clone(&self.file_history),
             ignore_patterns: Arc::clone(&self.ignore_patterns),
+            peer: self.peer.clone(),
         }
     }
 }
@@ -1452,11 +1390,11 @@ mod tests {
         let ignore_patterns = builder.build().unwrap();
 
         let router = DeveloperRouter {
-            tools: vec![],
             prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            peer: None,
         };
 
         // Test basic file matching
@@ -1502,11 +1440,11 @@ mod tests {
         let ignore_patterns = builder.build().unwrap();
 
         let router = DeveloperRouter {
-            tools: DeveloperRouter::new().tools, // Reuse default tools
             prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            peer: None,
         };
 
         // Try to write to an ignored file
@@ -1559,11 +1497,11 @@ mod tests {
         let ignore_patterns = builder.build().unwrap();
 
         let router = DeveloperRouter {
-            tools: DeveloperRouter::new().tools, // Reuse default tools
             prompts: Arc::new(HashMap::new()),
             instructions: String::new(),
             file_history: Arc::new(Mutex::new(HashMap::new())),
             ignore_patterns: Arc::new(ignore_patterns),
+            peer: None,
         };
 
         // Create an ignored file
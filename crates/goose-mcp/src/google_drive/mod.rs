@@ -9,8 +9,8 @@ use mcp_core::{
     prompt::Prompt,
     protocol::ServerCapabilities,
     resource::Resource,
-    tool::Tool,
 };
+use mcp_macros::router;
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
@@ -64,7 +64,6 @@ impl InstalledFlowDelegate for LocalhostBrowserDelegate {
 }
 
 pub struct GoogleDriveRouter {
-    tools: Vec<Tool>,
     instructions: String,
     drive: DriveHub<HttpsConnector<HttpConnector>>,
 }
@@ -135,56 +134,6 @@ impl GoogleDriveRouter {
     pub async fn new() -> Self {
         let drive = Self::google_auth().await;
 
-        // handle auth
-        let search_tool = Tool::new(
-            "search".to_string(),
-            indoc! {r#"
-                Search for files in google drive by name, given an input search query.
-            "#}
-            .to_string(),
-            json!({
-              "type": "object",
-              "properties": {
-                "query": {
-                    "type": "string",
-                    "description": "Search query",
-                },
-                "corpora": {
-                    "type": "string",
-                    "description": "Which corpus to search, either 'user' (default), 'drive' or 'allDrives'",
-                },
-                "pageSize": {
-                    "type": "number",
-                    "description": "How many items to return from the search query, default 10, max 100",
-                }
-              },
-              "required": ["query"],
-            }),
-        );
-
-        let read_tool = Tool::new(
-            "read".to_string(),
-            indoc! {r#"
-                Read a file from google drive using the file uri.
-                Optionally include base64 encoded images, false by default.
-            "#}
-            .to_string(),
-            json!({
-              "type": "object",
-              "properties": {
-                  "uri": {
-                      "type": "string",
-                      "description": "google drive uri of the file to read",
-                  },
-                  "includeImages": {
-                      "type": "boolean",
-                      "description": "Whether or not to include images as base64 encoded strings, defaults to false",
-                  }
-              },
-              "required": ["uri"],
-            }),
-        );
-
         let instructions = indoc::formatdoc! {r#"
             Google Drive MCP Server Instructions
 
@@ -240,103 +189,11 @@ impl GoogleDriveRouter {
         "#};
 
         Self {
-            tools: vec![search_tool, read_tool],
             instructions,
             drive,
         }
     }
 
-    // Implement search tool functionality
-    async fn search(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let query = params
-            .get("query")
-            .and_then(|q| q.as_str())
-            .ok_or(ToolError::InvalidParameters(
-                "The query string is required".to_string(),
-            ))?
-            .replace('\\', "\\\\")
-            .replace('\'', "\\'");
-
-        // extract corpora query parameter, validate options, or default to "user"
-        let corpus = params
-            .get("corpora")
-            .and_then(|c| c.as_str())
-            .map(|s| {
-                if ["user", "drive", "allDrives"].contains(&s) {
-                    Ok(s)
-                } else {
-                    Err(ToolError::InvalidParameters(format!(
-                        "corpora must be either 'user', 'drive', or 'allDrives', got {}",
-                        s
-                    )))
-                }
-            })
-            .unwrap_or(Ok("user"))?;
-
-        // extract pageSize, and convert it to an i32, default to 10
-        let page_size: i32 = params
-            .get("pageSize")
-            .map(|s| {
-                s.as_i64()
-                    .and_then(|n| i32::try_from(n).ok())
-                    .ok_or_else(|| ToolError::InvalidParameters(format!("Invalid pageSize: {}", s)))
-                    .and_then(|n| {
-                        if (0..=100).contains(&n) {
-                            Ok(n)
-                        } else {
-                            Err(ToolError::InvalidParameters(format!(
-                                "pageSize must be between 0 and 100, got {}",
-                                n
-                            )))
-                        }
-                    })
-            })
-            .unwrap_or(Ok(10))?;
-
-        let result = self
-            .drive
-            .files()
-            .list()
-            .corpora(corpus)
-            .q(format!("name contains '{}'", query).as_str())
-            .order_by("viewedByMeTime desc")
-            .param("fields", "files(id, name, mimeType, modifiedTime, size)")
-            .page_size(page_size)
-            .supports_all_drives(true)
-            .include_items_from_all_drives(true)
-            .clear_scopes() // Scope::MeetReadonly is the default, remove it
-            .add_scope(Scope::Readonly)
-            .doit()
-            .await;
-
-        match result {
-            Err(e) => Err(ToolError::ExecutionError(format!(
-                "Failed to execute google drive search query, {}.",
-                e
-            ))),
-            Ok(r) => {
-                let content =
-                    r.1.files
-                        .map(|files| {
-                            files.into_iter().map(|f| {
-                                format!(
-                                    "{} ({}) (uri: {})",
-                                    f.name.unwrap_or_default(),
-                                    f.mime_type.unwrap_or_default(),
-                                    f.id.unwrap_or_default()
-                                )
-                            })
-                        })
-                        .into_iter()
-                        .flatten()
-                        .collect::<Vec<_>>()
-                        .join("\n");
-
-                Ok(vec![Content::text(content.to_string())])
-            }
-        }
-    }
-
     async fn fetch_file_metadata(&self, uri: &str) -> Result<File, ToolError> {
         self.drive
             .files()
@@ -476,36 +333,6 @@ impl GoogleDriveRouter {
         }
     }
 
-    async fn read(&self, params: Value) -> Result<Vec<Content>, ToolError> {
-        let uri =
-            params
-                .get("uri")
-                .and_then(|q| q.as_str())
-                .ok_or(ToolError::InvalidParameters(
-                    "The uri of the file is required".to_string(),
-                ))?;
-
-        let drive_uri = uri.replace("gdrive:///", "");
-
-        let include_images = params
-            .get("includeImages")
-            .and_then(|i| i.as_bool())
-            .unwrap_or(false);
-
-        let metadata = self.fetch_file_metadata(&drive_uri).await?;
-        let mime_type = metadata.mime_type.ok_or_else(|| {
-            ToolError::ExecutionError(format!("Missing mime type in file metadata for {}.", uri))
-        })?;
-
-        // Handle Google Docs export
-        if mime_type.starts_with("application/vnd.google-apps") {
-            self.export_google_file(&drive_uri, &mime_type, include_images)
-                .await
-        } else {
-            self.get_google_file(&drive_uri, include_images).await
-        }
-    }
-
     async fn read_google_resource(&self, uri: String) -> Result<String, ResourceError> {
         self.read(json!({"uri": uri}))
             .await
@@ -568,6 +395,7 @@ impl GoogleDriveRouter {
     }
 }
 
+#[router]
 impl Router for GoogleDriveRouter {
     fn name(&self) -> String {
         "google_drive".to_string()
@@ -584,24 +412,169 @@ impl Router for GoogleDriveRouter {
             .build()
     }
 
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
+    #[tool(
+        description = indoc! {r#"
+            Search for files in google drive by name, given an input search query.
+        "#},
+        schema = json!({
+          "type": "object",
+          "properties": {
+            "query": {
+                "type": "string",
+                "description": "Search query",
+            },
+            "corpora": {
+                "type": "string",
+                "description": "Which corpus to search, either 'user' (default), 'drive' or 'allDrives'",
+            },
+            "pageSize": {
+                "type": "number",
+                "description": "How many items to return from the search query, default 10, max 100",
+            }
+          },
+          "required": ["query"],
+        }),
+        read_only_hint = true
+    )]
+    async fn search(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let query = params
+            .get("query")
+            .and_then(|q| q.as_str())
+            .ok_or(ToolError::InvalidParameters(
+                "The query string is required".to_string(),
+            ))?
+            .replace('\\', "\\\\")
+            .replace('\'', "\\'");
 
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-        Box::pin(async move {
-            match tool_name.as_str() {
-                "search" => this.search(arguments).await,
-                "read" => this.read(arguments).await,
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
+        // extract corpora query parameter, validate options, or default to "user"
+        let corpus = params
+            .get("corpora")
+            .and_then(|c| c.as_str())
+            .map(|s| {
+                if ["user", "drive", "allDrives"].contains(&s) {
+                    Ok(s)
+                } else {
+                    Err(ToolError::InvalidParameters(format!(
+                        "corpora must be either 'user', 'drive', or 'allDrives', got {}",
+                        s
+                    )))
+                }
+            })
+            .unwrap_or(Ok("user"))?;
+
+        // extract pageSize, and convert it to an i32, default to 10
+        let page_size: i32 = params
+            .get("pageSize")
+            .map(|s| {
+                s.as_i64()
+                    .and_then(|n| i32::try_from(n).ok())
+                    .ok_or_else(|| ToolError::InvalidParameters(format!("Invalid pageSize: {}", s)))
+                    .and_then(|n| {
+                        if (0..=100).contains(&n) {
+                            Ok(n)
+                        } else {
+                            Err(ToolError::InvalidParameters(format!(
+                                "pageSize must be between 0 and 100, got {}",
+                                n
+                            )))
+                        }
+                    })
+            })
+            .unwrap_or(Ok(10))?;
+
+        let result = self
+            .drive
+            .files()
+            .list()
+            .corpora(corpus)
+            .q(format!("name contains '{}'", query).as_str())
+            .order_by("viewedByMeTime desc")
+            .param("fields", "files(id, name, mimeType, modifiedTime, size)")
+            .page_size(page_size)
+            .supports_all_drives(true)
+            .include_items_from_all_drives(true)
+            .clear_scopes() // Scope::MeetReadonly is the default, remove it
+            .add_scope(Scope::Readonly)
+            .doit()
+            .await;
+
+        match result {
+            Err(e) => Err(ToolError::ExecutionError(format!(
+                "Failed to execute google drive search query, {}.",
+                e
+            ))),
+            Ok(r) => {
+                let content =
+                    r.1.files
+                        .map(|files| {
+                            files.into_iter().map(|f| {
+                                format!(
+                                    "{} ({}) (uri: {})",
+                                    f.name.unwrap_or_default(),
+                                    f.mime_type.unwrap_or_default(),
+                                    f.id.unwrap_or_default()
+                                )
+                            })
+                        })
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join("\n");
+
+                Ok(vec![Content::text(content.to_string())])
             }
-        })
+        }
+    }
+
+    #[tool(
+        description = indoc! {r#"
+            Read a file from google drive using the file uri.
+            Optionally include base64 encoded images, false by default.
+        "#},
+        schema = json!({
+          "type": "object",
+          "properties": {
+              "uri": {
+                  "type": "string",
+                  "description": "google drive uri of the file to read",
+              },
+              "includeImages": {
+                  "type": "boolean",
+                  "description": "Whether or not to include images as base64 encoded strings, defaults to false",
+              }
+          },
+          "required": ["uri"],
+        }),
+        read_only_hint = true
+    )]
+    async fn read(&self, params: Value) -> Result<Vec<Content>, ToolError> {
+        let uri =
+            params
+                .get("uri")
+                .and_then(|q| q.as_str())
+                .ok_or(ToolError::InvalidParameters(
+                    "The uri of the file is required".to_string(),
+                ))?;
+
+        let drive_uri = uri.replace("gdrive:///", "");
+
+        let include_images = params
+            .get("includeImages")
+            .and_then(|i| i.as_bool())
+            .unwrap_or(false);
+
+        let metadata = self.fetch_file_metadata(&drive_uri).await?;
+        let mime_type = metadata.mime_type.ok_or_else(|| {
+            ToolError::ExecutionError(format!("Missing mime type in file metadata for {}.", uri))
+        })?;
+
+        // Handle Google Docs export
+        if mime_type.starts_with("application/vnd.google-apps") {
+            self.export_google_file(&drive_uri, &mime_type, include_images)
+                .await
+        } else {
+            self.get_google_file(&drive_uri, include_images).await
+        }
     }
 
     fn list_resources(&self) -> Vec<Resource> {
@@ -641,7 +614,6 @@ impl Router for GoogleDriveRouter {
 impl Clone for GoogleDriveRouter {
     fn clone(&self) -> Self {
         Self {
-            tools: self.tools.clone(),
             instructions: self.instructions.clone(),
             drive: self.drive.clone(),
         }
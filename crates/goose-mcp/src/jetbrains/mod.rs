@@ -117,6 +117,8 @@ impl JetBrainsRouter {
     }
 }
 
+// Unlike the other routers this one is not generated with #[router]: its tools are whatever
+// the IDE plugin advertises at runtime, so there are no methods to derive them from.
 impl Router for JetBrainsRouter {
     fn name(&self) -> String {
         "jetbrains".to_string()
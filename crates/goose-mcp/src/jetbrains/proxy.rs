@@ -233,11 +233,7 @@ impl JetBrainsProxy {
                         }
                     };
 
-                    Some(Tool {
-                        name: name.to_string(),
-                        description: first_sentence,
-                        input_schema,
-                    })
+                    Some(Tool::new(name, first_sentence, input_schema))
                 } else {
                     debug!("Skipping invalid tool entry: {:?}", t);
                     None
@@ -1,4 +1,6 @@
 use etcetera::AppStrategyArgs;
+use mcp_server::router::RouterService;
+use mcp_server::{BoundedService, Peer};
 use once_cell::sync::Lazy;
 
 pub static APP_STRATEGY: Lazy<AppStrategyArgs> = Lazy::new(|| AppStrategyArgs {
@@ -20,3 +22,23 @@ pub use google_drive::GoogleDriveRouter;
 pub use jetbrains::JetBrainsRouter;
 pub use memory::MemoryRouter;
 pub use tutorial::TutorialRouter;
+
+/// Create the router of the builtin extension `name`, reaching the client through `peer`.
+/// Returns `None` for names that are not builtin extensions.
+pub async fn builtin_router(name: &str, peer: Peer) -> Option<Box<dyn BoundedService>> {
+    let router: Box<dyn BoundedService> = match name {
+        "developer" => Box::new(RouterService(DeveloperRouter::new().with_peer(peer))),
+        "computercontroller" => Box::new(RouterService(
+            ComputerControllerRouter::new().with_peer(peer),
+        )),
+        "jetbrains" => Box::new(RouterService(JetBrainsRouter::new())),
+        "google_drive" | "googledrive" => {
+            let router = GoogleDriveRouter::new().await;
+            Box::new(RouterService(router))
+        }
+        "memory" => Box::new(RouterService(MemoryRouter::new())),
+        "tutorial" => Box::new(RouterService(TutorialRouter::new())),
+        _ => return None,
+    };
+    Some(router)
+}
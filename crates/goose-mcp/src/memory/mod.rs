@@ -1,7 +1,7 @@
-use async_trait::async_trait;
 use etcetera::{choose_app_strategy, AppStrategy};
 use indoc::formatdoc;
-use serde_json::{json, Value};
+use schemars::JsonSchema;
+use serde::Serialize;
 use std::{
     collections::HashMap,
     fs,
@@ -16,16 +16,14 @@ use mcp_core::{
     prompt::Prompt,
     protocol::ServerCapabilities,
     resource::Resource,
-    tool::{Tool, ToolCall},
-    Content,
 };
+use mcp_macros::router;
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
 // MemoryRouter implementation
 #[derive(Clone)]
 pub struct MemoryRouter {
-    tools: Vec<Tool>,
     instructions: String,
     global_memory_dir: PathBuf,
     local_memory_dir: PathBuf,
@@ -39,61 +37,6 @@ impl Default for MemoryRouter {
 
 impl MemoryRouter {
     pub fn new() -> Self {
-        let remember_memory = Tool::new(
-            "remember_memory",
-            "Stores a memory with optional tags in a specified category",
-            json!({
-                "type": "object",
-                "properties": {
-                    "category": {"type": "string"},
-                    "data": {"type": "string"},
-                    "tags": {"type": "array", "items": {"type": "string"}},
-                    "is_global": {"type": "boolean"}
-                },
-                "required": ["category", "data", "is_global"]
-            }),
-        );
-
-        let retrieve_memories = Tool::new(
-            "retrieve_memories",
-            "Retrieves all memories from a specified category",
-            json!({
-                "type": "object",
-                "properties": {
-                    "category": {"type": "string"},
-                    "is_global": {"type": "boolean"}
-                },
-                "required": ["category", "is_global"]
-            }),
-        );
-
-        let remove_memory_category = Tool::new(
-            "remove_memory_category",
-            "Removes all memories within a specified category",
-            json!({
-                "type": "object",
-                "properties": {
-                    "category": {"type": "string"},
-                    "is_global": {"type": "boolean"}
-                },
-                "required": ["category", "is_global"]
-            }),
-        );
-
-        let remove_specific_memory = Tool::new(
-            "remove_specific_memory",
-            "Removes a specific memory within a specified category",
-            json!({
-                "type": "object",
-                "properties": {
-                    "category": {"type": "string"},
-                    "memory_content": {"type": "string"},
-                    "is_global": {"type": "boolean"}
-                },
-                "required": ["category", "memory_content", "is_global"]
-            }),
-        );
-
         let instructions = formatdoc! {r#"
              This extension allows storage and retrieval of categorized information with tagging support. It's designed to help
              manage important information across sessions in a systematic and organized manner.
@@ -192,12 +135,6 @@ impl MemoryRouter {
         fs::create_dir_all(&local_memory_dir).unwrap();
 
         let mut memory_router = Self {
-            tools: vec![
-                remember_memory,
-                retrieve_memories,
-                remove_memory_category,
-                remove_specific_memory,
-            ],
             instructions: instructions.clone(),
             global_memory_dir,
             local_memory_dir,
@@ -396,57 +333,26 @@ impl MemoryRouter {
         fs::remove_dir_all(base_dir)?;
         Ok(())
     }
+}
 
-    async fn execute_tool_call(&self, tool_call: ToolCall) -> Result<String, io::Error> {
-        match tool_call.name.as_str() {
-            "remember_memory" => {
-                let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let data = args.data.filter(|d| !d.is_empty()).ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        "Data must exist when remembering a memory",
-                    )
-                })?;
-                self.remember("context", args.category, data, &args.tags, args.is_global)?;
-                Ok(format!("Stored memory in category: {}", args.category))
-            }
-            "retrieve_memories" => {
-                let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memories = if args.category == "*" {
-                    self.retrieve_all(args.is_global)?
-                } else {
-                    self.retrieve(args.category, args.is_global)?
-                };
-                Ok(format!("Retrieved memories: {:?}", memories))
-            }
-            "remove_memory_category" => {
-                let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                if args.category == "*" {
-                    self.clear_all_global_or_local_memories(args.is_global)?;
-                    Ok(format!(
-                        "Cleared all memory {} categories",
-                        if args.is_global { "global" } else { "local" }
-                    ))
-                } else {
-                    self.clear_memory(args.category, args.is_global)?;
-                    Ok(format!("Cleared memories in category: {}", args.category))
-                }
-            }
-            "remove_specific_memory" => {
-                let args = MemoryArgs::from_value(&tool_call.arguments)?;
-                let memory_content = tool_call.arguments["memory_content"].as_str().unwrap();
-                self.remove_specific_memory(args.category, memory_content, args.is_global)?;
-                Ok(format!(
-                    "Removed specific memory from category: {}",
-                    args.category
-                ))
-            }
-            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, "Unknown tool")),
-        }
+/// The memories of a category, or of all of them
+#[derive(Serialize, JsonSchema)]
+struct Memories {
+    /// The memories of each category
+    memories: HashMap<String, Vec<String>>,
+}
+
+/// A category given to a memory tool, which names a file so must not be empty
+fn check_category(category: &str) -> Result<(), ToolError> {
+    if category.is_empty() {
+        return Err(ToolError::InvalidParameters(
+            "The category must not be empty".to_string(),
+        ));
     }
+    Ok(())
 }
 
-#[async_trait]
+#[router]
 impl Router for MemoryRouter {
     fn name(&self) -> String {
         "memory".to_string()
@@ -460,30 +366,6 @@ impl Router for MemoryRouter {
         CapabilitiesBuilder::new().with_tools(false).build()
     }
 
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
-
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-
-        Box::pin(async move {
-            let tool_call = ToolCall {
-                name: tool_name,
-                arguments,
-            };
-            match this.execute_tool_call(tool_call).await {
-                Ok(result) => Ok(vec![Content::text(result)]),
-                Err(err) => Err(ToolError::ExecutionError(err.to_string())),
-            }
-        })
-    }
-
     fn list_resources(&self) -> Vec<Resource> {
         Vec::new()
     }
@@ -510,56 +392,109 @@ impl Router for MemoryRouter {
             )))
         })
     }
-}
 
-#[derive(Debug)]
-struct MemoryArgs<'a> {
-    category: &'a str,
-    data: Option<&'a str>,
-    tags: Vec<&'a str>,
-    is_global: bool,
-}
-
-impl<'a> MemoryArgs<'a> {
-    // Category is required, data is optional, tags are optional, is_global is optional
-    fn from_value(args: &'a Value) -> Result<Self, io::Error> {
-        let category = args["category"].as_str().ok_or_else(|| {
-            io::Error::new(io::ErrorKind::InvalidInput, "Category must be a string")
-        })?;
-
-        if category.is_empty() {
-            return Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Category must be a string",
+    /// Stores a memory with optional tags in a specified category
+    #[tool(
+        params(
+            category = "The category to store the memory in",
+            data = "The memory",
+            tags = "Tags to find the memory by",
+            is_global = "Whether to store the memory for all projects rather than this one"
+        ),
+        destructive_hint = false,
+        open_world_hint = false
+    )]
+    fn remember_memory(
+        &self,
+        category: String,
+        data: String,
+        tags: Option<Vec<String>>,
+        is_global: bool,
+    ) -> Result<String, ToolError> {
+        check_category(&category)?;
+        if data.is_empty() {
+            return Err(ToolError::InvalidParameters(
+                "Data must exist when remembering a memory".to_string(),
             ));
         }
+        let tags: Vec<&str> = tags.iter().flatten().map(String::as_str).collect();
+        self.remember("context", &category, &data, &tags, is_global)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(format!("Stored memory in category: {}", category))
+    }
 
-        let data = args.get("data").and_then(|d| d.as_str());
-
-        let tags = match &args["tags"] {
-            Value::Array(arr) => arr.iter().filter_map(|v| v.as_str()).collect(),
-            Value::String(s) => vec![s.as_str()],
-            _ => Vec::new(),
+    /// Retrieves all memories from a specified category
+    #[tool(
+        params(
+            category = "The category to retrieve, '*' for all of them",
+            is_global = "Whether to retrieve the memories of all projects rather than this one"
+        ),
+        read_only_hint = true,
+        open_world_hint = false
+    )]
+    fn retrieve_memories(&self, category: String, is_global: bool) -> Result<Memories, ToolError> {
+        check_category(&category)?;
+        let memories = if category == "*" {
+            self.retrieve_all(is_global)
+        } else {
+            self.retrieve(&category, is_global)
         };
+        let memories = memories.map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(Memories { memories })
+    }
 
-        let is_global = match &args.get("is_global") {
-            // Default to false if no is_global flag is provided
-            Some(Value::Bool(b)) => *b,
-            Some(Value::String(s)) => s.to_lowercase() == "true",
-            None => false,
-            _ => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "is_global must be a boolean or string 'true'/'false'",
-                ))
-            }
-        };
+    /// Removes all memories within a specified category
+    #[tool(
+        params(
+            category = "The category to remove, '*' for all of them",
+            is_global = "Whether to remove the memories of all projects rather than this one"
+        ),
+        idempotent_hint = true,
+        open_world_hint = false
+    )]
+    fn remove_memory_category(
+        &self,
+        category: String,
+        is_global: bool,
+    ) -> Result<String, ToolError> {
+        check_category(&category)?;
+        if category == "*" {
+            self.clear_all_global_or_local_memories(is_global)
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            Ok(format!(
+                "Cleared all memory {} categories",
+                if is_global { "global" } else { "local" }
+            ))
+        } else {
+            self.clear_memory(&category, is_global)
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+            Ok(format!("Cleared memories in category: {}", category))
+        }
+    }
 
-        Ok(Self {
-            category,
-            data,
-            tags,
-            is_global,
-        })
+    /// Removes a specific memory within a specified category
+    #[tool(
+        name = "remove_specific_memory",
+        params(
+            category = "The category of the memory",
+            memory_content = "Text of the memory to remove, every memory containing it is removed",
+            is_global = "Whether the memory is kept for all projects rather than this one"
+        ),
+        idempotent_hint = true,
+        open_world_hint = false
+    )]
+    fn remove_specific_memory_tool(
+        &self,
+        category: String,
+        memory_content: String,
+        is_global: bool,
+    ) -> Result<String, ToolError> {
+        check_category(&category)?;
+        self.remove_specific_memory(&category, &memory_content, is_global)
+            .map_err(|e| ToolError::ExecutionError(e.to_string()))?;
+        Ok(format!(
+            "Removed specific memory from category: {}",
+            category
+        ))
     }
 }
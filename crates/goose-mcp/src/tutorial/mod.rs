@@ -1,7 +1,6 @@
 use anyhow::Result;
 use include_dir::{include_dir, Dir};
 use indoc::formatdoc;
-use serde_json::{json, Value};
 use std::{future::Future, pin::Pin};
 
 use mcp_core::{
@@ -10,8 +9,8 @@ use mcp_core::{
     protocol::ServerCapabilities,
     resource::Resource,
     role::Role,
-    tool::Tool,
 };
+use mcp_macros::router;
 use mcp_server::router::CapabilitiesBuilder;
 use mcp_server::Router;
 
@@ -20,7 +19,6 @@ use mcp_core::content::Content;
 static TUTORIALS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/src/tutorial/tutorials");
 
 pub struct TutorialRouter {
-    tools: Vec<Tool>,
     instructions: String,
 }
 
@@ -32,21 +30,6 @@ impl Default for TutorialRouter {
 
 impl TutorialRouter {
     pub fn new() -> Self {
-        let load_tutorial = Tool::new(
-            "load_tutorial".to_string(),
-            "Load a specific tutorial by name. The tutorial will be returned as markdown content that provides step by step instructions.".to_string(),
-            json!({
-                "type": "object",
-                "required": ["name"],
-                "properties": {
-                    "name": {
-                        "type": "string",
-                        "description": "Name of the tutorial to load, e.g. 'getting-started' or 'developer-mcp'"
-                    }
-                }
-            }),
-        );
-
         // Get base instructions and available tutorials
         let available_tutorials = Self::get_available_tutorials();
 
@@ -68,10 +51,7 @@ impl TutorialRouter {
             tutorials=available_tutorials,
         };
 
-        Self {
-            tools: vec![load_tutorial],
-            instructions,
-        }
+        Self { instructions }
     }
 
     fn get_available_tutorials() -> String {
@@ -89,19 +69,9 @@ impl TutorialRouter {
         }
         tutorials
     }
-
-    async fn load_tutorial(&self, name: &str) -> Result<String, ToolError> {
-        let file_name = format!("{}.md", name);
-        let file = TUTORIALS_DIR
-            .get_file(&file_name)
-            .ok_or(ToolError::ExecutionError(format!(
-                "Could not locate tutorial '{}'",
-                name
-            )))?;
-        Ok(String::from_utf8_lossy(file.contents()).into_owned())
-    }
 }
 
+#[router]
 impl Router for TutorialRouter {
     fn name(&self) -> String {
         "tutorial".to_string()
@@ -115,38 +85,6 @@ impl Router for TutorialRouter {
         CapabilitiesBuilder::new().with_tools(false).build()
     }
 
-    fn list_tools(&self) -> Vec<Tool> {
-        self.tools.clone()
-    }
-
-    fn call_tool(
-        &self,
-        tool_name: &str,
-        arguments: Value,
-    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
-        let this = self.clone();
-        let tool_name = tool_name.to_string();
-
-        Box::pin(async move {
-            match tool_name.as_str() {
-                "load_tutorial" => {
-                    let name = arguments
-                        .get("name")
-                        .and_then(|v| v.as_str())
-                        .ok_or_else(|| {
-                            ToolError::InvalidParameters("Missing 'name' parameter".to_string())
-                        })?;
-
-                    let content = this.load_tutorial(name).await?;
-                    Ok(vec![
-                        Content::text(content).with_audience(vec![Role::Assistant])
-                    ])
-                }
-                _ => Err(ToolError::NotFound(format!("Tool {} not found", tool_name))),
-            }
-        })
-    }
-
     fn list_resources(&self) -> Vec<Resource> {
         Vec::new()
     }
@@ -174,12 +112,31 @@ impl Router for TutorialRouter {
             )))
         })
     }
+
+    /// Load a specific tutorial by name. The tutorial will be returned as markdown content that provides step by step instructions.
+    #[tool(
+        params(name = "Name of the tutorial to load, e.g. 'getting-started' or 'developer-mcp'"),
+        read_only_hint = true,
+        open_world_hint = false
+    )]
+    fn load_tutorial(&self, name: String) -> Result<Vec<Content>, ToolError> {
+        let file_name = format!("{}.md", name);
+        let file = TUTORIALS_DIR
+            .get_file(&file_name)
+            .ok_or(ToolError::ExecutionError(format!(
+                "Could not locate tutorial '{}'",
+                name
+            )))?;
+        let content = String::from_utf8_lossy(file.contents()).into_owned();
+        Ok(vec![
+            Content::text(content).with_audience(vec![Role::Assistant])
+        ])
+    }
 }
 
 impl Clone for TutorialRouter {
     fn clone(&self) -> Self {
         Self {
-            tools: self.tools.clone(),
             instructions: self.instructions.clone(),
         }
     }
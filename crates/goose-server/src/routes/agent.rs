@@ -10,6 +10,7 @@ use goose::{agents::AgentFactory, model::ModelConfig, providers};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::env;
+use std::sync::Arc;
 
 #[derive(Serialize)]
 struct VersionsResponse {
@@ -132,7 +133,14 @@ async fn create_agent(
         .version
         .unwrap_or_else(|| AgentFactory::default_version().to_string());
 
-    let new_agent = AgentFactory::create(&version, provider).expect("Failed to create agent");
+    let mut new_agent = AgentFactory::create(&version, provider).expect("Failed to create agent");
+
+    // Builtin extensions run in this process, unless configured to run as their own process
+    new_agent
+        .set_builtin_extensions(Arc::new(|name, peer| {
+            Box::pin(async move { goose_mcp::builtin_router(&name, peer).await })
+        }))
+        .await;
 
     let mut agent = state.agent.write().await;
     *agent = Some(new_agent);
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use crate::state::AppState;
 use axum::{extract::State, routing::post, Json, Router};
 use goose::{
-    agents::{extension::Envs, ExtensionConfig},
+    agents::{
+        extension::{Envs, ExtensionAuth},
+        ExtensionConfig,
+    },
     config::Config,
 };
 use http::{HeaderMap, StatusCode};
@@ -20,9 +23,16 @@ enum ExtensionConfigRequest {
         name: String,
         /// The URI endpoint for the SSE extension.
         uri: String,
-        /// List of environment variable keys. The server will fetch their values from the keyring.
+        /// Not supported, remote extensions get no environment. Requests with keys are
+        /// rejected rather than having them ignored.
         #[serde(default)]
         env_keys: Vec<String>,
+        /// HTTP headers sent with every request to the extension.
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// How to authenticate to the extension, e.g. with a bearer token from the keyring.
+        #[serde(default)]
+        auth: Option<ExtensionAuth>,
         timeout: Option<u64>,
     },
     /// Standard I/O (stdio) extension.
@@ -87,26 +97,17 @@ async fn add_extension(
             name,
             uri,
             env_keys,
+            headers,
+            auth,
             timeout,
         } => {
-            let mut env_map = HashMap::new();
-            for key in env_keys {
-                match config.get_secret(&key) {
-                    Ok(value) => {
-                        env_map.insert(key, value);
-                    }
-                    Err(_) => {
-                        missing_keys.push(key);
-                    }
-                }
-            }
-
-            if !missing_keys.is_empty() {
+            // Remote extensions get no environment, credentials go in headers or auth
+            if !env_keys.is_empty() {
                 return Ok(Json(ExtensionResponse {
                     error: true,
                     message: Some(format!(
-                        "Missing secrets for keys: {}",
-                        missing_keys.join(", ")
+                        "Remote extensions do not take environment variables ({}), use headers or auth instead",
+                        env_keys.join(", ")
                     )),
                 }));
             }
@@ -114,7 +115,9 @@ async fn add_extension(
             ExtensionConfig::Sse {
                 name,
                 uri,
-                envs: Envs::new(env_map),
+                envs: Envs::default(),
+                headers,
+                auth,
                 timeout,
             }
         }
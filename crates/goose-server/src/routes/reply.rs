@@ -184,7 +184,28 @@ async fn handler(
 
         // Collect all messages for storage
         let mut all_messages = messages.clone();
-        let session_path = session::get_path(session::Identifier::Name(session_id.clone()));
+        let stored =
+            match session::StoredSession::open(session::Identifier::Name(session_id.clone())) {
+                Ok(stored) => stored,
+                Err(e) => {
+                    tracing::error!("Failed to open session: {:?}", e);
+                    let _ = stream_event(
+                        MessageEvent::Error {
+                            error: e.to_string(),
+                        },
+                        &tx,
+                    )
+                    .await;
+                    let _ = stream_event(
+                        MessageEvent::Finish {
+                            reason: "error".to_string(),
+                        },
+                        &tx,
+                    )
+                    .await;
+                    return;
+                }
+            };
 
         loop {
             tokio::select! {
@@ -204,12 +225,16 @@ async fn handler(
                             }
 
                             // Store messages and generate description in background
-                            let session_path = session_path.clone();
+                            let stored = stored.clone();
                             let messages = all_messages.clone();
                             let provider = provider.clone();
                             tokio::spawn(async move {
-                                if let Err(e) = session::persist_messages(&session_path, &messages, Some(provider)).await {
-                                    tracing::error!("Failed to store session history: {:?}", e);
+                                match stored.persist(&messages, Some(provider)).await {
+                                    Ok(redactions) if !redactions.is_empty() => {
+                                        tracing::info!("Redacted {} from session history", redactions);
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => tracing::error!("Failed to store session history: {:?}", e),
                                 }
                             });
                         }
@@ -343,16 +368,23 @@ async fn ask_handler(
         all_messages.push(response_message);
     }
 
-    // Get the session path - file will be created when needed
-    let session_path = session::get_path(session::Identifier::Name(session_id.clone()));
+    // Get the session - it will be created when needed
+    let stored = session::StoredSession::open(session::Identifier::Name(session_id.clone()))
+        .map_err(|e| {
+            tracing::error!("Failed to open session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
     // Store messages and generate description in background
-    let session_path = session_path.clone();
     let messages = all_messages.clone();
     let provider = provider.clone();
     tokio::spawn(async move {
-        if let Err(e) = session::persist_messages(&session_path, &messages, Some(provider)).await {
-            tracing::error!("Failed to store session history: {:?}", e);
+        match stored.persist(&messages, Some(provider)).await {
+            Ok(redactions) if !redactions.is_empty() => {
+                tracing::info!("Redacted {} from session history", redactions);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::error!("Failed to store session history: {:?}", e),
         }
     });
 
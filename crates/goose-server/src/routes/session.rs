@@ -1,13 +1,14 @@
 use crate::state::AppState;
 use axum::{
-    extract::{Path, State},
-    http::{HeaderMap, StatusCode},
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
     routing::get,
     Json, Router,
 };
 use goose::message::Message;
 use goose::session;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Serialize)]
 struct SessionInfo {
@@ -29,6 +30,40 @@ struct SessionHistoryResponse {
     messages: Vec<Message>,
 }
 
+#[derive(Deserialize)]
+struct UpdateSessionRequest {
+    /// A new name for the session
+    name: Option<String>,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    archived: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct UpdateSessionResponse {
+    session_id: String,
+    metadata: session::SessionMetadata,
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    query: String,
+    limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+struct SearchResponse {
+    hits: Vec<session::SearchHit>,
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    format: Option<String>,
+    #[serde(default)]
+    thinking: bool,
+    max_result_length: Option<usize>,
+}
+
 // List all available sessions
 async fn list_sessions(
     State(state): State<AppState>,
@@ -44,7 +79,7 @@ async fn list_sessions(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    let sessions = match session::list_sessions() {
+    let sessions = match session::default_store().and_then(|store| store.list()) {
         Ok(sessions) => sessions,
         Err(e) => {
             tracing::error!("Failed to list sessions: {:?}", e);
@@ -54,27 +89,11 @@ async fn list_sessions(
 
     let session_infos = sessions
         .into_iter()
-        .map(|(id, path)| {
-            // Get last modified time as string
-            let modified = path
-                .metadata()
-                .and_then(|m| m.modified())
-                .map(|time| {
-                    chrono::DateTime::<chrono::Utc>::from(time)
-                        .format("%Y-%m-%d %H:%M:%S UTC")
-                        .to_string()
-                })
-                .unwrap_or_else(|_| "Unknown".to_string());
-
-            // Get session description
-            let metadata = session::read_metadata(&path).expect("Failed to read session metadata");
-
-            SessionInfo {
-                id,
-                path: path.to_string_lossy().to_string(),
-                modified,
-                metadata,
-            }
+        .map(|info| SessionInfo {
+            id: info.id,
+            path: info.path.to_string_lossy().to_string(),
+            modified: info.modified.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            metadata: info.metadata,
         })
         .collect();
 
@@ -99,12 +118,12 @@ async fn get_session_history(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    let session_path = session::get_path(session::Identifier::Name(session_id.clone()));
+    let stored = open_existing(session_id.clone())?;
 
     // Read metadata
-    let metadata = session::read_metadata(&session_path).map_err(|_| StatusCode::NOT_FOUND)?;
+    let metadata = stored.read_metadata().map_err(|_| StatusCode::NOT_FOUND)?;
 
-    let messages = match session::read_messages(&session_path) {
+    let messages = match stored.read_messages() {
         Ok(messages) => messages,
         Err(e) => {
             tracing::error!("Failed to read session messages: {:?}", e);
@@ -119,10 +138,218 @@ async fn get_session_history(
     }))
 }
 
+// Open an existing session of the default store, rejecting ids that are not session names
+fn open_existing(session_id: String) -> Result<session::StoredSession, StatusCode> {
+    if session::validate_session_id(&session_id).is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let stored =
+        session::StoredSession::open(session::Identifier::Name(session_id)).map_err(|e| {
+            tracing::error!("Failed to open session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if !stored.exists().unwrap_or(false) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(stored)
+}
+
+// Delete a session
+async fn delete_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+) -> Result<StatusCode, StatusCode> {
+    // Verify secret key
+    let secret_key = headers
+        .get("X-Secret-Key")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if secret_key != state.secret_key {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let stored = open_existing(session_id)?;
+    stored.delete().map_err(|e| {
+        tracing::error!("Failed to delete session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Rename a session or change its description, tags or whether it is archived
+async fn update_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Json(request): Json<UpdateSessionRequest>,
+) -> Result<Json<UpdateSessionResponse>, StatusCode> {
+    // Verify secret key
+    let secret_key = headers
+        .get("X-Secret-Key")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if secret_key != state.secret_key {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let mut stored = open_existing(session_id)?;
+    if let Some(name) = request.name {
+        if session::validate_session_id(&name).is_err() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        if stored.store().exists(&name).unwrap_or(false) {
+            return Err(StatusCode::CONFLICT);
+        }
+        stored.rename(&name).map_err(|e| {
+            tracing::error!("Failed to rename session: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+
+    let mut metadata = stored
+        .read_metadata()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if let Some(description) = request.description {
+        metadata.description = description;
+    }
+    if let Some(tags) = request.tags {
+        metadata.tags = tags;
+    }
+    if let Some(archived) = request.archived {
+        metadata.archived = archived;
+    }
+    stored.update_metadata(&metadata).map_err(|e| {
+        tracing::error!("Failed to update session metadata: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(Json(UpdateSessionResponse {
+        session_id: stored.id().to_string(),
+        metadata,
+    }))
+}
+
+// Search the messages and descriptions of all sessions
+async fn search_sessions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(search): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, StatusCode> {
+    // Verify secret key
+    let secret_key = headers
+        .get("X-Secret-Key")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if secret_key != state.secret_key {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let limit = search.limit.unwrap_or(20);
+    let hits = match session::default_store().and_then(|store| store.search(&search.query, limit)) {
+        Ok(hits) => hits,
+        Err(e) => {
+            tracing::error!("Failed to search sessions: {:?}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    Ok(Json(SearchResponse { hits }))
+}
+
+// Export a session as a Markdown, HTML or JSON transcript
+async fn export_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(session_id): Path<String>,
+    Query(export): Query<ExportQuery>,
+) -> Result<impl IntoResponse, StatusCode> {
+    // Verify secret key
+    let secret_key = headers
+        .get("X-Secret-Key")
+        .and_then(|value| value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if secret_key != state.secret_key {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let format: session::ExportFormat = export
+        .format
+        .as_deref()
+        .unwrap_or("md")
+        .parse()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let options = session::ExportOptions {
+        thinking: export.thinking,
+        // Zero keeps tool results whole
+        max_result_length: match export.max_result_length {
+            Some(0) => None,
+            Some(length) => Some(length),
+            None => session::ExportOptions::default().max_result_length,
+        },
+    };
+
+    let stored = open_existing(session_id)?;
+
+    let exported = session::export(&stored, format, &options).map_err(|e| {
+        tracing::error!("Failed to export session: {:?}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let content_type = match format {
+        session::ExportFormat::Markdown => "text/markdown; charset=utf-8",
+        session::ExportFormat::Html => "text/html; charset=utf-8",
+        session::ExportFormat::Json => "application/json",
+    };
+
+    Ok(([(header::CONTENT_TYPE, content_type)], exported))
+}
+
 // Configure routes for this module
 pub fn routes(state: AppState) -> Router {
     Router::new()
         .route("/sessions", get(list_sessions))
-        .route("/sessions/:session_id", get(get_session_history))
+        .route("/sessions/search", get(search_sessions))
+        .route(
+            "/sessions/:session_id",
+            get(get_session_history)
+                .delete(delete_session)
+                .patch(update_session),
+        )
+        .route("/sessions/:session_id/export", get(export_session))
         .with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_session_ids_outside_the_session_dir_are_rejected() {
+        // A file the id would reach in the JSONL store, were it not rejected
+        let name = format!("goose-outside-{}", std::process::id());
+        let outside = session::ensure_session_dir()
+            .unwrap()
+            .join(format!("../{}.jsonl", name));
+        std::fs::write(&outside, "{}\n").unwrap();
+
+        let state = AppState::new("test-secret".to_string()).await.unwrap();
+        let request = Request::builder()
+            .uri(format!("/sessions/..%2F{}", name))
+            .method("DELETE")
+            .header("x-secret-key", "test-secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = routes(state).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(outside.exists());
+        std::fs::remove_file(&outside).unwrap();
+    }
+}
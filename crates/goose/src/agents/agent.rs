@@ -8,7 +8,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
 
-use super::extension::{ExtensionConfig, ExtensionResult};
+use super::builtin::BuiltinExtensions;
+use super::extension::{ExtensionConfig, ExtensionResult, ExtensionState};
 use crate::message::Message;
 use crate::providers::base::{Provider, ProviderUsage};
 use crate::session;
@@ -40,9 +41,8 @@ pub trait Agent: Send + Sync {
     /// Remove an extension by name
     async fn remove_extension(&mut self, name: &str);
 
-    /// List all extensions
-    // TODO this needs to also include status so we can tell if extensions are dropped
-    async fn list_extensions(&self) -> Vec<String>;
+    /// List all extensions along with their health
+    async fn list_extensions(&self) -> Vec<ExtensionState>;
 
     /// Pass through a JSON-RPC request to a specific extension
     async fn passthrough(&self, extension: &str, request: Value) -> ExtensionResult<Value>;
@@ -59,6 +59,10 @@ pub trait Agent: Send + Sync {
     /// Override the system prompt with custom text
     async fn override_system_prompt(&mut self, template: String);
 
+    /// Run the builtin extensions added from now on in this process, with their routers
+    /// from `builtins`
+    async fn set_builtin_extensions(&mut self, builtins: BuiltinExtensions);
+
     /// Lists all prompts from all extensions
     async fn list_extension_prompts(&self) -> HashMap<String, Vec<Prompt>>;
 
@@ -66,6 +70,14 @@ pub trait Agent: Send + Sync {
     /// Returns the prompt text that would be used as user input
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult>;
 
+    /// Suggest values for an argument of the named prompt, given what was typed so far
+    async fn complete_prompt_argument(
+        &self,
+        name: &str,
+        argument: &str,
+        value: &str,
+    ) -> Result<Vec<String>>;
+
     /// Get a reference to the provider used by this agent
     async fn provider(&self) -> Arc<Box<dyn Provider>>;
 }
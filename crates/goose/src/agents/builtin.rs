@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use mcp_server::{BoundedService, Peer};
+
+/// Creates the routers of builtin extensions, so they run in this process.
+///
+/// Gets the extension name and the peer to reach the client through, and returns `None`
+/// for names it does not know. Builtin extensions are started as `<current exe> mcp <name>`
+/// processes when an agent has none, see [`crate::agents::Agent::set_builtin_extensions`].
+pub type BuiltinExtensions =
+    Arc<dyn Fn(String, Peer) -> BoxFuture<'static, Option<Box<dyn BoundedService>>> + Send + Sync>;
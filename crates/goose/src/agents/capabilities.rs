@@ -1,22 +1,40 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::{DateTime, TimeZone, Utc};
 use futures::stream::{FuturesUnordered, StreamExt};
-use mcp_client::McpService;
-use mcp_core::protocol::GetPromptResult;
+use mcp_core::protocol::{
+    CallToolResult, CompletionArgument, CompletionReference, GetPromptResult, JsonRpcNotification,
+    LoggingLevel, LoggingMessageNotification, ResourceUpdatedNotification, Root, RootsCapability,
+    SUPPORTED_PROTOCOL_VERSIONS,
+};
 use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::LazyLock;
 use std::time::Duration;
-use tokio::sync::Mutex;
-use tracing::{debug, instrument};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{debug, instrument, warn};
 
-use super::extension::{ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult};
+use super::builtin::BuiltinExtensions;
+use super::extension::{
+    ExtensionAuth, ExtensionConfig, ExtensionError, ExtensionInfo, ExtensionResult, ExtensionState,
+};
 use crate::config::Config;
 use crate::prompt_template;
 use crate::providers::base::{Provider, ProviderUsage};
-use mcp_client::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
-use mcp_client::transport::{SseTransport, StdioTransport, Transport};
+use crate::providers::oauth;
+use crate::providers::redacting::RedactingProvider;
+use crate::redaction::Redactor;
+use mcp_client::client::{BoxError, ClientCapabilities, ClientInfo, Error as ClientError};
+use mcp_client::manager::{connect, Connection, Connector, ManagerError, McpClientManager};
+use mcp_client::roots::Roots;
+use mcp_client::transport::{
+    Error as TransportError, HeaderSource, InProcessTransport, SseTransport, StdioTransport,
+    Transport,
+};
+use mcp_core::tool::ArgumentValidator;
 use mcp_core::{prompt::Prompt, Content, Tool, ToolCall, ToolError, ToolResult};
+use mcp_server::{Peer, Server};
 use serde_json::Value;
 
 // By default, we set it to Jan 1, 2020 if the resource does not have a timestamp
@@ -24,17 +42,43 @@ use serde_json::Value;
 static DEFAULT_TIMESTAMP: LazyLock<DateTime<Utc>> =
     LazyLock::new(|| Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap());
 
-type McpClientBox = Arc<Mutex<Box<dyn McpClientTrait>>>;
+/// Contents of resources read from subscribe-capable extensions, keyed by extension then uri
+type ResourceCache = Arc<Mutex<HashMap<String, HashMap<String, CachedResource>>>>;
+
+/// The last read contents of an active resource
+#[derive(Clone)]
+struct CachedResource {
+    timestamp: DateTime<Utc>,
+    contents: Vec<(String, String)>, // (uri, content) pairs returned by resources/read
+    stale: bool,                     // set when the extension reports the resource updated
+}
 
 /// Manages MCP clients and their interactions
 pub struct Capabilities {
-    clients: HashMap<String, McpClientBox>,
+    manager: McpClientManager,
     instructions: HashMap<String, String>,
     resource_capable_extensions: HashSet<String>,
+    resource_subscribable_extensions: HashSet<String>,
+    // Extensions whose tool annotations are relied on, builtins and those the user trusts
+    trusted_extensions: HashSet<String>,
+    resource_cache: ResourceCache,
+    // Input schemas of the prefixed tools as last listed, used to validate calls before dispatch
+    tool_schemas: HashMap<String, ArgumentValidator>,
     provider: Arc<Box<dyn Provider>>,
     provider_usage: Mutex<Vec<ProviderUsage>>,
     system_prompt_override: Option<String>,
     system_prompt_extensions: Vec<String>,
+    // Creates the builtin extensions that run in this process
+    builtins: Option<BuiltinExtensions>,
+}
+
+/// The result of a tool the agent provides itself rather than an extension
+fn platform_tool_result(content: Vec<Content>) -> CallToolResult {
+    CallToolResult {
+        content,
+        is_error: None,
+        structured_content: None,
+    }
 }
 
 /// A flattened representation of a resource used by the agent to prepare inference
@@ -70,6 +114,337 @@ impl ResourceItem {
     }
 }
 
+/// Re-emit the `notifications/message` logs of an extension into goose's tracing,
+/// so they end up in the same log files and tracing layers as goose's own logs
+fn forward_extension_logs(
+    extension: String,
+    mut notifications: broadcast::Receiver<JsonRpcNotification>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(extension = %extension, skipped, "dropped extension log messages");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            if notification.method != "notifications/message" {
+                continue;
+            }
+
+            let Some(log) = notification.params.and_then(|params| {
+                serde_json::from_value::<LoggingMessageNotification>(params).ok()
+            }) else {
+                continue;
+            };
+
+            let logger = log.logger.unwrap_or_default();
+            let message = match log.data {
+                Value::String(text) => text,
+                data => data.to_string(),
+            };
+
+            match log.level {
+                LoggingLevel::Debug => {
+                    tracing::debug!(extension = %extension, logger = %logger, "{}", message)
+                }
+                LoggingLevel::Info | LoggingLevel::Notice => {
+                    tracing::info!(extension = %extension, logger = %logger, "{}", message)
+                }
+                LoggingLevel::Warning => {
+                    tracing::warn!(extension = %extension, logger = %logger, "{}", message)
+                }
+                LoggingLevel::Error
+                | LoggingLevel::Critical
+                | LoggingLevel::Alert
+                | LoggingLevel::Emergency => {
+                    tracing::error!(extension = %extension, logger = %logger, "{}", message)
+                }
+            }
+        }
+    });
+}
+
+/// Mark cached resources of an extension stale as it reports them updated, so that
+/// `get_resources` only re-reads what changed
+fn track_resource_updates(
+    extension: String,
+    cache: ResourceCache,
+    mut notifications: broadcast::Receiver<JsonRpcNotification>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(_)) => {
+                    // We may have missed updates, so nothing cached can be trusted
+                    if let Some(resources) = cache.lock().await.get_mut(&extension) {
+                        resources.values_mut().for_each(|r| r.stale = true);
+                    }
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            if notification.method != "notifications/resources/updated" {
+                continue;
+            }
+
+            let Some(updated) = notification.params.and_then(|params| {
+                serde_json::from_value::<ResourceUpdatedNotification>(params).ok()
+            }) else {
+                continue;
+            };
+
+            if let Some(resource) = cache
+                .lock()
+                .await
+                .get_mut(&extension)
+                .and_then(|resources| resources.get_mut(&updated.uri))
+            {
+                resource.stale = true;
+            }
+        }
+    });
+}
+
+/// Seconds between pings checking extensions are alive, unless configured through
+/// `GOOSE_EXTENSION_PING_INTERVAL` (0 turns pinging off)
+const DEFAULT_PING_INTERVAL: u64 = 30;
+
+/// The headers to connect to a remote extension with, including its credentials
+async fn sse_headers(
+    name: &str,
+    uri: &str,
+    headers: &HashMap<String, String>,
+    auth: Option<&ExtensionAuth>,
+) -> ExtensionResult<HashMap<String, String>> {
+    let token = match auth {
+        None => return Ok(headers.clone()),
+        Some(ExtensionAuth::Bearer { secret }) => Config::global()
+            .get_secret::<String>(secret)
+            .map_err(|e| ExtensionError::Authentication(name.to_string(), e.to_string()))?,
+        Some(ExtensionAuth::OAuth {
+            client_id,
+            redirect_url,
+            scopes,
+        }) => oauth::get_mcp_oauth_token_async(uri, client_id, redirect_url, scopes)
+            .await
+            .map_err(|e| ExtensionError::Authentication(name.to_string(), e.to_string()))?,
+    };
+
+    let mut headers = headers.clone();
+    headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+    Ok(headers)
+}
+
+/// The headers of a remote extension authenticating with OAuth, fetched again once its
+/// access token expires
+struct OAuthHeaders {
+    name: String,
+    uri: String,
+    headers: HashMap<String, String>,
+    auth: ExtensionAuth,
+}
+
+#[async_trait]
+impl HeaderSource for OAuthHeaders {
+    async fn headers(&self) -> Result<HashMap<String, String>, TransportError> {
+        sse_headers(&self.name, &self.uri, &self.headers, Some(&self.auth))
+            .await
+            .map_err(|e| TransportError::SseConnection(e.to_string()))
+    }
+}
+
+/// Start the extension described by `config` and initialize a client for it
+async fn connect_extension(
+    config: &ExtensionConfig,
+    roots: &Roots,
+    builtins: Option<&BuiltinExtensions>,
+) -> ExtensionResult<Connection> {
+    let request_timeout = |timeout: &Option<u64>| {
+        Duration::from_secs(timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT))
+    };
+    let connected = match config {
+        ExtensionConfig::Sse {
+            name,
+            uri,
+            headers,
+            auth,
+            timeout,
+            ..
+        } => {
+            let mut transport =
+                SseTransport::new(uri, sse_headers(name, uri, headers, auth.as_ref()).await?);
+            if let Some(auth @ ExtensionAuth::OAuth { .. }) = auth {
+                transport = transport.with_header_source(Arc::new(OAuthHeaders {
+                    name: name.clone(),
+                    uri: uri.clone(),
+                    headers: headers.clone(),
+                    auth: auth.clone(),
+                }));
+            }
+            connect(
+                transport.start().await?,
+                roots,
+                request_timeout(timeout),
+                client_info(),
+                client_capabilities(),
+            )
+            .await
+        }
+        ExtensionConfig::Stdio {
+            cmd,
+            args,
+            envs,
+            timeout,
+            ..
+        } => {
+            let transport = StdioTransport::new(cmd, args.to_vec(), envs.get_env());
+            connect(
+                transport.start().await?,
+                roots,
+                request_timeout(timeout),
+                client_info(),
+                client_capabilities(),
+            )
+            .await
+        }
+        ExtensionConfig::Builtin { name, timeout } => {
+            let isolated = Config::global()
+                .get::<bool>("GOOSE_BUILTIN_PROCESS_ISOLATION")
+                .unwrap_or(false);
+            let (peer, messages) = Peer::new();
+            let router = match builtins {
+                Some(builtins) if !isolated => builtins(name.clone(), peer.clone()).await,
+                _ => None,
+            };
+
+            match router {
+                Some(router) => {
+                    let (to_server, from_server) =
+                        Server::new(router).with_peer(peer, messages).spawn();
+                    let transport = InProcessTransport::new(to_server, from_server);
+                    connect(
+                        transport.start().await?,
+                        roots,
+                        request_timeout(timeout),
+                        client_info(),
+                        client_capabilities(),
+                    )
+                    .await
+                }
+                None => {
+                    // Run the current executable with mcp and extension name
+                    let cmd = std::env::current_exe()
+                        .expect("should find the current executable")
+                        .to_str()
+                        .expect("should resolve executable to string path")
+                        .to_string();
+                    let transport = StdioTransport::new(
+                        &cmd,
+                        vec!["mcp".to_string(), name.clone()],
+                        HashMap::new(),
+                    );
+                    connect(
+                        transport.start().await?,
+                        roots,
+                        request_timeout(timeout),
+                        client_info(),
+                        client_capabilities(),
+                    )
+                    .await
+                }
+            }
+        }
+    };
+
+    connected.map_err(|e| match e {
+        ClientError::UnsupportedProtocolVersion(version) => {
+            ExtensionError::UnsupportedProtocolVersion(
+                config.name().to_string(),
+                version,
+                SUPPORTED_PROTOCOL_VERSIONS.join(", "),
+            )
+        }
+        e => ExtensionError::Initialization(Box::new(config.clone()), e),
+    })
+}
+
+fn client_info() -> ClientInfo {
+    ClientInfo {
+        name: "goose".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    }
+}
+
+// Initialize clients with default capabilities
+fn client_capabilities() -> ClientCapabilities {
+    ClientCapabilities {
+        roots: Some(RootsCapability {
+            list_changed: Some(true),
+        }),
+    }
+}
+
+/// Track the resource updates of an extension and forward its logs, as far as it supports them
+async fn watch_extension(name: &str, connected: &Connection, cache: &ResourceCache) {
+    let capabilities = &connected.init_result.capabilities;
+
+    let subscribe = capabilities
+        .resources
+        .as_ref()
+        .and_then(|r| r.subscribe)
+        .unwrap_or(false);
+    if subscribe {
+        track_resource_updates(
+            name.to_string(),
+            Arc::clone(cache),
+            connected.notifications.resubscribe(),
+        );
+    }
+
+    // Extensions that support logging get their logs forwarded into our tracing
+    if capabilities.logging.is_some() {
+        forward_extension_logs(name.to_string(), connected.notifications.resubscribe());
+
+        let level = Config::global()
+            .get::<LoggingLevel>("GOOSE_EXTENSION_LOG_LEVEL")
+            .unwrap_or(LoggingLevel::Info);
+        if let Err(e) = connected.client.set_logging_level(level).await {
+            warn!(extension = %name, error = %e, "failed to set extension log level");
+        }
+    }
+}
+
+/// Starts an extension again for the manager once its process died
+struct ExtensionConnector {
+    name: String,
+    config: ExtensionConfig,
+    roots: Roots,
+    builtins: Option<BuiltinExtensions>,
+    cache: ResourceCache,
+}
+
+#[async_trait]
+impl Connector for ExtensionConnector {
+    async fn connect(&self) -> Result<Connection, BoxError> {
+        let connected =
+            connect_extension(&self.config, &self.roots, self.builtins.as_ref()).await?;
+        // The new process knows nothing of our subscriptions, read everything again
+        self.cache.lock().await.remove(&self.name);
+        watch_extension(&self.name, &connected, &self.cache).await;
+        Ok(connected)
+    }
+
+    fn restartable(&self) -> bool {
+        !matches!(self.config, ExtensionConfig::Sse { .. })
+    }
+}
+
 /// Sanitizes a string by replacing invalid characters with underscores.
 /// Valid characters match [a-zA-Z0-9_-]
 fn normalize(input: String) -> String {
@@ -86,15 +461,48 @@ fn normalize(input: String) -> String {
 
 impl Capabilities {
     /// Create a new Capabilities with the specified provider
+    ///
+    /// With `GOOSE_REDACT_PROVIDER_REQUESTS` set, secrets are redacted from everything sent
+    /// to the provider.
     pub fn new(provider: Box<dyn Provider>) -> Self {
+        let config = Config::global();
+        let ping_interval = config
+            .get::<u64>("GOOSE_EXTENSION_PING_INTERVAL")
+            .unwrap_or(DEFAULT_PING_INTERVAL);
+        let redact_requests = config
+            .get::<bool>("GOOSE_REDACT_PROVIDER_REQUESTS")
+            .unwrap_or(false);
+        let provider: Box<dyn Provider> = match Redactor::global() {
+            Some(redactor) if redact_requests => {
+                Box::new(RedactingProvider::new(provider, redactor))
+            }
+            _ => provider,
+        };
+        // Until a session tells us otherwise, extensions work in our current directory
+        let roots = Roots::new(
+            std::env::current_dir()
+                .ok()
+                .and_then(|dir| Root::from_path(&dir))
+                .into_iter()
+                .collect(),
+        );
+        let manager = McpClientManager::new(client_info(), client_capabilities())
+            .with_roots(roots)
+            .with_ping_interval((ping_interval > 0).then(|| Duration::from_secs(ping_interval)));
+
         Self {
-            clients: HashMap::new(),
+            manager,
             instructions: HashMap::new(),
             resource_capable_extensions: HashSet::new(),
+            resource_subscribable_extensions: HashSet::new(),
+            trusted_extensions: HashSet::new(),
+            resource_cache: Arc::new(Mutex::new(HashMap::new())),
+            tool_schemas: HashMap::new(),
             provider: Arc::new(provider),
             provider_usage: Mutex::new(Vec::new()),
             system_prompt_override: None,
             system_prompt_extensions: Vec::new(),
+            builtins: None,
         }
     }
 
@@ -105,78 +513,28 @@ impl Capabilities {
     /// Add a new MCP extension based on the provided client type
     // TODO IMPORTANT need to ensure this times out if the extension command is broken!
     pub async fn add_extension(&mut self, config: ExtensionConfig) -> ExtensionResult<()> {
-        let mut client: Box<dyn McpClientTrait> = match &config {
-            ExtensionConfig::Sse {
-                uri, envs, timeout, ..
-            } => {
-                let transport = SseTransport::new(uri, envs.get_env());
-                let handle = transport.start().await?;
-                let service = McpService::with_timeout(
-                    handle,
-                    Duration::from_secs(
-                        timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
-                    ),
-                );
-                Box::new(McpClient::new(service))
-            }
-            ExtensionConfig::Stdio {
-                cmd,
-                args,
-                envs,
-                timeout,
-                ..
-            } => {
-                let transport = StdioTransport::new(cmd, args.to_vec(), envs.get_env());
-                let handle = transport.start().await?;
-                let service = McpService::with_timeout(
-                    handle,
-                    Duration::from_secs(
-                        timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
-                    ),
-                );
-                Box::new(McpClient::new(service))
-            }
-            ExtensionConfig::Builtin { name, timeout } => {
-                // For builtin extensions, we run the current executable with mcp and extension name
-                let cmd = std::env::current_exe()
-                    .expect("should find the current executable")
-                    .to_str()
-                    .expect("should resolve executable to string path")
-                    .to_string();
-                let transport = StdioTransport::new(
-                    &cmd,
-                    vec!["mcp".to_string(), name.clone()],
-                    HashMap::new(),
-                );
-                let handle = transport.start().await?;
-                let service = McpService::with_timeout(
-                    handle,
-                    Duration::from_secs(
-                        timeout.unwrap_or(crate::config::DEFAULT_EXTENSION_TIMEOUT),
-                    ),
-                );
-                Box::new(McpClient::new(service))
-            }
-        };
-
-        // Initialize the client with default capabilities
-        let info = ClientInfo {
-            name: "goose".to_string(),
-            version: env!("CARGO_PKG_VERSION").to_string(),
-        };
-        let capabilities = ClientCapabilities::default();
-
-        let init_result = client
-            .initialize(info, capabilities)
-            .await
-            .map_err(|e| ExtensionError::Initialization(config.clone(), e))?;
+        let connected =
+            connect_extension(&config, self.manager.roots(), self.builtins.as_ref()).await?;
+        let init_result = &connected.init_result;
 
         let sanitized_name = normalize(config.name().to_string());
 
         // Store instructions if provided
-        if let Some(instructions) = init_result.instructions {
+        if let Some(instructions) = &init_result.instructions {
             self.instructions
-                .insert(sanitized_name.clone(), instructions);
+                .insert(sanitized_name.clone(), instructions.clone());
+        }
+
+        // Extensions that can notify us of resource updates let us cache resource contents
+        let subscribe = init_result
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|r| r.subscribe)
+            .unwrap_or(false);
+        if subscribe {
+            self.resource_subscribable_extensions
+                .insert(sanitized_name.clone());
         }
 
         // if the server is capable if resources we track it
@@ -185,18 +543,68 @@ impl Capabilities {
                 .insert(sanitized_name.clone());
         }
 
-        // Store the client using the provided name
-        self.clients
-            .insert(sanitized_name.clone(), Arc::new(Mutex::new(client)));
+        // Annotations are only hints, any extension could claim its tools are read-only
+        let trusted: Vec<String> = Config::global()
+            .get("GOOSE_TRUSTED_EXTENSIONS")
+            .unwrap_or_default();
+        if matches!(config, ExtensionConfig::Builtin { .. })
+            || trusted
+                .iter()
+                .any(|name| normalize(name.clone()) == sanitized_name)
+        {
+            self.trusted_extensions.insert(sanitized_name.clone());
+        }
+
+        watch_extension(&sanitized_name, &connected, &self.resource_cache).await;
+
+        let connector = ExtensionConnector {
+            name: sanitized_name.clone(),
+            config,
+            roots: self.manager.roots().clone(),
+            builtins: self.builtins.clone(),
+            cache: Arc::clone(&self.resource_cache),
+        };
+        self.manager
+            .add_server(sanitized_name, connected, Some(Arc::new(connector)))
+            .await;
 
         Ok(())
     }
 
+    /// Expose `dirs` to extensions as their roots, letting them know if they changed
+    pub async fn set_working_dirs(&self, dirs: &[PathBuf]) {
+        let roots = dirs.iter().filter_map(|dir| Root::from_path(dir)).collect();
+        if !self.manager.roots().set(roots) {
+            return;
+        }
+
+        for (name, client) in self.manager.clients() {
+            if let Err(e) = client.lock().await.notify_roots_list_changed().await {
+                warn!(extension = %name, error = %e, "failed to notify extension of changed roots");
+            }
+        }
+    }
+
+    /// Whether the annotations of a prefixed tool can be relied on, which is the case for
+    /// builtin extensions and those listed in `GOOSE_TRUSTED_EXTENSIONS`
+    pub fn trusts_annotations(&self, tool_name: &str) -> bool {
+        // the extension the tool is routed to, as names may contain the separator
+        self.manager
+            .client_for_tool(tool_name)
+            .is_some_and(|(extension, _)| self.trusted_extensions.contains(extension))
+    }
+
     /// Add a system prompt extension
     pub fn add_system_prompt_extension(&mut self, extension: String) {
         self.system_prompt_extensions.push(extension);
     }
 
+    /// Run the builtin extensions added from now on in this process, with their routers
+    /// from `builtins`
+    pub fn set_builtin_extensions(&mut self, builtins: BuiltinExtensions) {
+        self.builtins = Some(builtins);
+    }
+
     /// Override the system prompt with custom text
     pub fn set_system_prompt_override(&mut self, template: String) {
         self.system_prompt_override = Some(template);
@@ -217,14 +625,28 @@ impl Capabilities {
     pub async fn remove_extension(&mut self, name: &str) -> ExtensionResult<()> {
         let sanitized_name = normalize(name.to_string());
 
-        self.clients.remove(&sanitized_name);
+        self.manager.remove_server(&sanitized_name).await;
         self.instructions.remove(&sanitized_name);
         self.resource_capable_extensions.remove(&sanitized_name);
+        self.resource_subscribable_extensions
+            .remove(&sanitized_name);
+        self.trusted_extensions.remove(&sanitized_name);
+        self.resource_cache.lock().await.remove(&sanitized_name);
+        let prefix = format!("{}__", sanitized_name);
+        self.tool_schemas
+            .retain(|name, _| !name.starts_with(&prefix));
         Ok(())
     }
 
-    pub async fn list_extensions(&self) -> ExtensionResult<Vec<String>> {
-        Ok(self.clients.keys().cloned().collect())
+    /// List the extensions along with their health
+    pub async fn list_extensions(&self) -> ExtensionResult<Vec<ExtensionState>> {
+        Ok(self
+            .manager
+            .statuses()
+            .await
+            .into_iter()
+            .map(|(name, status)| ExtensionState { name, status })
+            .collect())
     }
 
     pub async fn get_usage(&self) -> Vec<ProviderUsage> {
@@ -252,39 +674,50 @@ impl Capabilities {
 
     /// Get all tools from all clients with proper prefixing
     pub async fn get_prefixed_tools(&mut self) -> ExtensionResult<Vec<Tool>> {
-        let mut tools = Vec::new();
-        for (name, client) in &self.clients {
-            let client_guard = client.lock().await;
-            let mut client_tools = client_guard.list_tools(None).await?;
-
-            loop {
-                for tool in client_tools.tools {
-                    tools.push(Tool::new(
-                        format!("{}__{}", name, tool.name),
-                        &tool.description,
-                        tool.input_schema,
-                    ));
-                }
-
-                // exit loop when there are no more pages
-                if client_tools.next_cursor.is_none() {
-                    break;
-                }
-
-                client_tools = client_guard.list_tools(client_tools.next_cursor).await?;
-            }
-        }
+        let tools = self.manager.list_tools().await?;
+        // Schemas are only compiled again when they change
+        let mut schemas = std::mem::take(&mut self.tool_schemas);
+        self.tool_schemas = tools
+            .iter()
+            .map(|tool| {
+                let validator = schemas
+                    .remove(&tool.name)
+                    .filter(|validator| *validator.schema() == tool.input_schema)
+                    .unwrap_or_else(|| ArgumentValidator::new(tool.input_schema.clone()));
+                (tool.name.clone(), validator)
+            })
+            .collect();
         Ok(tools)
     }
 
     /// Get client resources and their contents
+    ///
+    /// Resources of extensions that support subscriptions are only re-read when the extension
+    /// reports them updated or their timestamp changes, other resources are read every time.
     pub async fn get_resources(&self) -> ExtensionResult<Vec<ResourceItem>> {
         let mut result: Vec<ResourceItem> = Vec::new();
 
-        for (name, client) in &self.clients {
+        for (name, client) in self.manager.clients() {
             let client_guard = client.lock().await;
             let resources = client_guard.list_resources(None).await?;
 
+            let cacheable = self.resource_subscribable_extensions.contains(name);
+            // Work on a copy, so updates reported while reading mark the cache rather than wait
+            // for the lock. Clearing the marks tells which updates arrive in the meantime.
+            let mut previous = match cacheable {
+                true => {
+                    let mut cache = self.resource_cache.lock().await;
+                    let cached = cache.entry(name.clone()).or_default();
+                    let previous = cached.clone();
+                    cached
+                        .values_mut()
+                        .for_each(|resource| resource.stale = false);
+                    previous
+                }
+                false => HashMap::new(),
+            };
+            let mut current = HashMap::new();
+
             for resource in resources.resources {
                 // Skip reading the resource if it's not marked active
                 // This avoids blowing up the context with inactive resources
@@ -292,30 +725,80 @@ impl Capabilities {
                     continue;
                 }
 
-                if let Ok(contents) = client_guard.read_resource(&resource.uri).await {
-                    for content in contents.contents {
-                        let (uri, content_str) = match content {
-                            mcp_core::resource::ResourceContents::TextResourceContents {
-                                uri,
-                                text,
-                                ..
-                            } => (uri, text),
-                            mcp_core::resource::ResourceContents::BlobResourceContents {
-                                uri,
-                                blob,
-                                ..
-                            } => (uri, blob),
+                let timestamp = resource.timestamp().unwrap_or(*DEFAULT_TIMESTAMP);
+                let previous_entry = previous.remove(&resource.uri);
+                let subscribed = previous_entry.is_some();
+                let cached =
+                    previous_entry.filter(|cached| !cached.stale && cached.timestamp == timestamp);
+
+                let contents = match cached {
+                    Some(cached) => cached.contents,
+                    None => {
+                        let Ok(contents) = client_guard.read_resource(&resource.uri).await else {
+                            continue;
                         };
+                        if cacheable && !subscribed {
+                            if let Err(e) = client_guard.subscribe_resource(&resource.uri).await {
+                                warn!(extension = %name, uri = %resource.uri, error = %e, "failed to subscribe to resource");
+                            }
+                        }
+                        contents
+                            .contents
+                            .into_iter()
+                            .map(|content| match content {
+                                mcp_core::resource::ResourceContents::TextResourceContents {
+                                    uri,
+                                    text,
+                                    ..
+                                } => (uri, text),
+                                mcp_core::resource::ResourceContents::BlobResourceContents {
+                                    uri,
+                                    blob,
+                                    ..
+                                } => (uri, blob),
+                            })
+                            .collect()
+                    }
+                };
+
+                for (uri, content_str) in &contents {
+                    result.push(ResourceItem::new(
+                        name.clone(),
+                        uri.clone(),
+                        resource.name.clone(),
+                        content_str.clone(),
+                        timestamp,
+                        resource.priority().unwrap_or(0.0),
+                    ));
+                }
 
-                        result.push(ResourceItem::new(
-                            name.clone(),
-                            uri,
-                            resource.name.clone(),
-                            content_str,
-                            resource.timestamp().unwrap_or(*DEFAULT_TIMESTAMP),
-                            resource.priority().unwrap_or(0.0),
-                        ));
+                if cacheable {
+                    current.insert(
+                        resource.uri.clone(),
+                        CachedResource {
+                            timestamp,
+                            contents,
+                            stale: false,
+                        },
+                    );
+                }
+            }
+
+            // Stop following resources that are gone or no longer active
+            for uri in previous.keys() {
+                if let Err(e) = client_guard.unsubscribe_resource(uri).await {
+                    debug!(extension = %name, uri = %uri, error = %e, "failed to unsubscribe from resource");
+                }
+            }
+
+            if cacheable {
+                let mut cache = self.resource_cache.lock().await;
+                // the extension may have been removed while we were reading
+                if let Some(cached) = cache.get_mut(name) {
+                    for (uri, resource) in current.iter_mut() {
+                        resource.stale = cached.get(uri).is_some_and(|cached| cached.stale);
                     }
+                    *cached = current;
                 }
             }
         }
@@ -327,8 +810,8 @@ impl Capabilities {
         let mut context: HashMap<&str, Value> = HashMap::new();
 
         let extensions_info: Vec<ExtensionInfo> = self
-            .clients
-            .keys()
+            .manager
+            .server_names()
             .map(|name| {
                 let instructions = self.instructions.get(name).cloned().unwrap_or_default();
                 let has_resources = self.resource_capable_extensions.contains(name);
@@ -373,14 +856,6 @@ impl Capabilities {
         }
     }
 
-    /// Find and return a reference to the appropriate client for a tool call
-    fn get_client_for_tool(&self, prefixed_name: &str) -> Option<(&str, McpClientBox)> {
-        self.clients
-            .iter()
-            .find(|(key, _)| prefixed_name.starts_with(*key))
-            .map(|(name, client)| (name.as_str(), Arc::clone(client)))
-    }
-
     // Function that gets executed for read_resource tool
     async fn read_resource(&self, params: Value) -> Result<Vec<Content>, ToolError> {
         let uri = params
@@ -412,8 +887,8 @@ impl Capabilities {
 
         // None of the extensions had the resource so we raise an error
         let available_extensions = self
-            .clients
-            .keys()
+            .manager
+            .server_names()
             .map(|s| s.as_str())
             .collect::<Vec<&str>>()
             .join(", ");
@@ -431,8 +906,8 @@ impl Capabilities {
         extension_name: &str,
     ) -> Result<Vec<Content>, ToolError> {
         let available_extensions = self
-            .clients
-            .keys()
+            .manager
+            .server_names()
             .map(|s| s.as_str())
             .collect::<Vec<&str>>()
             .join(", ");
@@ -442,8 +917,8 @@ impl Capabilities {
         );
 
         let client = self
-            .clients
-            .get(extension_name)
+            .manager
+            .client(extension_name)
             .ok_or(ToolError::InvalidParameters(error_msg))?;
 
         let client_guard = client.lock().await;
@@ -468,7 +943,7 @@ impl Capabilities {
         &self,
         extension_name: &str,
     ) -> Result<Vec<Content>, ToolError> {
-        let client = self.clients.get(extension_name).ok_or_else(|| {
+        let client = self.manager.client(extension_name).ok_or_else(|| {
             ToolError::InvalidParameters(format!("Extension {} is not valid", extension_name))
         })?;
 
@@ -546,32 +1021,35 @@ impl Capabilities {
 
     /// Dispatch a single tool call to the appropriate client
     #[instrument(skip(self, tool_call), fields(input, output))]
-    pub async fn dispatch_tool_call(&self, tool_call: ToolCall) -> ToolResult<Vec<Content>> {
+    pub async fn dispatch_tool_call(&self, tool_call: ToolCall) -> ToolResult<CallToolResult> {
         let result = if tool_call.name == "platform__read_resource" {
             // Check if the tool is read_resource and handle it separately
-            self.read_resource(tool_call.arguments.clone()).await
+            self.read_resource(tool_call.arguments.clone())
+                .await
+                .map(platform_tool_result)
         } else if tool_call.name == "platform__list_resources" {
-            self.list_resources(tool_call.arguments.clone()).await
+            self.list_resources(tool_call.arguments.clone())
+                .await
+                .map(platform_tool_result)
         } else {
-            // Else, dispatch tool call based on the prefix naming convention
-            let (client_name, client) = self
-                .get_client_for_tool(&tool_call.name)
-                .ok_or_else(|| ToolError::NotFound(tool_call.name.clone()))?;
-
-            // rsplit returns the iterator in reverse, tool_name is then at 0
-            let tool_name = tool_call
-                .name
-                .strip_prefix(client_name)
-                .and_then(|s| s.strip_prefix("__"))
-                .ok_or_else(|| ToolError::NotFound(tool_call.name.clone()))?;
+            // Report malformed arguments uniformly, rather than leaving it to each tool
+            let arguments = match self.tool_schemas.get(&tool_call.name) {
+                Some(validator) => validator.validate(tool_call.arguments.clone()),
+                None => Ok(tool_call.arguments.clone()),
+            };
 
-            let client_guard = client.lock().await;
-
-            client_guard
-                .call_tool(tool_name, tool_call.clone().arguments)
-                .await
-                .map(|result| result.content)
-                .map_err(|e| ToolError::ExecutionError(e.to_string()))
+            // Else, dispatch tool call based on the prefix naming convention
+            match arguments {
+                Ok(arguments) => self
+                    .manager
+                    .call_tool(&tool_call.name, arguments)
+                    .await
+                    .map_err(|e| match e {
+                        ManagerError::UnknownTool(name) => ToolError::NotFound(name),
+                        e => ToolError::ExecutionError(e.to_string()),
+                    }),
+                Err(e) => Err(e),
+            }
         };
 
         debug!(
@@ -586,7 +1064,7 @@ impl Capabilities {
         &self,
         extension_name: &str,
     ) -> Result<Vec<Prompt>, ToolError> {
-        let client = self.clients.get(extension_name).ok_or_else(|| {
+        let client = self.manager.client(extension_name).ok_or_else(|| {
             ToolError::InvalidParameters(format!("Extension {} is not valid", extension_name))
         })?;
 
@@ -604,45 +1082,7 @@ impl Capabilities {
     }
 
     pub async fn list_prompts(&self) -> Result<HashMap<String, Vec<Prompt>>, ToolError> {
-        let mut futures = FuturesUnordered::new();
-
-        for extension_name in self.clients.keys() {
-            futures.push(async move {
-                (
-                    extension_name,
-                    self.list_prompts_from_extension(extension_name).await,
-                )
-            });
-        }
-
-        let mut all_prompts = HashMap::new();
-        let mut errors = Vec::new();
-
-        // Process results as they complete
-        while let Some(result) = futures.next().await {
-            let (name, prompts) = result;
-            match prompts {
-                Ok(content) => {
-                    all_prompts.insert(name.to_string(), content);
-                }
-                Err(tool_error) => {
-                    errors.push(tool_error);
-                }
-            }
-        }
-
-        // Log any errors that occurred
-        if !errors.is_empty() {
-            tracing::debug!(
-                errors = ?errors
-                    .into_iter()
-                    .map(|e| format!("{:?}", e))
-                    .collect::<Vec<_>>(),
-                "errors from listing prompts"
-            );
-        }
-
-        Ok(all_prompts)
+        Ok(self.manager.list_prompts().await)
     }
 
     pub async fn get_prompt(
@@ -652,8 +1092,8 @@ impl Capabilities {
         arguments: Value,
     ) -> Result<GetPromptResult> {
         let client = self
-            .clients
-            .get(extension_name)
+            .manager
+            .client(extension_name)
             .ok_or_else(|| anyhow::anyhow!("Extension {} not found", extension_name))?;
 
         let client_guard = client.lock().await;
@@ -662,21 +1102,55 @@ impl Capabilities {
             .await
             .map_err(|e| anyhow::anyhow!("Failed to get prompt: {}", e))
     }
+
+    /// Ask the extension for values of a prompt argument that start from what was typed
+    pub async fn complete_prompt_argument(
+        &self,
+        extension_name: &str,
+        prompt_name: &str,
+        argument_name: &str,
+        value: &str,
+    ) -> Result<Vec<String>> {
+        let client = self
+            .manager
+            .client(extension_name)
+            .ok_or_else(|| anyhow::anyhow!("Extension {} not found", extension_name))?;
+
+        let client_guard = client.lock().await;
+        let result = client_guard
+            .complete(
+                CompletionReference::Prompt {
+                    name: prompt_name.to_string(),
+                },
+                CompletionArgument {
+                    name: argument_name.to_string(),
+                    value: value.to_string(),
+                },
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to complete argument: {}", e))?;
+
+        Ok(result.completion.values)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::agents::extension::ExtensionStatus;
     use crate::message::Message;
     use crate::model::ModelConfig;
     use crate::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
     use crate::providers::errors::ProviderError;
     use mcp_client::client::Error;
     use mcp_client::client::McpClientTrait;
+    use mcp_client::manager::{ManagerEvent, ServerStatus};
+    use mcp_client::transport::{RecordingTransport, ReplayTransport};
     use mcp_core::protocol::{
-        CallToolResult, GetPromptResult, InitializeResult, ListPromptsResult, ListResourcesResult,
-        ListToolsResult, ReadResourceResult,
+        CallToolResult, CompleteResult, GetPromptResult, InitializeResult, ListPromptsResult,
+        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, ReadResourceResult,
     };
+    use mcp_server::testing::EchoRouter;
     use serde_json::json;
 
     // Mock Provider implementation for testing
@@ -720,6 +1194,10 @@ mod tests {
             Err(Error::NotInitialized)
         }
 
+        async fn ping(&self) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
         async fn list_resources(
             &self,
             _next_cursor: Option<String>,
@@ -740,6 +1218,7 @@ mod tests {
                 "tool" | "test__tool" => Ok(CallToolResult {
                     content: vec![],
                     is_error: None,
+                    structured_content: None,
                 }),
                 _ => Err(Error::NotInitialized),
             }
@@ -759,10 +1238,366 @@ mod tests {
         ) -> Result<GetPromptResult, Error> {
             Err(Error::NotInitialized)
         }
+
+        async fn list_resource_templates(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourceTemplatesResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn complete(
+            &self,
+            _reference: CompletionReference,
+            _argument: CompletionArgument,
+        ) -> Result<CompleteResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn set_logging_level(&self, _level: LoggingLevel) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+    }
+
+    /// Serves a single active resource and counts how often it is read
+    struct ResourceClient {
+        reads: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl McpClientTrait for ResourceClient {
+        async fn initialize(
+            &mut self,
+            _info: ClientInfo,
+            _capabilities: ClientCapabilities,
+        ) -> Result<InitializeResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn ping(&self) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourcesResult, Error> {
+            let mut resource = mcp_core::Resource::new("str:///note", None, None).unwrap();
+            resource.annotations = Some(mcp_core::content::Annotations::for_resource(
+                1.0,
+                *DEFAULT_TIMESTAMP,
+            ));
+            Ok(ListResourcesResult {
+                resources: vec![resource],
+                next_cursor: None,
+            })
+        }
+
+        async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error> {
+            let reads = self.reads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(ReadResourceResult {
+                contents: vec![mcp_core::resource::ResourceContents::TextResourceContents {
+                    uri: uri.to_string(),
+                    mime_type: None,
+                    text: format!("read {}", reads + 1),
+                }],
+            })
+        }
+
+        async fn list_resource_templates(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListResourceTemplatesResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn list_tools(&self, _next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn call_tool(&self, _name: &str, _arguments: Value) -> Result<CallToolResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<ListPromptsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn complete(
+            &self,
+            _reference: CompletionReference,
+            _argument: CompletionArgument,
+        ) -> Result<CompleteResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn set_logging_level(&self, _level: LoggingLevel) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_resources_only_rereads_updated_resources() {
+        let mock_model_config =
+            ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
+
+        let mut capabilities = Capabilities::new(Box::new(MockProvider {
+            model_config: mock_model_config,
+        }));
+
+        let reads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        capabilities.manager.add_client(
+            "notes".to_string(),
+            Box::new(ResourceClient {
+                reads: Arc::clone(&reads),
+            }),
+        );
+        capabilities
+            .resource_subscribable_extensions
+            .insert("notes".to_string());
+
+        let notifications = mcp_client::transport::notification_channel();
+        track_resource_updates(
+            "notes".to_string(),
+            Arc::clone(&capabilities.resource_cache),
+            notifications.subscribe(),
+        );
+
+        let resources = capabilities.get_resources().await.unwrap();
+        assert_eq!(resources[0].content, "read 1");
+
+        // Unchanged resources are served from the cache
+        let resources = capabilities.get_resources().await.unwrap();
+        assert_eq!(resources[0].content, "read 1");
+        assert_eq!(reads.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        notifications
+            .send(JsonRpcNotification {
+                jsonrpc: "2.0".to_string(),
+                method: "notifications/resources/updated".to_string(),
+                params: Some(json!({"uri": "str:///note"})),
+            })
+            .unwrap();
+
+        // Give the listener a chance to mark the resource stale
+        for _ in 0..100 {
+            let cache = capabilities.resource_cache.lock().await;
+            if cache["notes"]["str:///note"].stale {
+                break;
+            }
+            drop(cache);
+            tokio::task::yield_now().await;
+        }
+
+        let resources = capabilities.get_resources().await.unwrap();
+        assert_eq!(resources[0].content, "read 2");
     }
 
-    #[test]
-    fn test_get_client_for_tool() {
+    #[tokio::test]
+    async fn test_unresponsive_remote_extension_is_reported() {
+        let mock_model_config =
+            ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
+        let mut capabilities = Capabilities::new(Box::new(MockProvider {
+            model_config: mock_model_config,
+        }));
+
+        capabilities.manager = McpClientManager::new(client_info(), client_capabilities())
+            .with_ping_interval(Some(Duration::from_millis(10)));
+        let mut events = capabilities.manager.subscribe();
+
+        // Connected through a server in process, but answering like a dead remote one
+        let (to_server, from_server) =
+            Server::new(mcp_server::router::RouterService(EchoRouter)).spawn();
+        let transport = InProcessTransport::new(to_server, from_server);
+        let connection = connect(
+            transport.start().await.unwrap(),
+            &Roots::default(),
+            Duration::from_secs(5),
+            client_info(),
+            client_capabilities(),
+        )
+        .await
+        .unwrap();
+        let connector = ExtensionConnector {
+            name: "remote".to_string(),
+            config: ExtensionConfig::sse("remote", "http://localhost:1/sse", 1u64),
+            roots: Roots::default(),
+            builtins: None,
+            cache: Arc::clone(&capabilities.resource_cache),
+        };
+        capabilities
+            .manager
+            .add_server(
+                "remote",
+                Connection {
+                    client: Box::new(MockClient {}),
+                    ..connection
+                },
+                Some(Arc::new(connector)),
+            )
+            .await;
+        let unresponsive = async {
+            loop {
+                match events.recv().await {
+                    Ok(ManagerEvent::StatusChanged {
+                        status: ServerStatus::Unresponsive(_),
+                        ..
+                    }) => break,
+                    Ok(_) => continue,
+                    Err(e) => panic!("no more events: {}", e),
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), unresponsive)
+            .await
+            .expect("extension not reported unresponsive");
+
+        let extensions = capabilities.list_extensions().await.unwrap();
+        assert_eq!(extensions.len(), 1);
+        assert_eq!(extensions[0].name, "remote");
+        assert!(matches!(
+            extensions[0].status,
+            ExtensionStatus::Unresponsive(_)
+        ));
+
+        // Once removed, the status is gone along with the extension
+        capabilities.remove_extension("remote").await.unwrap();
+        assert!(capabilities.list_extensions().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_builtin_runs_in_process() {
+        let mock_model_config =
+            ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
+        let mut capabilities = Capabilities::new(Box::new(MockProvider {
+            model_config: mock_model_config,
+        }));
+        capabilities.set_builtin_extensions(Arc::new(|name, _peer| {
+            Box::pin(async move {
+                (name == "echo").then(|| {
+                    Box::new(mcp_server::router::RouterService(EchoRouter))
+                        as Box<dyn mcp_server::BoundedService>
+                })
+            })
+        }));
+
+        // No `mcp echo` process could be started, so this only works in process
+        capabilities
+            .add_extension(ExtensionConfig::Builtin {
+                name: "echo".to_string(),
+                timeout: Some(5),
+            })
+            .await
+            .unwrap();
+
+        let tools = capabilities.get_prefixed_tools().await.unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].name, "echo__echo");
+        assert!(capabilities.trusts_annotations("echo__echo"));
+        assert!(!capabilities.trusts_annotations("other__echo"));
+
+        let result = capabilities
+            .dispatch_tool_call(ToolCall::new("echo__echo", json!({"text": "hi"})))
+            .await
+            .unwrap();
+        assert_eq!(result.content, vec![Content::text(r#"{"text":"hi"}"#)]);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_extension_replays_offline() {
+        let dir = tempfile::tempdir().unwrap();
+        let fixture = dir.path().join("echo.json");
+
+        // Record what the extension does once
+        let (to_server, from_server) =
+            Server::new(mcp_server::router::RouterService(EchoRouter)).spawn();
+        let transport =
+            RecordingTransport::new(InProcessTransport::new(to_server, from_server), &fixture);
+        let recorded = connect(
+            transport.start().await.unwrap(),
+            &Roots::default(),
+            Duration::from_secs(5),
+            client_info(),
+            client_capabilities(),
+        )
+        .await
+        .unwrap();
+        recorded.client.list_tools(None).await.unwrap();
+        recorded
+            .client
+            .call_tool("echo", json!({"text": "hi"}))
+            .await
+            .unwrap();
+        transport.close().await.unwrap();
+
+        // and have the agent use it without the extension running
+        let mock_model_config =
+            ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
+        let mut capabilities = Capabilities::new(Box::new(MockProvider {
+            model_config: mock_model_config,
+        }));
+        let transport = ReplayTransport::from_file(&fixture).unwrap();
+        let replayed = connect(
+            transport.start().await.unwrap(),
+            &Roots::default(),
+            Duration::from_secs(5),
+            client_info(),
+            client_capabilities(),
+        )
+        .await
+        .unwrap();
+        capabilities
+            .manager
+            .add_server("echo", replayed, None)
+            .await;
+
+        let tools = capabilities.get_prefixed_tools().await.unwrap();
+        assert_eq!(tools[0].name, "echo__echo");
+
+        let result = capabilities
+            .dispatch_tool_call(ToolCall::new("echo__echo", json!({"text": "hi"})))
+            .await
+            .unwrap();
+        assert_eq!(result.content, vec![Content::text(r#"{"text":"hi"}"#)]);
+    }
+
+    #[tokio::test]
+    async fn test_get_client_for_tool() {
         let mock_model_config =
             ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
 
@@ -771,41 +1606,47 @@ mod tests {
         }));
 
         // Add some mock clients
-        capabilities.clients.insert(
+        capabilities.manager.add_client(
             normalize("test_client".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
+            Box::new(MockClient {}),
         );
 
-        capabilities.clients.insert(
-            normalize("__client".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
-        );
+        capabilities
+            .manager
+            .add_client(normalize("__client".to_string()), Box::new(MockClient {}));
 
-        capabilities.clients.insert(
+        capabilities.manager.add_client(
             normalize("__cli__ent__".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
+            Box::new(MockClient {}),
         );
 
-        capabilities.clients.insert(
-            normalize("client 🚀".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
-        );
+        capabilities
+            .manager
+            .add_client(normalize("client 🚀".to_string()), Box::new(MockClient {}));
 
         // Test basic case
         assert!(capabilities
-            .get_client_for_tool("test_client__tool")
+            .manager
+            .client_for_tool("test_client__tool")
             .is_some());
 
         // Test leading underscores
-        assert!(capabilities.get_client_for_tool("__client__tool").is_some());
+        assert!(capabilities
+            .manager
+            .client_for_tool("__client__tool")
+            .is_some());
 
         // Test multiple underscores in client name, and ending with __
         assert!(capabilities
-            .get_client_for_tool("__cli__ent____tool")
+            .manager
+            .client_for_tool("__cli__ent____tool")
             .is_some());
 
         // Test unicode in tool name, "client 🚀" should become "client_"
-        assert!(capabilities.get_client_for_tool("client___tool").is_some());
+        assert!(capabilities
+            .manager
+            .client_for_tool("client___tool")
+            .is_some());
     }
 
     #[tokio::test]
@@ -820,20 +1661,19 @@ mod tests {
         }));
 
         // Add some mock clients
-        capabilities.clients.insert(
+        capabilities.manager.add_client(
             normalize("test_client".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
+            Box::new(MockClient {}),
         );
 
-        capabilities.clients.insert(
+        capabilities.manager.add_client(
             normalize("__cli__ent__".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
+            Box::new(MockClient {}),
         );
 
-        capabilities.clients.insert(
-            normalize("client 🚀".to_string()),
-            Arc::new(Mutex::new(Box::new(MockClient {}))),
-        );
+        capabilities
+            .manager
+            .add_client(normalize("client 🚀".to_string()), Box::new(MockClient {}));
 
         // verify a normal tool call
         let tool_call = ToolCall {
@@ -900,4 +1740,44 @@ mod tests {
         let result = capabilities.dispatch_tool_call(invalid_tool_call).await;
         assert!(matches!(result.err().unwrap(), ToolError::NotFound(_)));
     }
+
+    #[tokio::test]
+    async fn test_dispatch_tool_call_validates_arguments() {
+        let mock_model_config =
+            ModelConfig::new("test-model".to_string()).with_context_limit(200_000.into());
+
+        let mut capabilities = Capabilities::new(Box::new(MockProvider {
+            model_config: mock_model_config,
+        }));
+        capabilities
+            .manager
+            .add_client("test_client".to_string(), Box::new(MockClient {}));
+        capabilities.tool_schemas.insert(
+            "test_client__tool".to_string(),
+            ArgumentValidator::new(json!({
+                "type": "object",
+                "required": ["command"],
+                "properties": {
+                    "command": {"type": "string"},
+                    "timeout": {"type": "integer"}
+                }
+            })),
+        );
+
+        let tool_call = ToolCall::new("test_client__tool", json!({"timeout": "soon"}));
+        let Err(ToolError::InvalidParameters(message)) =
+            capabilities.dispatch_tool_call(tool_call).await
+        else {
+            panic!("Expected invalid parameters");
+        };
+        assert!(message.contains("\"command\" is a required property"));
+        assert!(message.contains("- /timeout:"));
+
+        let tool_call = ToolCall::new("test_client__tool", json!({"command": "ls"}));
+        assert!(capabilities.dispatch_tool_call(tool_call).await.is_ok());
+
+        // Removing the extension forgets its schemas
+        capabilities.remove_extension("test_client").await.unwrap();
+        assert!(capabilities.tool_schemas.is_empty());
+    }
 }
@@ -10,13 +10,17 @@ use crate::config;
 #[derive(Error, Debug)]
 pub enum ExtensionError {
     #[error("Failed to start the MCP server from configuration `{0}` `{1}`")]
-    Initialization(ExtensionConfig, ClientError),
+    Initialization(Box<ExtensionConfig>, ClientError),
     #[error("Failed a client call to an MCP server: {0}")]
     Client(#[from] ClientError),
     #[error("User Message exceeded context-limit. History could not be truncated to accomodate.")]
     ContextLimit,
     #[error("Transport error: {0}")]
     Transport(#[from] mcp_client::transport::Error),
+    #[error("Failed to authenticate to extension `{0}`: {1}")]
+    Authentication(String, String),
+    #[error("Extension `{0}` speaks MCP protocol version {1}, which goose does not support (supported: {2})")]
+    UnsupportedProtocolVersion(String, String, String),
 }
 
 pub type ExtensionResult<T> = Result<T, ExtensionError>;
@@ -42,6 +46,24 @@ impl Envs {
     }
 }
 
+/// How goose authenticates to a remote extension, on top of the headers it sends
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum ExtensionAuth {
+    /// Send the secret stored under `secret` (e.g. in the keyring) as a bearer token
+    #[serde(rename = "bearer")]
+    Bearer { secret: String },
+    /// Sign in through the browser with the OAuth authorization code flow. The server
+    /// advertises its authorization server at `/.well-known/oauth-authorization-server`
+    #[serde(rename = "oauth")]
+    OAuth {
+        client_id: String,
+        redirect_url: String,
+        #[serde(default)]
+        scopes: Vec<String>,
+    },
+}
+
 /// Represents the different types of MCP extensions that can be added to the manager
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type")]
@@ -52,8 +74,15 @@ pub enum ExtensionConfig {
         /// The name used to identify this extension
         name: String,
         uri: String,
+        // NOTE: remote extensions do not run in our environment, so these are not passed
+        // on. Kept so existing configurations still load, use headers instead.
         #[serde(default)]
         envs: Envs,
+        /// HTTP headers sent with every request, e.g. an API key
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        #[serde(default)]
+        auth: Option<ExtensionAuth>,
         // NOTE: set timeout to be optional for compatibility.
         // However, new configurations should include this field.
         timeout: Option<u64>,
@@ -93,6 +122,8 @@ impl ExtensionConfig {
             name: name.into(),
             uri: uri.into(),
             envs: Envs::default(),
+            headers: HashMap::new(),
+            auth: None,
             timeout: Some(timeout.into()),
         }
     }
@@ -171,3 +202,20 @@ impl ExtensionInfo {
         }
     }
 }
+
+/// Health of an extension, as seen by the periodic pings
+pub use mcp_client::manager::ServerStatus as ExtensionStatus;
+
+/// An extension and its health, as listed by the agent
+#[derive(Clone, Debug, Serialize)]
+pub struct ExtensionState {
+    pub name: String,
+    #[serde(flatten)]
+    pub status: ExtensionStatus,
+}
+
+impl std::fmt::Display for ExtensionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.name, self.status)
+    }
+}
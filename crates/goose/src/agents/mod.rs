@@ -1,4 +1,5 @@
 mod agent;
+mod builtin;
 mod capabilities;
 pub mod extension;
 mod factory;
@@ -9,8 +10,9 @@ mod summarize;
 mod truncate;
 
 pub use agent::{Agent, SessionConfig};
+pub use builtin::BuiltinExtensions;
 pub use capabilities::Capabilities;
 pub use extension::ExtensionConfig;
 pub use factory::{register_agent, AgentFactory};
-pub use permission_judge::detect_read_only_tools;
+pub use permission_judge::{detect_read_only_tools, split_annotated_read_only_tools};
 pub use permission_store::ToolPermissionStore;
@@ -100,6 +100,34 @@ fn extract_read_only_tools(response: &Message) -> Option<Vec<String>> {
     None
 }
 
+/// Splits the tool requests into the names of the tools annotated as read-only by their
+/// extension, and the requests for other tools that still need to be judged.
+///
+/// Only the annotations of tools `trusted` says can be relied on count, see
+/// [`Capabilities::trusts_annotations`].
+pub fn split_annotated_read_only_tools<'a>(
+    tools: &[Tool],
+    trusted: impl Fn(&str) -> bool,
+    tool_requests: Vec<&'a ToolRequest>,
+) -> (Vec<String>, Vec<&'a ToolRequest>) {
+    let mut read_only_tools = Vec::new();
+    let mut unknown_requests = Vec::new();
+    for request in tool_requests {
+        let read_only = request.tool_call.as_ref().ok().and_then(|tool_call| {
+            tools
+                .iter()
+                .find(|tool| tool.name == tool_call.name)
+                .filter(|tool| tool.is_read_only() && trusted(&tool.name))
+                .map(|tool| tool.name.clone())
+        });
+        match read_only {
+            Some(name) => read_only_tools.push(name),
+            None => unknown_requests.push(request),
+        }
+    }
+    (read_only_tools, unknown_requests)
+}
+
 /// Executes the read-only tools detection and returns the list of tools with read-only operations.
 pub async fn detect_read_only_tools(
     capabilities: &Capabilities,
@@ -137,6 +165,7 @@ mod tests {
     use crate::providers::base::{Provider, ProviderMetadata, ProviderUsage, Usage};
     use crate::providers::errors::ProviderError;
     use chrono::Utc;
+    use mcp_core::tool::ToolAnnotations;
     use mcp_core::ToolCall;
     use mcp_core::{tool::Tool, Role, ToolResult};
     use serde_json::json;
@@ -262,4 +291,47 @@ mod tests {
         let result = detect_read_only_tools(&capabilities, vec![]).await;
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_split_annotated_read_only_tools() {
+        let tools = vec![
+            Tool::new("developer__read", "Read a file", json!({})).with_annotations(
+                ToolAnnotations {
+                    read_only_hint: true,
+                    ..Default::default()
+                },
+            ),
+            Tool::new("developer__write", "Write a file", json!({})),
+            // Extensions that are not trusted cannot skip the judge by claiming to be read-only
+            Tool::new("other__delete", "Delete a file", json!({})).with_annotations(
+                ToolAnnotations {
+                    read_only_hint: true,
+                    ..Default::default()
+                },
+            ),
+        ];
+        let read_request = ToolRequest {
+            id: "tool_1".to_string(),
+            tool_call: ToolResult::Ok(ToolCall::new("developer__read", json!({}))),
+        };
+        let write_request = ToolRequest {
+            id: "tool_2".to_string(),
+            tool_call: ToolResult::Ok(ToolCall::new("developer__write", json!({}))),
+        };
+
+        let delete_request = ToolRequest {
+            id: "tool_3".to_string(),
+            tool_call: ToolResult::Ok(ToolCall::new("other__delete", json!({}))),
+        };
+
+        let (read_only_tools, unknown_requests) = split_annotated_read_only_tools(
+            &tools,
+            |name| name.starts_with("developer__"),
+            vec![&read_request, &write_request, &delete_request],
+        );
+        assert_eq!(read_only_tools, vec!["developer__read"]);
+        assert_eq!(unknown_requests.len(), 2);
+        assert_eq!(unknown_requests[0].id, "tool_2");
+        assert_eq!(unknown_requests[1].id, "tool_3");
+    }
 }
@@ -8,9 +8,10 @@ use tokio::sync::Mutex;
 use tracing::{debug, instrument};
 
 use super::agent::SessionConfig;
+use super::builtin::BuiltinExtensions;
 use super::Agent;
 use crate::agents::capabilities::Capabilities;
-use crate::agents::extension::{ExtensionConfig, ExtensionResult};
+use crate::agents::extension::{ExtensionConfig, ExtensionResult, ExtensionState};
 use crate::message::{Message, ToolRequest};
 use crate::providers::base::Provider;
 use crate::providers::base::ProviderUsage;
@@ -20,7 +21,7 @@ use anyhow::{anyhow, Result};
 use indoc::indoc;
 use mcp_core::prompt::Prompt;
 use mcp_core::protocol::GetPromptResult;
-use mcp_core::tool::Tool;
+use mcp_core::tool::{Tool, ToolAnnotations};
 use serde_json::{json, Value};
 
 /// Reference implementation of an Agent
@@ -54,7 +55,7 @@ impl Agent for ReferenceAgent {
             .expect("Failed to remove extension");
     }
 
-    async fn list_extensions(&self) -> Vec<String> {
+    async fn list_extensions(&self) -> Vec<ExtensionState> {
         let capabilities = self.capabilities.lock().await;
         capabilities
             .list_extensions()
@@ -80,6 +81,11 @@ impl Agent for ReferenceAgent {
         let mut messages = messages.to_vec();
         let reply_span = tracing::Span::current();
         let mut capabilities = self.capabilities.lock().await;
+        if let Some(session) = &session {
+            capabilities
+                .set_working_dirs(std::slice::from_ref(&session.working_dir))
+                .await;
+        }
         let mut tools = capabilities.get_prefixed_tools().await?;
         // we add in the read_resource tool by default
         // TODO: make sure there is no collision with another extension's tool name
@@ -101,7 +107,12 @@ impl Agent for ReferenceAgent {
                     "extension_name": {"type": "string", "description": "Optional extension name"}
                 }
             }),
-        );
+        )
+        .with_annotations(ToolAnnotations {
+            read_only_hint: true,
+            open_world_hint: false,
+            ..Default::default()
+        });
 
         let list_resources_tool = Tool::new(
             "platform__list_resources".to_string(),
@@ -119,7 +130,12 @@ impl Agent for ReferenceAgent {
                     "extension_name": {"type": "string", "description": "Optional extension name"}
                 }
             }),
-        );
+        )
+        .with_annotations(ToolAnnotations {
+            read_only_hint: true,
+            open_world_hint: false,
+            ..Default::default()
+        });
 
         if capabilities.supports_resources() {
             tools.push(read_resource_tool);
@@ -151,14 +167,14 @@ impl Agent for ReferenceAgent {
                 // record usage for the session in the session file
                 if let Some(session) = session.clone() {
                     // TODO: track session_id in langfuse tracing
-                    let session_file = session::get_path(session.id);
-                    let mut metadata = session::read_metadata(&session_file)?;
+                    let stored = session::StoredSession::open(session.id)?;
+                    let mut metadata = stored.read_metadata()?;
                     metadata.working_dir = session.working_dir;
                     metadata.total_tokens = usage.usage.total_tokens;
                     // The message count is the number of messages in the session + 1 for the response
                     // The message count does not include the tool response till next iteration
                     metadata.message_count = messages.len() + 1;
-                    session::update_metadata(&session_file, &metadata).await?;
+                    stored.update_metadata(&metadata)?;
                 }
 
                 // Yield the assistant's response
@@ -190,7 +206,7 @@ impl Agent for ReferenceAgent {
                 let mut message_tool_response = Message::user();
                 // Now combine these into MessageContent::ToolResponse using the original ID
                 for (request, output) in tool_requests.iter().zip(outputs.into_iter()) {
-                    message_tool_response = message_tool_response.with_tool_response(
+                    message_tool_response = message_tool_response.with_tool_result(
                         request.id.clone(),
                         output,
                     );
@@ -219,6 +235,11 @@ impl Agent for ReferenceAgent {
         capabilities.set_system_prompt_override(template);
     }
 
+    async fn set_builtin_extensions(&mut self, builtins: BuiltinExtensions) {
+        let mut capabilities = self.capabilities.lock().await;
+        capabilities.set_builtin_extensions(builtins);
+    }
+
     async fn list_extension_prompts(&self) -> HashMap<String, Vec<Prompt>> {
         let capabilities = self.capabilities.lock().await;
         capabilities
@@ -250,6 +271,32 @@ impl Agent for ReferenceAgent {
         Err(anyhow!("Prompt '{}' not found", name))
     }
 
+    async fn complete_prompt_argument(
+        &self,
+        name: &str,
+        argument: &str,
+        value: &str,
+    ) -> Result<Vec<String>> {
+        let capabilities = self.capabilities.lock().await;
+
+        let prompts = capabilities
+            .list_prompts()
+            .await
+            .map_err(|e| anyhow!("Failed to list prompts: {}", e))?;
+
+        if let Some(extension) = prompts
+            .iter()
+            .find(|(_, prompt_list)| prompt_list.iter().any(|p| p.name == name))
+            .map(|(extension, _)| extension)
+        {
+            return capabilities
+                .complete_prompt_argument(extension, name, argument, value)
+                .await;
+        }
+
+        Err(anyhow!("Prompt '{}' not found", name))
+    }
+
     async fn provider(&self) -> Arc<Box<dyn Provider>> {
         let capabilities = self.capabilities.lock().await;
         capabilities.provider()
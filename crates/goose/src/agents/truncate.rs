@@ -9,10 +9,11 @@ use tokio::sync::Mutex;
 use tracing::{debug, error, instrument, warn};
 
 use super::agent::SessionConfig;
-use super::detect_read_only_tools;
+use super::builtin::BuiltinExtensions;
 use super::Agent;
+use super::{detect_read_only_tools, split_annotated_read_only_tools};
 use crate::agents::capabilities::Capabilities;
-use crate::agents::extension::{ExtensionConfig, ExtensionResult};
+use crate::agents::extension::{ExtensionConfig, ExtensionResult, ExtensionState};
 use crate::agents::ToolPermissionStore;
 use crate::config::Config;
 use crate::config::ExperimentManager;
@@ -27,8 +28,11 @@ use crate::truncate::{truncate_messages, OldestFirstTruncation};
 use anyhow::{anyhow, Result};
 use indoc::indoc;
 use mcp_core::prompt::Prompt;
-use mcp_core::protocol::GetPromptResult;
-use mcp_core::{tool::Tool, Content, ToolError};
+use mcp_core::protocol::{CallToolResult, GetPromptResult};
+use mcp_core::{
+    tool::{Tool, ToolAnnotations},
+    Content, ToolError,
+};
 use serde_json::{json, Value};
 use std::time::Duration;
 
@@ -116,7 +120,7 @@ impl TruncateAgent {
         capabilities: &Capabilities,
         tool_call: mcp_core::tool::ToolCall,
         request_id: String,
-    ) -> (String, Result<Vec<Content>, ToolError>) {
+    ) -> (String, Result<CallToolResult, ToolError>) {
         let output = capabilities.dispatch_tool_call(tool_call).await;
         (request_id, output)
     }
@@ -137,7 +141,7 @@ impl Agent for TruncateAgent {
             .expect("Failed to remove extension");
     }
 
-    async fn list_extensions(&self) -> Vec<String> {
+    async fn list_extensions(&self) -> Vec<ExtensionState> {
         let capabilities = self.capabilities.lock().await;
         capabilities
             .list_extensions()
@@ -166,6 +170,11 @@ impl Agent for TruncateAgent {
         let mut messages = messages.to_vec();
         let reply_span = tracing::Span::current();
         let mut capabilities = self.capabilities.lock().await;
+        if let Some(session) = &session {
+            capabilities
+                .set_working_dirs(std::slice::from_ref(&session.working_dir))
+                .await;
+        }
         let mut tools = capabilities.get_prefixed_tools().await?;
         let mut truncation_attempt: usize = 0;
 
@@ -193,7 +202,12 @@ impl Agent for TruncateAgent {
                     "extension_name": {"type": "string", "description": "Optional extension name"}
                 }
             }),
-        );
+        )
+        .with_annotations(ToolAnnotations {
+            read_only_hint: true,
+            open_world_hint: false,
+            ..Default::default()
+        });
 
         let list_resources_tool = Tool::new(
             "platform__list_resources".to_string(),
@@ -211,7 +225,12 @@ impl Agent for TruncateAgent {
                     "extension_name": {"type": "string", "description": "Optional extension name"}
                 }
             }),
-        );
+        )
+        .with_annotations(ToolAnnotations {
+            read_only_hint: true,
+            open_world_hint: false,
+            ..Default::default()
+        });
 
         if capabilities.supports_resources() {
             tools.push(read_resource_tool);
@@ -243,14 +262,14 @@ impl Agent for TruncateAgent {
                         // record usage for the session in the session file
                         if let Some(session) = session.clone() {
                             // TODO: track session_id in langfuse tracing
-                            let session_file = session::get_path(session.id);
-                            let mut metadata = session::read_metadata(&session_file)?;
+                            let stored = session::StoredSession::open(session.id)?;
+                            let mut metadata = stored.read_metadata()?;
                             metadata.working_dir = session.working_dir;
                             metadata.total_tokens = usage.usage.total_tokens;
                             // The message count is the number of messages in the session + 1 for the response
                             // The message count does not include the tool response till next iteration
                             metadata.message_count = messages.len() + 1;
-                            session::update_metadata(&session_file, &metadata).await?;
+                            stored.update_metadata(&metadata)?;
                         }
 
                         // Reset truncation attempt
@@ -298,9 +317,12 @@ impl Agent for TruncateAgent {
                                     }
                                 }
 
-                                // Only check read-only status for tools needing confirmation
+                                // Only check read-only status for tools needing confirmation, trusting the
+                                // annotations of the tools before asking the judge about the others
                                 if !needs_confirmation.is_empty() && ExperimentManager::is_enabled("GOOSE_SMART_APPROVE")? {
-                                    read_only_tools = detect_read_only_tools(&capabilities, needs_confirmation.clone()).await;
+                                    let (annotated, unknown) = split_annotated_read_only_tools(&tools, |name| capabilities.trusts_annotations(name), needs_confirmation.clone());
+                                    read_only_tools = annotated;
+                                    read_only_tools.extend(detect_read_only_tools(&capabilities, unknown).await);
                                 }
 
                                 // Handle pre-approved and read-only tools in parallel
@@ -356,7 +378,7 @@ impl Agent for TruncateAgent {
                                 // Wait for all tool calls to complete
                                 let results = futures::future::join_all(tool_futures).await;
                                 for (request_id, output) in results {
-                                    message_tool_response = message_tool_response.with_tool_response(
+                                    message_tool_response = message_tool_response.with_tool_result(
                                         request_id,
                                         output,
                                     );
@@ -395,7 +417,7 @@ impl Agent for TruncateAgent {
                                 // Wait for all tool calls to complete
                                 let results = futures::future::join_all(tool_futures).await;
                                 for (request_id, output) in results {
-                                    message_tool_response = message_tool_response.with_tool_response(
+                                    message_tool_response = message_tool_response.with_tool_result(
                                         request_id,
                                         output,
                                     );
@@ -468,6 +490,11 @@ impl Agent for TruncateAgent {
         capabilities.set_system_prompt_override(template);
     }
 
+    async fn set_builtin_extensions(&mut self, builtins: BuiltinExtensions) {
+        let mut capabilities = self.capabilities.lock().await;
+        capabilities.set_builtin_extensions(builtins);
+    }
+
     async fn list_extension_prompts(&self) -> HashMap<String, Vec<Prompt>> {
         let capabilities = self.capabilities.lock().await;
         capabilities
@@ -499,6 +526,32 @@ impl Agent for TruncateAgent {
         Err(anyhow!("Prompt '{}' not found", name))
     }
 
+    async fn complete_prompt_argument(
+        &self,
+        name: &str,
+        argument: &str,
+        value: &str,
+    ) -> Result<Vec<String>> {
+        let capabilities = self.capabilities.lock().await;
+
+        let prompts = capabilities
+            .list_prompts()
+            .await
+            .map_err(|e| anyhow!("Failed to list prompts: {}", e))?;
+
+        if let Some(extension) = prompts
+            .iter()
+            .find(|(_, prompt_list)| prompt_list.iter().any(|p| p.name == name))
+            .map(|(extension, _)| extension)
+        {
+            return capabilities
+                .complete_prompt_argument(extension, name, argument, value)
+                .await;
+        }
+
+        Err(anyhow!("Prompt '{}' not found", name))
+    }
+
     async fn provider(&self) -> Arc<Box<dyn Provider>> {
         let capabilities = self.capabilities.lock().await;
         capabilities.provider()
@@ -8,9 +8,10 @@ use std::collections::HashSet;
 /// The content of the messages uses MCP types to avoid additional conversions
 /// when interacting with MCP servers.
 use chrono::Utc;
-use mcp_core::content::{Content, ImageContent, TextContent};
+use mcp_core::content::{AudioContent, Content, ImageContent, ResourceLink, TextContent};
 use mcp_core::handler::ToolResult;
 use mcp_core::prompt::{PromptMessage, PromptMessageContent, PromptMessageRole};
+use mcp_core::protocol::CallToolResult;
 use mcp_core::resource::ResourceContents;
 use mcp_core::role::Role;
 use mcp_core::tool::ToolCall;
@@ -48,6 +49,9 @@ pub struct ToolResponse {
     pub id: String,
     #[serde(with = "tool_result_serde")]
     pub tool_result: ToolResult<Vec<Content>>,
+    /// The result as JSON, when the tool declares an output schema
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
 }
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -76,6 +80,9 @@ pub struct RedactedThinkingContent {
 pub enum MessageContent {
     Text(TextContent),
     Image(ImageContent),
+    Audio(AudioContent),
+    #[serde(rename = "resource_link")]
+    ResourceLink(ResourceLink),
     ToolRequest(ToolRequest),
     ToolResponse(ToolResponse),
     ToolConfirmationRequest(ToolConfirmationRequest),
@@ -99,6 +106,24 @@ impl MessageContent {
         })
     }
 
+    pub fn audio<S: Into<String>, T: Into<String>>(data: S, mime_type: T) -> Self {
+        MessageContent::Audio(AudioContent {
+            data: data.into(),
+            mime_type: mime_type.into(),
+            annotations: None,
+        })
+    }
+
+    pub fn resource_link<S: Into<String>, T: Into<String>>(uri: S, name: T) -> Self {
+        MessageContent::ResourceLink(ResourceLink {
+            uri: uri.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+            annotations: None,
+        })
+    }
+
     pub fn tool_request<S: Into<String>>(id: S, tool_call: ToolResult<ToolCall>) -> Self {
         MessageContent::ToolRequest(ToolRequest {
             id: id.into(),
@@ -110,6 +135,20 @@ impl MessageContent {
         MessageContent::ToolResponse(ToolResponse {
             id: id.into(),
             tool_result,
+            structured_content: None,
+        })
+    }
+
+    /// A tool response from the result of calling a tool, keeping its structured content
+    pub fn tool_result<S: Into<String>>(id: S, result: ToolResult<CallToolResult>) -> Self {
+        let (tool_result, structured_content) = match result {
+            Ok(result) => (Ok(result.content), result.structured_content),
+            Err(e) => (Err(e), None),
+        };
+        MessageContent::ToolResponse(ToolResponse {
+            id: id.into(),
+            tool_result,
+            structured_content,
         })
     }
 
@@ -206,6 +245,8 @@ impl From<Content> for MessageContent {
         match content {
             Content::Text(text) => MessageContent::Text(text),
             Content::Image(image) => MessageContent::Image(image),
+            Content::Audio(audio) => MessageContent::Audio(audio),
+            Content::ResourceLink(link) => MessageContent::ResourceLink(link),
             Content::Resource(resource) => MessageContent::Text(TextContent {
                 text: resource.get_text(),
                 annotations: None,
@@ -289,6 +330,16 @@ impl Message {
         self.with_content(MessageContent::image(data, mime_type))
     }
 
+    /// Add audio content to the message
+    pub fn with_audio<S: Into<String>, T: Into<String>>(self, data: S, mime_type: T) -> Self {
+        self.with_content(MessageContent::audio(data, mime_type))
+    }
+
+    /// Add a link to a resource to the message
+    pub fn with_resource_link<S: Into<String>, T: Into<String>>(self, uri: S, name: T) -> Self {
+        self.with_content(MessageContent::resource_link(uri, name))
+    }
+
     /// Add a tool request to the message
     pub fn with_tool_request<S: Into<String>>(
         self,
@@ -307,6 +358,15 @@ impl Message {
         self.with_content(MessageContent::tool_response(id, result))
     }
 
+    /// Add the result of calling a tool to the message
+    pub fn with_tool_result<S: Into<String>>(
+        self,
+        id: S,
+        result: ToolResult<CallToolResult>,
+    ) -> Self {
+        self.with_content(MessageContent::tool_result(id, result))
+    }
+
     /// Add a tool confirmation request to the message
     pub fn with_tool_confirmation_request<S: Into<String>>(
         self,
@@ -659,6 +719,25 @@ mod tests {
         assert_eq!(message.as_concat_text(), "I can help with that.");
     }
 
+    #[test]
+    fn test_audio_and_resource_link_round_trip() {
+        let message = Message::user()
+            .with_audio("base64audio", "audio/wav")
+            .with_resource_link("file:///notes.md", "notes");
+
+        let value = serde_json::to_value(&message).unwrap();
+        assert_eq!(value["content"][0]["type"], "audio");
+        assert_eq!(value["content"][0]["mimeType"], "audio/wav");
+        assert_eq!(value["content"][1]["type"], "resource_link");
+        assert_eq!(value["content"][1]["uri"], "file:///notes.md");
+
+        let restored: Message = serde_json::from_value(value).unwrap();
+        assert_eq!(restored.content, message.content);
+
+        let content = MessageContent::from(Content::audio("base64audio", "audio/mpeg"));
+        assert!(matches!(content, MessageContent::Audio(audio) if audio.mime_type == "audio/mpeg"));
+    }
+
     #[test]
     fn test_message_with_text() {
         let message = Message::user().with_text("Hello");
@@ -2,8 +2,10 @@ use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
 use crate::providers::base::Usage;
 use crate::providers::errors::ProviderError;
+use crate::providers::utils::resource_link_text;
 use anyhow::{anyhow, Result};
 use mcp_core::content::Content;
+use mcp_core::handler::ToolError;
 use mcp_core::role::Role;
 use mcp_core::tool::{Tool, ToolCall};
 use serde_json::{json, Value};
@@ -41,14 +43,19 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                 }
                 MessageContent::ToolResponse(tool_response) => {
                     if let Ok(result) = &tool_response.tool_result {
-                        let text = result
-                            .iter()
-                            .filter_map(|c| match c {
-                                Content::Text(t) => Some(t.text.clone()),
-                                _ => None,
-                            })
-                            .collect::<Vec<_>>()
-                            .join("\n");
+                        // Structured results are passed on as the JSON they are
+                        let text = match &tool_response.structured_content {
+                            Some(structured) => structured.to_string(),
+                            None => result
+                                .iter()
+                                .filter_map(|c| match c {
+                                    Content::Text(t) => Some(t.text.clone()),
+                                    Content::ResourceLink(link) => Some(resource_link_text(link)),
+                                    _ => None,
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                        };
 
                         content.push(json!({
                             "type": "tool_result",
@@ -74,6 +81,13 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                     }));
                 }
                 MessageContent::Image(_) => continue, // Anthropic doesn't support image content yet
+                MessageContent::Audio(_) => continue, // Anthropic doesn't support audio content
+                MessageContent::ResourceLink(link) => {
+                    content.push(json!({
+                        "type": "text",
+                        "text": resource_link_text(link)
+                    }));
+                }
             }
         }
 
@@ -160,6 +174,74 @@ pub fn format_system(system: &str) -> Value {
     }])
 }
 
+/// Add a content block of Anthropic's API to a message, blocks of unknown types are left out
+fn with_content_block(message: Message, block: &Value) -> Result<Message> {
+    let message = match block.get("type").and_then(|t| t.as_str()) {
+        Some("text") => match block.get("text").and_then(|t| t.as_str()) {
+            Some(text) => message.with_text(text.to_string()),
+            None => message,
+        },
+        Some("tool_use") => {
+            let id = block
+                .get("id")
+                .and_then(|i| i.as_str())
+                .ok_or_else(|| anyhow!("Missing tool_use id"))?;
+            let name = block
+                .get("name")
+                .and_then(|n| n.as_str())
+                .ok_or_else(|| anyhow!("Missing tool_use name"))?;
+            let input = block
+                .get("input")
+                .ok_or_else(|| anyhow!("Missing tool_use input"))?;
+
+            let tool_call = ToolCall::new(name, input.clone());
+            message.with_tool_request(id, Ok(tool_call))
+        }
+        Some("tool_result") => {
+            let id = block
+                .get("tool_use_id")
+                .and_then(|i| i.as_str())
+                .ok_or_else(|| anyhow!("Missing tool_result tool_use_id"))?;
+            // The content is a string or an array of blocks, of which the text is kept
+            let text = match block.get("content") {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Array(blocks)) => blocks
+                    .iter()
+                    .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            let result = if block.get("is_error") == Some(&json!(true)) {
+                Err(ToolError::ExecutionError(text))
+            } else {
+                Ok(vec![Content::text(text)])
+            };
+            message.with_tool_response(id, result)
+        }
+        Some("thinking") => {
+            let thinking = block
+                .get("thinking")
+                .and_then(|t| t.as_str())
+                .ok_or_else(|| anyhow!("Missing thinking content"))?;
+            let signature = block
+                .get("signature")
+                .and_then(|s| s.as_str())
+                .ok_or_else(|| anyhow!("Missing thinking signature"))?;
+            message.with_thinking(thinking, signature)
+        }
+        Some("redacted_thinking") => {
+            let data = block
+                .get("data")
+                .and_then(|d| d.as_str())
+                .ok_or_else(|| anyhow!("Missing redacted_thinking data"))?;
+            message.with_redacted_thinking(data)
+        }
+        _ => message,
+    };
+    Ok(message)
+}
+
 /// Convert Anthropic's API response to internal Message format
 pub fn response_to_message(response: Value) -> Result<Message> {
     let content_blocks = response
@@ -168,53 +250,39 @@ pub fn response_to_message(response: Value) -> Result<Message> {
         .ok_or_else(|| anyhow!("Invalid response format: missing content array"))?;
 
     let mut message = Message::assistant();
-
     for block in content_blocks {
-        match block.get("type").and_then(|t| t.as_str()) {
-            Some("text") => {
-                if let Some(text) = block.get("text").and_then(|t| t.as_str()) {
-                    message = message.with_text(text.to_string());
+        message = with_content_block(message, block)?;
+    }
+    Ok(message)
+}
+
+/// Convert messages in Anthropic's format back to internal Messages, the inverse of
+/// [`format_messages`]
+///
+/// Content other than text, tool use and thinking is left out, and so are messages left
+/// empty.
+pub fn messages_from_anthropic(messages: &[Value]) -> Result<Vec<Message>> {
+    let mut converted = Vec::new();
+    for message in messages {
+        let mut result = match message.get("role").and_then(|r| r.as_str()) {
+            Some("user") => Message::user(),
+            Some("assistant") => Message::assistant(),
+            _ => return Err(anyhow!("Invalid message role: {}", message)),
+        };
+        match message.get("content") {
+            Some(Value::String(text)) => result = result.with_text(text.clone()),
+            Some(Value::Array(blocks)) => {
+                for block in blocks {
+                    result = with_content_block(result, block)?;
                 }
             }
-            Some("tool_use") => {
-                let id = block
-                    .get("id")
-                    .and_then(|i| i.as_str())
-                    .ok_or_else(|| anyhow!("Missing tool_use id"))?;
-                let name = block
-                    .get("name")
-                    .and_then(|n| n.as_str())
-                    .ok_or_else(|| anyhow!("Missing tool_use name"))?;
-                let input = block
-                    .get("input")
-                    .ok_or_else(|| anyhow!("Missing tool_use input"))?;
-
-                let tool_call = ToolCall::new(name, input.clone());
-                message = message.with_tool_request(id, Ok(tool_call));
-            }
-            Some("thinking") => {
-                let thinking = block
-                    .get("thinking")
-                    .and_then(|t| t.as_str())
-                    .ok_or_else(|| anyhow!("Missing thinking content"))?;
-                let signature = block
-                    .get("signature")
-                    .and_then(|s| s.as_str())
-                    .ok_or_else(|| anyhow!("Missing thinking signature"))?;
-                message = message.with_thinking(thinking, signature);
-            }
-            Some("redacted_thinking") => {
-                let data = block
-                    .get("data")
-                    .and_then(|d| d.as_str())
-                    .ok_or_else(|| anyhow!("Missing redacted_thinking data"))?;
-                message = message.with_redacted_thinking(data);
-            }
-            _ => continue,
+            _ => return Err(anyhow!("Invalid message content: {}", message)),
+        }
+        if !result.content.is_empty() {
+            converted.push(result);
         }
     }
-
-    Ok(message)
+    Ok(converted)
 }
 
 /// Extract usage information from Anthropic's API response
@@ -613,4 +681,44 @@ mod tests {
         // Return the test result
         result
     }
+
+    #[test]
+    fn test_messages_from_anthropic() -> Result<()> {
+        let messages = vec![
+            Message::user().with_text("List the files"),
+            Message::assistant()
+                .with_thinking("I should run ls", "signature")
+                .with_tool_request(
+                    "1",
+                    Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+                ),
+            Message::user().with_tool_response("1", Ok(vec![Content::text("a.txt")])),
+            Message::assistant().with_text("There is one file"),
+        ];
+        let converted = messages_from_anthropic(&format_messages(&messages))?;
+        assert_eq!(converted.len(), messages.len());
+        for (converted, message) in converted.iter().zip(&messages) {
+            assert_eq!(converted.role, message.role);
+            assert_eq!(converted.content, message.content);
+        }
+
+        let converted = messages_from_anthropic(&[json!({
+            "role": "user",
+            "content": [{
+                "type": "tool_result",
+                "tool_use_id": "2",
+                "is_error": true,
+                "content": [{"type": "text", "text": "not found"}]
+            }]
+        })])?;
+        assert_eq!(
+            converted[0].content,
+            vec![MessageContent::tool_response(
+                "2",
+                Err(ToolError::ExecutionError("not found".to_string()))
+            )]
+        );
+        assert!(messages_from_anthropic(&[json!({"role": "system", "content": "Hi"})]).is_err());
+        Ok(())
+    }
 }
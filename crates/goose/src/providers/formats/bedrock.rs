@@ -10,6 +10,7 @@ use serde_json::Value;
 
 use super::super::base::Usage;
 use crate::message::{Message, MessageContent};
+use crate::providers::utils::resource_link_text;
 
 pub fn to_bedrock_message(message: &Message) -> Result<bedrock::Message> {
     bedrock::Message::builder()
@@ -25,6 +26,18 @@ pub fn to_bedrock_message(message: &Message) -> Result<bedrock::Message> {
         .map_err(|err| anyhow!("Failed to construct Bedrock message: {}", err))
 }
 
+/// Bedrock takes no audio, so the model is told some was left out instead
+fn audio_placeholder(mime_type: &str) -> String {
+    tracing::warn!(
+        mime_type,
+        "Audio content is not supported by Bedrock provider yet, sending a placeholder"
+    );
+    format!(
+        "[{} audio left out, Bedrock does not support audio]",
+        mime_type
+    )
+}
+
 pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::ContentBlock> {
     Ok(match content {
         MessageContent::Text(text) => bedrock::ContentBlock::Text(text.text.to_string()),
@@ -34,6 +47,10 @@ pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::C
         MessageContent::Image(_) => {
             bail!("Image content is not supported by Bedrock provider yet")
         }
+        MessageContent::Audio(audio) => {
+            bedrock::ContentBlock::Text(audio_placeholder(&audio.mime_type))
+        }
+        MessageContent::ResourceLink(link) => bedrock::ContentBlock::Text(resource_link_text(link)),
         MessageContent::Thinking(_) => {
             // Thinking blocks are not supported in Bedrock - skip
             bedrock::ContentBlock::Text("".to_string())
@@ -58,14 +75,18 @@ pub fn to_bedrock_message_content(content: &MessageContent) -> Result<bedrock::C
             bedrock::ContentBlock::ToolUse(tool_use)
         }
         MessageContent::ToolResponse(tool_res) => {
-            let content = match &tool_res.tool_result {
-                Ok(content) => Some(
+            let content = match (&tool_res.tool_result, &tool_res.structured_content) {
+                // Structured results are passed on as the JSON they are
+                (Ok(_), Some(structured)) => Some(vec![bedrock::ToolResultContentBlock::Json(
+                    to_bedrock_json(structured),
+                )]),
+                (Ok(content), None) => Some(
                     content
                         .iter()
                         .map(|c| to_bedrock_tool_result_content_block(&tool_res.id, c))
                         .collect::<Result<_>>()?,
                 ),
-                Err(_) => None,
+                (Err(_), _) => None,
             };
             bedrock::ContentBlock::ToolResult(
                 bedrock::ToolResultBlock::builder()
@@ -89,6 +110,12 @@ pub fn to_bedrock_tool_result_content_block(
     Ok(match content {
         Content::Text(text) => bedrock::ToolResultContentBlock::Text(text.text.to_string()),
         Content::Image(_) => bail!("Image content is not supported by Bedrock provider yet"),
+        Content::Audio(audio) => {
+            bedrock::ToolResultContentBlock::Text(audio_placeholder(&audio.mime_type))
+        }
+        Content::ResourceLink(link) => {
+            bedrock::ToolResultContentBlock::Text(resource_link_text(link))
+        }
         Content::Resource(resource) => bedrock::ToolResultContentBlock::Document(
             to_bedrock_document(tool_use_id, &resource.resource)?,
         ),
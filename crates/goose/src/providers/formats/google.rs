@@ -2,7 +2,7 @@ use crate::message::{Message, MessageContent};
 use crate::model::ModelConfig;
 use crate::providers::base::Usage;
 use crate::providers::errors::ProviderError;
-use crate::providers::utils::{is_valid_function_name, sanitize_function_name};
+use crate::providers::utils::{is_valid_function_name, resource_link_text, sanitize_function_name};
 use anyhow::Result;
 use mcp_core::content::Content;
 use mcp_core::role::Role;
@@ -28,6 +28,17 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                             parts.push(json!({"text": text.text}));
                         }
                     }
+                    MessageContent::Audio(audio) => {
+                        parts.push(json!({
+                            "inline_data": {
+                                "mime_type": audio.mime_type,
+                                "data": audio.data,
+                            }
+                        }));
+                    }
+                    MessageContent::ResourceLink(link) => {
+                        parts.push(json!({"text": resource_link_text(link)}));
+                    }
                     MessageContent::ToolRequest(request) => match &request.tool_call {
                         Ok(tool_call) => {
                             let mut function_call_part = Map::new();
@@ -74,6 +85,18 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                                                 }
                                             }));
                                         }
+                                        Content::Audio(audio) => {
+                                            parts.push(json!({
+                                                "inline_data": {
+                                                    "mime_type": audio.mime_type,
+                                                    "data": audio.data,
+                                                }
+                                            }));
+                                        }
+                                        Content::ResourceLink(link) => {
+                                            tool_content
+                                                .push(Content::text(resource_link_text(&link)));
+                                        }
                                         _ => {
                                             tool_content.push(content);
                                         }
@@ -87,7 +110,15 @@ pub fn format_messages(messages: &[Message]) -> Vec<Value> {
                                     })
                                     .collect::<Vec<_>>()
                                     .join("\n");
-                                if !tool_content.is_empty() {
+                                if let Some(structured) = &response.structured_content {
+                                    // Structured results are passed on as the JSON they are
+                                    parts.push(json!({
+                                        "functionResponse": {
+                                            "name": response.id,
+                                            "response": structured,
+                                        }}
+                                    ));
+                                } else if !tool_content.is_empty() {
                                     if text.is_empty() {
                                         text = "Tool call is done.".to_string();
                                     }
@@ -315,6 +346,7 @@ pub fn create_request(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use mcp_core::protocol::CallToolResult;
     use serde_json::json;
 
     fn set_up_text_message(text: &str, role: Role) -> Message {
@@ -345,13 +377,13 @@ mod tests {
     }
 
     fn set_up_tool(name: &str, description: &str, params: Value) -> Tool {
-        Tool {
-            name: name.to_string(),
-            description: description.to_string(),
-            input_schema: json!({
+        Tool::new(
+            name,
+            description,
+            json!({
                 "properties": params
             }),
-        }
+        )
     }
 
     #[test]
@@ -415,6 +447,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_to_google_spec_audio_and_resource_link() {
+        let messages = vec![
+            Message::user().with_audio("base64audio", "audio/ogg"),
+            set_up_tool_response_message(
+                "response_id",
+                vec![Content::resource_link("file:///notes.md", "notes")],
+            ),
+        ];
+        let payload = format_messages(&messages);
+        assert_eq!(
+            payload[0]["parts"][0]["inline_data"]["mime_type"],
+            "audio/ogg"
+        );
+        assert_eq!(
+            payload[1]["parts"][0]["functionResponse"]["response"]["content"]["text"],
+            "Resource notes (file:///notes.md)"
+        );
+    }
+
+    #[test]
+    fn test_message_to_google_spec_structured_tool_result() {
+        let result = CallToolResult {
+            content: vec![Content::text("3 rows")],
+            is_error: None,
+            structured_content: Some(json!({"rows": [1, 2, 3]})),
+        };
+        let messages = vec![Message::user().with_tool_result("response_id", Ok(result))];
+        let payload = format_messages(&messages);
+        assert_eq!(
+            payload[0]["parts"][0]["functionResponse"]["response"],
+            json!({"rows": [1, 2, 3]})
+        );
+    }
+
     #[test]
     fn test_message_to_google_spec_tool_result_multiple_texts() {
         let tool_result: Vec<Content> = vec![
@@ -488,13 +555,13 @@ mod tests {
 
     #[test]
     fn test_tools_to_google_spec_with_empty_properties() {
-        let tools = vec![Tool {
-            name: "tool1".to_string(),
-            description: "description1".to_string(),
-            input_schema: json!({
+        let tools = vec![Tool::new(
+            "tool1",
+            "description1",
+            json!({
                 "properties": {}
             }),
-        }];
+        )];
         let result = format_tools(&tools);
         assert_eq!(result.len(), 1);
         assert_eq!(result[0]["name"], "tool1");
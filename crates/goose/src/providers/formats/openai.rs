@@ -3,8 +3,8 @@ use crate::model::ModelConfig;
 use crate::providers::base::Usage;
 use crate::providers::errors::ProviderError;
 use crate::providers::utils::{
-    convert_image, detect_image_path, is_valid_function_name, load_image_file,
-    sanitize_function_name, ImageFormat,
+    convert_audio, convert_image, detect_image_path, is_valid_function_name, load_image_file,
+    resource_link_text, sanitize_function_name, ImageFormat,
 };
 use anyhow::{anyhow, Error};
 use mcp_core::ToolError;
@@ -108,6 +108,24 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                                             "content": [convert_image(&image, image_format)]
                                         }));
                                     }
+                                    Content::Audio(audio) => match convert_audio(&audio) {
+                                        Some(input_audio) => {
+                                            tool_content.push(Content::text("This tool result included audio that is uploaded in the next message."));
+                                            image_messages.push(json!({
+                                                "role": "user",
+                                                "content": [input_audio]
+                                            }));
+                                        }
+                                        None => {
+                                            tool_content.push(Content::text(format!(
+                                                "This tool result included audio in an unsupported format ({}).",
+                                                audio.mime_type
+                                            )));
+                                        }
+                                    },
+                                    Content::ResourceLink(link) => {
+                                        tool_content.push(Content::text(resource_link_text(&link)));
+                                    }
                                     Content::Resource(resource) => {
                                         tool_content.push(Content::text(resource.get_text()));
                                     }
@@ -116,14 +134,18 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                                     }
                                 }
                             }
-                            let tool_response_content: Value = json!(tool_content
-                                .iter()
-                                .map(|content| match content {
-                                    Content::Text(text) => text.text.clone(),
-                                    _ => String::new(),
-                                })
-                                .collect::<Vec<String>>()
-                                .join(" "));
+                            // Structured results are passed on as the JSON they are
+                            let tool_response_content: Value = match &response.structured_content {
+                                Some(structured) => json!(structured.to_string()),
+                                None => json!(tool_content
+                                    .iter()
+                                    .map(|content| match content {
+                                        Content::Text(text) => text.text.clone(),
+                                        _ => String::new(),
+                                    })
+                                    .collect::<Vec<String>>()
+                                    .join(" ")),
+                            };
 
                             // First add the tool response with all content
                             output.push(json!({
@@ -151,6 +173,19 @@ pub fn format_messages(messages: &[Message], image_format: &ImageFormat) -> Vec<
                     // Handle direct image content
                     converted["content"] = json!([convert_image(image, image_format)]);
                 }
+                MessageContent::Audio(audio) => {
+                    // Only wav and mp3 can be sent as input audio
+                    converted["content"] = match convert_audio(audio) {
+                        Some(input_audio) => json!([input_audio]),
+                        None => json!(format!(
+                            "Audio in an unsupported format ({})",
+                            audio.mime_type
+                        )),
+                    };
+                }
+                MessageContent::ResourceLink(link) => {
+                    converted["content"] = json!(resource_link_text(link));
+                }
             }
         }
 
@@ -190,6 +225,51 @@ pub fn format_tools(tools: &[Tool]) -> anyhow::Result<Vec<Value>> {
     Ok(result)
 }
 
+/// Convert the `tool_calls` of an OpenAI message to tool requests
+fn tool_requests(tool_calls: &[Value]) -> Vec<MessageContent> {
+    let mut content = Vec::new();
+    for tool_call in tool_calls {
+        let id = tool_call["id"].as_str().unwrap_or_default().to_string();
+        let function_name = tool_call["function"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let mut arguments = tool_call["function"]["arguments"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        // If arguments is empty, we will have invalid json parsing error later.
+        if arguments.is_empty() {
+            arguments = "{}".to_string();
+        }
+
+        if !is_valid_function_name(&function_name) {
+            let error = ToolError::NotFound(format!(
+                "The provided function name '{}' had invalid characters, it must match this regex [a-zA-Z0-9_-]+",
+                function_name
+            ));
+            content.push(MessageContent::tool_request(id, Err(error)));
+        } else {
+            match serde_json::from_str::<Value>(&arguments) {
+                Ok(params) => {
+                    content.push(MessageContent::tool_request(
+                        id,
+                        Ok(ToolCall::new(&function_name, params)),
+                    ));
+                }
+                Err(e) => {
+                    let error = ToolError::InvalidParameters(format!(
+                        "Could not interpret tool use parameters for id {}: {}",
+                        id, e
+                    ));
+                    content.push(MessageContent::tool_request(id, Err(error)));
+                }
+            }
+        }
+    }
+    content
+}
+
 /// Convert OpenAI's API response to internal Message format
 pub fn response_to_message(response: Value) -> anyhow::Result<Message> {
     let original = response["choices"][0]["message"].clone();
@@ -203,45 +283,7 @@ pub fn response_to_message(response: Value) -> anyhow::Result<Message> {
 
     if let Some(tool_calls) = original.get("tool_calls") {
         if let Some(tool_calls_array) = tool_calls.as_array() {
-            for tool_call in tool_calls_array {
-                let id = tool_call["id"].as_str().unwrap_or_default().to_string();
-                let function_name = tool_call["function"]["name"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string();
-                let mut arguments = tool_call["function"]["arguments"]
-                    .as_str()
-                    .unwrap_or_default()
-                    .to_string();
-                // If arguments is empty, we will have invalid json parsing error later.
-                if arguments.is_empty() {
-                    arguments = "{}".to_string();
-                }
-
-                if !is_valid_function_name(&function_name) {
-                    let error = ToolError::NotFound(format!(
-                        "The provided function name '{}' had invalid characters, it must match this regex [a-zA-Z0-9_-]+",
-                        function_name
-                    ));
-                    content.push(MessageContent::tool_request(id, Err(error)));
-                } else {
-                    match serde_json::from_str::<Value>(&arguments) {
-                        Ok(params) => {
-                            content.push(MessageContent::tool_request(
-                                id,
-                                Ok(ToolCall::new(&function_name, params)),
-                            ));
-                        }
-                        Err(e) => {
-                            let error = ToolError::InvalidParameters(format!(
-                                "Could not interpret tool use parameters for id {}: {}",
-                                id, e
-                            ));
-                            content.push(MessageContent::tool_request(id, Err(error)));
-                        }
-                    }
-                }
-            }
+            content.extend(tool_requests(tool_calls_array));
         }
     }
 
@@ -252,6 +294,77 @@ pub fn response_to_message(response: Value) -> anyhow::Result<Message> {
     })
 }
 
+// The text of a message content, a string or an array of parts of which only the text is kept
+fn content_text(content: &Value) -> Option<String> {
+    match content {
+        Value::String(text) => Some(text.clone()),
+        Value::Array(parts) => {
+            let texts: Vec<&str> = parts
+                .iter()
+                .filter(|part| part["type"] == "text")
+                .filter_map(|part| part["text"].as_str())
+                .collect();
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+        _ => None,
+    }
+}
+
+/// Convert messages in OpenAI's chat format back to internal Messages, the inverse of
+/// [`format_messages`]
+///
+/// System messages and content other than text are left out. Tool results and the user
+/// messages following them are joined into one user message, as goose sends them.
+pub fn messages_from_openai(messages: &[Value]) -> anyhow::Result<Vec<Message>> {
+    let mut converted: Vec<Message> = Vec::new();
+    for message in messages {
+        let role = message["role"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Message without a role: {}", message))?;
+        let text = content_text(&message["content"]).filter(|text| !text.is_empty());
+
+        let content = match role {
+            "system" | "developer" => continue,
+            "assistant" => {
+                let mut assistant = Message::assistant();
+                if let Some(text) = text {
+                    assistant.content.push(MessageContent::text(text));
+                }
+                if let Some(tool_calls) = message["tool_calls"].as_array() {
+                    assistant.content.extend(tool_requests(tool_calls));
+                }
+                converted.push(assistant);
+                continue;
+            }
+            "user" => text.map(MessageContent::text),
+            "tool" => {
+                let id = message["tool_call_id"]
+                    .as_str()
+                    .ok_or_else(|| anyhow!("Tool message without a tool_call_id"))?;
+                let text = text.unwrap_or_default();
+                let result = match text
+                    .strip_prefix("The tool call returned the following error:\n")
+                    .or_else(|| text.strip_prefix("Error: "))
+                {
+                    Some(error) => Err(ToolError::ExecutionError(error.to_string())),
+                    None => Ok(vec![Content::text(text.clone())]),
+                };
+                Some(MessageContent::tool_response(id, result))
+            }
+            other => return Err(anyhow!("Unknown message role '{}'", other)),
+        };
+
+        if converted.last().is_none_or(|last| last.role != Role::User) {
+            converted.push(Message::user());
+        }
+        if let (Some(content), Some(last)) = (content, converted.last_mut()) {
+            last.content.push(content);
+        }
+    }
+    converted.retain(|message| !message.content.is_empty());
+    Ok(converted)
+}
+
 pub fn get_usage(data: &Value) -> Result<Usage, ProviderError> {
     let usage = data
         .get("usage")
@@ -426,6 +539,7 @@ pub fn create_request(
 mod tests {
     use super::*;
     use mcp_core::content::Content;
+    use mcp_core::protocol::CallToolResult;
     use serde_json::json;
 
     #[test]
@@ -535,6 +649,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_format_messages_with_audio() -> anyhow::Result<()> {
+        let message = Message::user().with_audio("base64audio", "audio/wav");
+        let spec = format_messages(&[message], &ImageFormat::OpenAi);
+
+        assert_eq!(spec.len(), 1);
+        assert_eq!(spec[0]["content"][0]["type"], "input_audio");
+        assert_eq!(spec[0]["content"][0]["input_audio"]["format"], "wav");
+
+        let message =
+            Message::assistant().with_tool_request("tool1", Ok(ToolCall::new("record", json!({}))));
+        let response = Message::user().with_tool_response(
+            "tool1",
+            Ok(vec![
+                Content::audio("base64audio", "audio/mpeg"),
+                Content::resource_link("file:///notes.md", "notes"),
+            ]),
+        );
+        let spec = format_messages(&[message, response], &ImageFormat::OpenAi);
+
+        assert_eq!(spec.len(), 3);
+        assert_eq!(spec[1]["role"], "tool");
+        assert!(spec[1]["content"]
+            .as_str()
+            .unwrap()
+            .contains("Resource notes (file:///notes.md)"));
+        assert_eq!(spec[2]["role"], "user");
+        assert_eq!(spec[2]["content"][0]["input_audio"]["format"], "mp3");
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_messages_with_structured_content() -> anyhow::Result<()> {
+        let result = CallToolResult {
+            content: vec![Content::text("3 rows")],
+            is_error: None,
+            structured_content: Some(json!({"rows": [1, 2, 3]})),
+        };
+        let message = Message::user().with_tool_result("tool1", Ok(result));
+        let spec = format_messages(&[message], &ImageFormat::OpenAi);
+
+        assert_eq!(spec.len(), 1);
+        assert_eq!(spec[0]["role"], "tool");
+        assert_eq!(spec[0]["content"], r#"{"rows":[1,2,3]}"#);
+        Ok(())
+    }
+
     #[test]
     fn test_format_tools() -> anyhow::Result<()> {
         let tool = Tool::new(
@@ -908,4 +1069,46 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_messages_from_openai() -> anyhow::Result<()> {
+        let messages = vec![
+            Message::user().with_text("List the files"),
+            Message::assistant().with_text("Sure").with_tool_request(
+                "1",
+                Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+            ),
+            Message::user().with_tool_response("1", Ok(vec![Content::text("a.txt")])),
+            Message::assistant().with_text("There is one file"),
+        ];
+        let mut spec = vec![json!({"role": "system", "content": "Be brief"})];
+        spec.extend(format_messages(&messages, &ImageFormat::OpenAi));
+
+        let converted = messages_from_openai(&spec)?;
+        assert_eq!(converted.len(), messages.len());
+        for (converted, message) in converted.iter().zip(&messages) {
+            assert_eq!(converted.role, message.role);
+            assert_eq!(converted.content, message.content);
+        }
+
+        // Failed tool calls keep their error, user messages join the tool results before them
+        let converted = messages_from_openai(&[
+            json!({"role": "tool", "tool_call_id": "2", "content": "Error: not found"}),
+            json!({"role": "user", "content": [{"type": "text", "text": "Try again"}]}),
+        ])?;
+        assert_eq!(converted.len(), 1);
+        assert_eq!(
+            converted[0].content,
+            vec![
+                MessageContent::tool_response(
+                    "2",
+                    Err(ToolError::ExecutionError("not found".to_string()))
+                ),
+                MessageContent::text("Try again"),
+            ]
+        );
+
+        assert!(messages_from_openai(&[json!({"role": "robot", "content": "beep"})]).is_err());
+        Ok(())
+    }
 }
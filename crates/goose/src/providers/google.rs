@@ -122,12 +122,24 @@ impl Provider for GoogleProvider {
         tools: &[Tool],
     ) -> Result<(Message, ProviderUsage), ProviderError> {
         // Skip tools for specific Gemini models known to have issues with them
-        let is_gemini_with_tool_issues = self.model.model_name.to_lowercase().contains("gemini-2.0-flash") || 
-                                        self.model.model_name.to_lowercase().contains("gemini-2.0-pro-exp");
-        
+        let is_gemini_with_tool_issues = self
+            .model
+            .model_name
+            .to_lowercase()
+            .contains("gemini-2.0-flash")
+            || self
+                .model
+                .model_name
+                .to_lowercase()
+                .contains("gemini-2.0-pro-exp");
+
         // Use empty tools array for problematic models
-        let effective_tools = if is_gemini_with_tool_issues { &[] } else { tools };
-        
+        let effective_tools = if is_gemini_with_tool_issues {
+            &[]
+        } else {
+            tools
+        };
+
         let payload = create_request(&self.model, system, messages, effective_tools)?;
 
         // Make request
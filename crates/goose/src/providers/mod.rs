@@ -14,6 +14,7 @@ pub mod oauth;
 pub mod ollama;
 pub mod openai;
 pub mod openrouter;
+pub mod redacting;
 pub mod utils;
 
 pub use factory::{create, providers};
@@ -94,12 +94,18 @@ impl TokenCache {
     }
 }
 
-async fn get_workspace_endpoints(host: &str) -> Result<OidcEndpoints> {
-    let base_url = Url::parse(host).expect("Invalid host URL");
-    let oidc_url = base_url
-        .join("oidc/.well-known/oauth-authorization-server")
-        .expect("Invalid OIDC URL");
+/// Databricks workspaces serve their authorization server metadata under `oidc/`
+fn workspace_oidc_url(host: &str) -> Result<Url> {
+    Ok(Url::parse(host)?.join("oidc/.well-known/oauth-authorization-server")?)
+}
+
+/// MCP servers serve their authorization server metadata at the root of their host
+fn mcp_oidc_url(server_url: &str) -> Result<Url> {
+    Ok(Url::parse(server_url)?.join("/.well-known/oauth-authorization-server")?)
+}
 
+/// Fetch the endpoints from the authorization server metadata at `oidc_url`
+async fn get_endpoints(oidc_url: Url) -> Result<OidcEndpoints> {
     let client = reqwest::Client::new();
     let resp = client.get(oidc_url.clone()).send().await?;
 
@@ -371,6 +377,35 @@ pub(crate) async fn get_oauth_token_async(
     client_id: &str,
     redirect_url: &str,
     scopes: &[String],
+) -> Result<String> {
+    get_token(
+        host,
+        workspace_oidc_url(host)?,
+        client_id,
+        redirect_url,
+        scopes,
+    )
+    .await
+}
+
+/// Get a token for a remote MCP server, cached separately for each server
+pub(crate) async fn get_mcp_oauth_token_async(
+    server_url: &str,
+    client_id: &str,
+    redirect_url: &str,
+    scopes: &[String],
+) -> Result<String> {
+    let oidc_url = mcp_oidc_url(server_url)?;
+    get_token(server_url, oidc_url, client_id, redirect_url, scopes).await
+}
+
+/// Get a token from the cache, by refreshing the cached one or by running the flow in the browser
+async fn get_token(
+    host: &str,
+    oidc_url: Url,
+    client_id: &str,
+    redirect_url: &str,
+    scopes: &[String],
 ) -> Result<String> {
     // Acquire the global mutex to ensure only one OAuth flow runs at a time
     let _guard = OAUTH_MUTEX.lock().await;
@@ -397,7 +432,7 @@ pub(crate) async fn get_oauth_token_async(
         // Token is expired or has no expiration, try to refresh if we have a refresh token
         if let Some(refresh_token) = token.refresh_token {
             // Get endpoints for token refresh
-            match get_workspace_endpoints(host).await {
+            match get_endpoints(oidc_url.clone()).await {
                 Ok(endpoints) => {
                     let flow = OAuthFlow::new(
                         endpoints,
@@ -436,7 +471,7 @@ pub(crate) async fn get_oauth_token_async(
     }
 
     // Get endpoints and execute flow for a new token
-    let endpoints = get_workspace_endpoints(host).await?;
+    let endpoints = get_endpoints(oidc_url).await?;
     let flow = OAuthFlow::new(
         endpoints,
         client_id.to_string(),
@@ -475,7 +510,7 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let endpoints = get_workspace_endpoints(&mock_server.uri()).await?;
+        let endpoints = get_endpoints(workspace_oidc_url(&mock_server.uri())?).await?;
 
         assert_eq!(
             endpoints.authorization_endpoint,
@@ -486,6 +521,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_mcp_server_endpoints() -> Result<()> {
+        let mock_server = MockServer::start().await;
+
+        let mock_response = serde_json::json!({
+            "authorization_endpoint": "https://example.com/authorize",
+            "token_endpoint": "https://example.com/token"
+        });
+
+        Mock::given(method("GET"))
+            .and(path("/.well-known/oauth-authorization-server"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&mock_response))
+            .mount(&mock_server)
+            .await;
+
+        let server_url = format!("{}/mcp/sse", mock_server.uri());
+        let endpoints = get_endpoints(mcp_oidc_url(&server_url)?).await?;
+
+        assert_eq!(
+            endpoints.authorization_endpoint,
+            "https://example.com/authorize"
+        );
+        assert_eq!(endpoints.token_endpoint, "https://example.com/token");
+
+        Ok(())
+    }
+
     #[test]
     fn test_token_cache() -> Result<()> {
         let cache = TokenCache::new(
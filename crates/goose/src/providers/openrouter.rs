@@ -166,10 +166,10 @@ fn create_request_based_on_model(
 ) -> anyhow::Result<Value, Error> {
     // For Gemini models accessed through OpenRouter, disable tools to avoid compatibility issues
     let is_gemini = model_config.model_name.to_lowercase().contains("gemini");
-    
+
     // Skip tools for Gemini models
     let effective_tools = if is_gemini { &[] } else { tools };
-    
+
     let mut payload = create_request(
         model_config,
         system,
@@ -10,7 +10,7 @@ use std::io::Read;
 use std::path::Path;
 
 use crate::providers::errors::{OpenAIError, ProviderError};
-use mcp_core::content::ImageContent;
+use mcp_core::content::{AudioContent, ImageContent, ResourceLink};
 
 #[derive(serde::Deserialize)]
 struct OpenAIErrorResponse {
@@ -43,6 +43,30 @@ pub fn convert_image(image: &ImageContent, image_format: &ImageFormat) -> Value
     }
 }
 
+/// Convert an audio content into an OpenAI input_audio json, if its format is supported
+pub fn convert_audio(audio: &AudioContent) -> Option<Value> {
+    let format = match audio.mime_type.as_str() {
+        "audio/wav" | "audio/x-wav" | "audio/wave" => "wav",
+        "audio/mpeg" | "audio/mp3" => "mp3",
+        _ => return None,
+    };
+    Some(json!({
+        "type": "input_audio",
+        "input_audio": {
+            "data": audio.data,
+            "format": format,
+        }
+    }))
+}
+
+/// Describe a resource link as text, for providers that can not fetch the resource
+pub fn resource_link_text(link: &ResourceLink) -> String {
+    match &link.description {
+        Some(description) => format!("Resource {} ({}): {}", link.name, link.uri, description),
+        None => format!("Resource {} ({})", link.name, link.uri),
+    }
+}
+
 /// Handle response from OpenAI compatible endpoints
 /// Error codes: https://platform.openai.com/docs/guides/error-codes
 /// Context window exceeded: https://community.openai.com/t/help-needed-tackling-context-length-limits-in-openai-models/617543
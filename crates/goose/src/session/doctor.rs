@@ -0,0 +1,213 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use mcp_core::handler::ToolError;
+use mcp_core::role::Role;
+
+use crate::message::{Message, MessageContent};
+
+/// Something wrong with the conversation of a session, which providers would reject
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// A tool request without a response in the next message
+    UnansweredToolRequest {
+        message_index: usize,
+        id: String,
+    },
+    /// A tool response without a request in the message before
+    UnrequestedToolResponse {
+        message_index: usize,
+        id: String,
+    },
+    EmptyMessage {
+        message_index: usize,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnansweredToolRequest { message_index, id } => write!(
+                f,
+                "message {}: tool request {} has no response",
+                message_index, id
+            ),
+            Problem::UnrequestedToolResponse { message_index, id } => write!(
+                f,
+                "message {}: tool response {} answers no request",
+                message_index, id
+            ),
+            Problem::EmptyMessage { message_index } => {
+                write!(f, "message {}: the message is empty", message_index)
+            }
+        }
+    }
+}
+
+fn tool_request_ids(message: Option<&Message>) -> Vec<String> {
+    message
+        .into_iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolRequest(request) => Some(request.id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn tool_response_ids(message: Option<&Message>) -> HashSet<String> {
+    message
+        .into_iter()
+        .flat_map(|message| &message.content)
+        .filter_map(|content| match content {
+            MessageContent::ToolResponse(response) => Some(response.id.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Find what is wrong with the conversation of a session
+pub fn diagnose(messages: &[Message]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (index, message) in messages.iter().enumerate() {
+        if message.content.is_empty() {
+            problems.push(Problem::EmptyMessage {
+                message_index: index,
+            });
+        }
+
+        let requested = tool_request_ids(index.checked_sub(1).map(|i| &messages[i]));
+        for id in tool_response_ids(Some(message)) {
+            if !requested.contains(&id) {
+                problems.push(Problem::UnrequestedToolResponse {
+                    message_index: index,
+                    id,
+                });
+            }
+        }
+
+        let answered = tool_response_ids(messages.get(index + 1));
+        for id in tool_request_ids(Some(message)) {
+            if !answered.contains(&id) {
+                problems.push(Problem::UnansweredToolRequest {
+                    message_index: index,
+                    id,
+                });
+            }
+        }
+    }
+    problems
+}
+
+/// Fix the problems [`diagnose`] finds
+///
+/// Unanswered tool requests get an error response, as if the tool call had failed,
+/// responses without a request are dropped and so are messages left empty.
+pub fn repair(messages: &[Message]) -> Vec<Message> {
+    let mut repaired: Vec<Message> = Vec::new();
+    for message in messages {
+        let mut message = message.clone();
+        let requested = tool_request_ids(repaired.last());
+        message.content.retain(|content| match content {
+            MessageContent::ToolResponse(response) => requested.contains(&response.id),
+            _ => true,
+        });
+
+        let answered = tool_response_ids(Some(&message));
+        let unanswered: Vec<String> = requested
+            .into_iter()
+            .filter(|id| !answered.contains(id))
+            .collect();
+        if !unanswered.is_empty() {
+            if message.role == Role::User {
+                // Tool responses come before anything else the user says
+                let responses = unanswered.into_iter().map(missing_response);
+                message.content.splice(0..0, responses);
+            } else {
+                repaired.push(responses_to(unanswered));
+            }
+        }
+
+        if !message.content.is_empty() {
+            repaired.push(message);
+        }
+    }
+
+    let unanswered = tool_request_ids(repaired.last());
+    if !unanswered.is_empty() {
+        repaired.push(responses_to(unanswered));
+    }
+    repaired
+}
+
+fn missing_response(id: String) -> MessageContent {
+    MessageContent::tool_response(
+        id,
+        Err(ToolError::ExecutionError(
+            "The result of this tool call was lost".to_string(),
+        )),
+    )
+}
+
+fn responses_to(ids: Vec<String>) -> Message {
+    let mut message = Message::user();
+    message.content = ids.into_iter().map(missing_response).collect();
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn shell(id: &str) -> Message {
+        Message::assistant().with_tool_request(
+            id,
+            Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+        )
+    }
+
+    #[test]
+    fn test_repairs_orphaned_tool_calls() {
+        let messages = vec![
+            Message::user().with_text("List the files"),
+            shell("1"),
+            Message::user().with_tool_response("2", Ok(vec![])),
+            Message::assistant().with_text("There are no files"),
+            Message::user().with_text("Try again"),
+            shell("3"),
+        ];
+
+        assert_eq!(
+            diagnose(&messages),
+            vec![
+                Problem::UnansweredToolRequest {
+                    message_index: 1,
+                    id: "1".to_string()
+                },
+                Problem::UnrequestedToolResponse {
+                    message_index: 2,
+                    id: "2".to_string()
+                },
+                Problem::UnansweredToolRequest {
+                    message_index: 5,
+                    id: "3".to_string()
+                },
+            ]
+        );
+
+        let repaired = repair(&messages);
+        assert!(diagnose(&repaired).is_empty());
+        assert_eq!(repaired.len(), 7);
+        assert_eq!(
+            tool_response_ids(repaired.get(2)),
+            HashSet::from(["1".to_string()])
+        );
+        assert_eq!(
+            tool_response_ids(repaired.last()),
+            HashSet::from(["3".to_string()])
+        );
+        assert_eq!(repair(&repaired), repaired);
+    }
+}
@@ -0,0 +1,185 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::Value;
+
+use crate::config::{Config, ConfigError};
+
+// The secret in the keyring holding the key sessions are encrypted with
+const KEY_SECRET: &str = "GOOSE_SESSION_KEY";
+
+// Marks an encrypted line, plaintext lines are JSON and start with `{`
+pub(super) const PREFIX: &str = "encrypted:";
+
+/// Encrypts the lines of session files with AES-256-GCM
+///
+/// Every line is encrypted on its own, with a random nonce, so encrypted sessions can
+/// still be appended to line by line.
+pub struct SessionCipher {
+    key: LessSafeKey,
+    random: SystemRandom,
+}
+
+impl SessionCipher {
+    /// A cipher from the base64 encoded 256 bit key
+    pub fn new(key: &str) -> Result<Self> {
+        let key = base64::prelude::BASE64_STANDARD.decode(key.trim())?;
+        let key = UnboundKey::new(&AES_256_GCM, &key)
+            .map_err(|_| anyhow::anyhow!("The session key must be 256 bits long"))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            random: SystemRandom::new(),
+        })
+    }
+
+    /// A new random key, base64 encoded
+    pub fn generate_key() -> Result<String> {
+        let mut key = [0u8; 32];
+        SystemRandom::new()
+            .fill(&mut key)
+            .map_err(|_| anyhow::anyhow!("Failed to generate a session key"))?;
+        Ok(base64::prelude::BASE64_STANDARD.encode(key))
+    }
+
+    /// Encrypt a line, the result is a single line as well
+    pub fn encrypt(&self, line: &[u8]) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        self.random
+            .fill(&mut nonce)
+            .map_err(|_| anyhow::anyhow!("Failed to generate a nonce"))?;
+
+        let mut sealed = line.to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut sealed,
+            )
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt session data"))?;
+        sealed.splice(0..0, nonce);
+        Ok(format!(
+            "{}{}",
+            PREFIX,
+            base64::prelude::BASE64_STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Decrypt a line written by [`SessionCipher::encrypt`]
+    pub fn decrypt(&self, line: &str) -> Result<Vec<u8>> {
+        let sealed = line
+            .strip_prefix(PREFIX)
+            .ok_or_else(|| anyhow::anyhow!("The line is not encrypted"))?;
+        let mut sealed = base64::prelude::BASE64_STANDARD.decode(sealed.trim_end())?;
+        if sealed.len() < NONCE_LEN {
+            return Err(anyhow::anyhow!("The encrypted line is too short"));
+        }
+
+        let mut encrypted = sealed.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&sealed)
+            .map_err(|_| anyhow::anyhow!("Invalid nonce"))?;
+        let line = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut encrypted)
+            .map_err(|_| {
+                anyhow::anyhow!("Failed to decrypt session data, the session key is wrong")
+            })?;
+        Ok(line.to_vec())
+    }
+}
+
+pub fn is_encrypted(line: &str) -> bool {
+    line.starts_with(PREFIX)
+}
+
+fn required(cipher: Option<&SessionCipher>) -> Result<&SessionCipher> {
+    cipher.ok_or_else(|| {
+        anyhow::anyhow!(
+            "The session is encrypted, but there is no {} in the keyring",
+            KEY_SECRET
+        )
+    })
+}
+
+/// Parse a line of a session file, decrypting it if needed
+pub fn decode_line(line: &str, cipher: Option<&SessionCipher>) -> Result<Value> {
+    if !is_encrypted(line) {
+        return Ok(serde_json::from_str(line)?);
+    }
+    Ok(serde_json::from_slice(&required(cipher)?.decrypt(line)?)?)
+}
+
+/// Read text that may have been encrypted, like the description of a session
+pub fn decode_text(text: &str, cipher: Option<&SessionCipher>) -> Result<String> {
+    if !is_encrypted(text) {
+        return Ok(text.to_string());
+    }
+    Ok(String::from_utf8(required(cipher)?.decrypt(text)?)?)
+}
+
+// The key, or that there is none, is kept once read, to not go to the keyring for every
+// session
+static CIPHER: Mutex<Option<Option<Arc<SessionCipher>>>> = Mutex::new(None);
+
+/// The cipher for the session key in the keyring, none if there is no key yet
+///
+/// With `create`, a key is generated and kept in the keyring if there is none.
+pub fn keyring_cipher(create: bool) -> Result<Option<Arc<SessionCipher>>> {
+    let mut cipher = CIPHER.lock().unwrap();
+    match cipher.as_ref() {
+        Some(Some(cipher)) => return Ok(Some(Arc::clone(cipher))),
+        Some(None) if !create => return Ok(None),
+        _ => {}
+    }
+
+    let config = Config::global();
+    let key = match config.get_secret::<String>(KEY_SECRET) {
+        Ok(key) => key,
+        Err(ConfigError::NotFound(_)) if create => {
+            let key = SessionCipher::generate_key()?;
+            config.set_secret(KEY_SECRET, Value::String(key.clone()))?;
+            key
+        }
+        Err(ConfigError::NotFound(_)) => {
+            *cipher = Some(None);
+            return Ok(None);
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let created = Arc::new(SessionCipher::new(&key)?);
+    *cipher = Some(Some(Arc::clone(&created)));
+    Ok(Some(created))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_lines() -> Result<()> {
+        let cipher = SessionCipher::new(&SessionCipher::generate_key()?)?;
+        let line = br#"{"role":"user"}"#;
+
+        let encrypted = cipher.encrypt(line)?;
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains('\n'));
+        assert_ne!(encrypted, cipher.encrypt(line)?);
+        assert_eq!(cipher.decrypt(&encrypted)?, line);
+        assert_eq!(decode_line(&encrypted, Some(&cipher))?["role"], "user");
+        assert!(decode_line(&encrypted, None).is_err());
+
+        let description = cipher.encrypt("Code Migration".as_bytes())?;
+        assert_eq!(decode_text(&description, Some(&cipher))?, "Code Migration");
+        assert_eq!(decode_text("Code Migration", None)?, "Code Migration");
+        assert!(decode_text(&description, None).is_err());
+
+        // Another key or a cut off line cannot be decrypted
+        let other = SessionCipher::new(&SessionCipher::generate_key()?)?;
+        assert!(other.decrypt(&encrypted).is_err());
+        assert!(cipher.decrypt(&encrypted[..encrypted.len() - 4]).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,534 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::Result;
+use mcp_core::content::Content;
+use mcp_core::role::Role;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::storage::SessionMetadata;
+use super::store::StoredSession;
+use crate::message::{Message, MessageContent};
+
+/// The formats a session can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl FromStr for ExportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            "json" => Ok(ExportFormat::Json),
+            other => Err(anyhow::anyhow!(
+                "Unknown export format '{}', expected 'md', 'html' or 'json'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Markdown => write!(f, "md"),
+            ExportFormat::Html => write!(f, "html"),
+            ExportFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What to include in an export
+#[derive(Debug, Clone)]
+pub struct ExportOptions {
+    /// Include the thinking of the model
+    pub thinking: bool,
+    /// Cut tool results longer than this many characters
+    pub max_result_length: Option<usize>,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            thinking: false,
+            max_result_length: Some(1000),
+        }
+    }
+}
+
+/// Split a prefixed tool name like `developer__shell` into its extension and tool
+pub fn split_tool_name(name: &str) -> (String, String) {
+    match name.rsplit_once("__") {
+        Some((extension, tool)) => (extension.to_string(), tool.to_string()),
+        None => ("unknown".to_string(), name.to_string()),
+    }
+}
+
+/// Whether the content is meant to be shown to the user, as told by its audience
+pub fn is_for_user(content: &Content) -> bool {
+    content
+        .audience()
+        .is_none_or(|audience| audience.contains(&Role::User))
+}
+
+/// A part of a conversation as shown to people reading it
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    Text {
+        role: Role,
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    ToolCall {
+        id: String,
+        extension: String,
+        tool: String,
+        arguments: Value,
+    },
+    ToolResult {
+        id: String,
+        output: String,
+        is_error: bool,
+        truncated: bool,
+    },
+    /// Content shown by a short description, like images
+    Attachment {
+        role: Role,
+        description: String,
+    },
+}
+
+impl TranscriptEntry {
+    /// Who the entry comes from, tool calls and their results are shown as the assistant's
+    fn speaker(&self) -> Role {
+        match self {
+            TranscriptEntry::Text { role, .. } | TranscriptEntry::Attachment { role, .. } => {
+                role.clone()
+            }
+            _ => Role::Assistant,
+        }
+    }
+}
+
+/// A session as shown to people reading it
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    pub session_id: String,
+    pub description: String,
+    pub working_dir: String,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+fn truncate(text: String, max_length: Option<usize>) -> (String, bool) {
+    match max_length {
+        Some(max) if text.chars().count() > max => {
+            (text.chars().take(max).collect::<String>() + "…", true)
+        }
+        _ => (text, false),
+    }
+}
+
+fn tool_output(contents: &[Content]) -> String {
+    contents
+        .iter()
+        .filter(|content| is_for_user(content))
+        .map(|content| match content {
+            Content::Text(text) => text.text.clone(),
+            Content::Image(image) => format!("[image: {}]", image.mime_type),
+            Content::Audio(audio) => format!("[audio: {}]", audio.mime_type),
+            Content::ResourceLink(link) => format!("[{}]({})", link.name, link.uri),
+            Content::Resource(resource) => resource.get_text(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Transcript {
+    pub fn new(
+        session_id: &str,
+        metadata: &SessionMetadata,
+        messages: &[Message],
+        options: &ExportOptions,
+    ) -> Self {
+        let mut entries = Vec::new();
+        for message in messages {
+            for content in &message.content {
+                let entry = match content {
+                    MessageContent::Text(text) => TranscriptEntry::Text {
+                        role: message.role.clone(),
+                        text: text.text.clone(),
+                    },
+                    MessageContent::Thinking(thinking) if options.thinking => {
+                        TranscriptEntry::Thinking {
+                            text: thinking.thinking.clone(),
+                        }
+                    }
+                    MessageContent::RedactedThinking(_) if options.thinking => {
+                        TranscriptEntry::Thinking {
+                            text: "Thinking was redacted".to_string(),
+                        }
+                    }
+                    MessageContent::ToolRequest(request) => match &request.tool_call {
+                        Ok(call) => {
+                            let (extension, tool) = split_tool_name(&call.name);
+                            TranscriptEntry::ToolCall {
+                                id: request.id.clone(),
+                                extension,
+                                tool,
+                                arguments: call.arguments.clone(),
+                            }
+                        }
+                        Err(e) => TranscriptEntry::ToolResult {
+                            id: request.id.clone(),
+                            output: e.to_string(),
+                            is_error: true,
+                            truncated: false,
+                        },
+                    },
+                    MessageContent::ToolResponse(response) => {
+                        let (output, is_error) = match &response.tool_result {
+                            Ok(contents) => (tool_output(contents), false),
+                            Err(e) => (e.to_string(), true),
+                        };
+                        let (output, truncated) = truncate(output, options.max_result_length);
+                        TranscriptEntry::ToolResult {
+                            id: response.id.clone(),
+                            output,
+                            is_error,
+                            truncated,
+                        }
+                    }
+                    MessageContent::Image(image) => TranscriptEntry::Attachment {
+                        role: message.role.clone(),
+                        description: format!("Image ({})", image.mime_type),
+                    },
+                    MessageContent::Audio(audio) => TranscriptEntry::Attachment {
+                        role: message.role.clone(),
+                        description: format!("Audio ({})", audio.mime_type),
+                    },
+                    MessageContent::ResourceLink(link) => TranscriptEntry::Attachment {
+                        role: message.role.clone(),
+                        description: format!("{} ({})", link.name, link.uri),
+                    },
+                    _ => continue,
+                };
+                entries.push(entry);
+            }
+        }
+
+        Self {
+            session_id: session_id.to_string(),
+            description: metadata.description.clone(),
+            working_dir: metadata.working_dir.display().to_string(),
+            entries,
+        }
+    }
+
+    fn title(&self) -> &str {
+        if self.description.trim().is_empty() {
+            &self.session_id
+        } else {
+            self.description.trim()
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title());
+        out.push_str(&format!(
+            "Session `{}` in `{}`\n",
+            self.session_id, self.working_dir
+        ));
+
+        let mut speaker = None;
+        for entry in &self.entries {
+            if speaker.as_ref() != Some(&entry.speaker()) {
+                speaker = Some(entry.speaker());
+                out.push_str(&format!("\n## {}\n", speaker_name(&entry.speaker())));
+            }
+            out.push('\n');
+
+            match entry {
+                TranscriptEntry::Text { text, .. } => {
+                    out.push_str(text.trim_end());
+                    out.push('\n');
+                }
+                TranscriptEntry::Thinking { text } => {
+                    for line in text.trim_end().lines() {
+                        out.push_str(&format!("> {}\n", line));
+                    }
+                }
+                TranscriptEntry::ToolCall {
+                    extension,
+                    tool,
+                    arguments,
+                    ..
+                } => {
+                    out.push_str(&format!("**{}** | {}\n\n", tool, extension));
+                    out.push_str(&fenced(
+                        "json",
+                        &serde_json::to_string_pretty(arguments).unwrap_or_default(),
+                    ));
+                }
+                TranscriptEntry::ToolResult {
+                    output,
+                    is_error,
+                    truncated,
+                    ..
+                } => {
+                    out.push_str(if *is_error {
+                        "Error:\n\n"
+                    } else {
+                        "Result:\n\n"
+                    });
+                    out.push_str(&fenced("", output));
+                    if *truncated {
+                        out.push_str("\n_Output truncated_\n");
+                    }
+                }
+                TranscriptEntry::Attachment { description, .. } => {
+                    out.push_str(&format!("_{}_\n", description));
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut body = String::new();
+        body.push_str(&format!("<h1>{}</h1>\n", escape_html(self.title())));
+        body.push_str(&format!(
+            "<p class=\"session\">Session <code>{}</code> in <code>{}</code></p>\n",
+            escape_html(&self.session_id),
+            escape_html(&self.working_dir)
+        ));
+
+        let mut speaker = None;
+        for entry in &self.entries {
+            if speaker.as_ref() != Some(&entry.speaker()) {
+                if speaker.is_some() {
+                    body.push_str("</section>\n");
+                }
+                speaker = Some(entry.speaker());
+                let name = speaker_name(&entry.speaker());
+                body.push_str(&format!(
+                    "<section class=\"{}\">\n<h2>{}</h2>\n",
+                    name.to_lowercase(),
+                    name
+                ));
+            }
+
+            match entry {
+                TranscriptEntry::Text { text, .. } => {
+                    body.push_str(&format!(
+                        "<div class=\"text\">{}</div>\n",
+                        escape_html(text)
+                    ));
+                }
+                TranscriptEntry::Thinking { text } => {
+                    body.push_str(&format!(
+                        "<details class=\"thinking\"><summary>Thinking</summary><div class=\"text\">{}</div></details>\n",
+                        escape_html(text)
+                    ));
+                }
+                TranscriptEntry::ToolCall {
+                    extension,
+                    tool,
+                    arguments,
+                    ..
+                } => {
+                    body.push_str(&format!(
+                        "<div class=\"tool-call\"><strong>{}</strong> | {}<pre>{}</pre></div>\n",
+                        escape_html(tool),
+                        escape_html(extension),
+                        escape_html(&serde_json::to_string_pretty(arguments).unwrap_or_default())
+                    ));
+                }
+                TranscriptEntry::ToolResult {
+                    output,
+                    is_error,
+                    truncated,
+                    ..
+                } => {
+                    body.push_str(&format!(
+                        "<details class=\"tool-result{}\"><summary>{}{}</summary><pre>{}</pre></details>\n",
+                        if *is_error { " error" } else { "" },
+                        if *is_error { "Error" } else { "Result" },
+                        if *truncated { " (truncated)" } else { "" },
+                        escape_html(output)
+                    ));
+                }
+                TranscriptEntry::Attachment { description, .. } => {
+                    body.push_str(&format!(
+                        "<p class=\"attachment\"><em>{}</em></p>\n",
+                        escape_html(description)
+                    ));
+                }
+            }
+        }
+        if speaker.is_some() {
+            body.push_str("</section>\n");
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape_html(self.title()),
+            HTML_STYLE,
+            body
+        )
+    }
+}
+
+const HTML_STYLE: &str = "
+body { font-family: sans-serif; max-width: 50em; margin: 2em auto; line-height: 1.5; }
+section { border-left: 3px solid #ccc; padding-left: 1em; margin: 1em 0; }
+section.goose { border-color: #7c5cff; }
+.text { white-space: pre-wrap; }
+pre { background: #f4f4f4; padding: 0.5em; overflow-x: auto; }
+.error summary { color: #b00020; }
+.session, .thinking { color: #666; }
+";
+
+fn speaker_name(role: &Role) -> &'static str {
+    match role {
+        Role::User => "User",
+        Role::Assistant => "Goose",
+    }
+}
+
+/// A fenced code block long enough not to be closed by backticks in the text
+fn fenced(language: &str, text: &str) -> String {
+    let mut fence = "```".to_string();
+    while text.contains(&fence) {
+        fence.push('`');
+    }
+    format!("{}{}\n{}\n{}\n", fence, language, text.trim_end(), fence)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render a stored session in the given format
+pub fn export(
+    session: &StoredSession,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<String> {
+    let transcript = Transcript::new(
+        session.id(),
+        &session.read_metadata()?,
+        &session.read_messages()?,
+        options,
+    );
+    match format {
+        ExportFormat::Markdown => Ok(transcript.to_markdown()),
+        ExportFormat::Html => Ok(transcript.to_html()),
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(&transcript)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("List the files"),
+            Message::assistant()
+                .with_thinking("The shell can list them", "signature")
+                .with_tool_request(
+                    "1",
+                    Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+                ),
+            Message::user().with_tool_response(
+                "1",
+                Ok(vec![
+                    Content::text("a.txt <b.txt>").with_audience(vec![Role::User]),
+                    Content::text("only for the model").with_audience(vec![Role::Assistant]),
+                ]),
+            ),
+            Message::assistant().with_text("There are two files"),
+        ]
+    }
+
+    #[test]
+    fn test_markdown_export() {
+        let metadata = SessionMetadata {
+            description: "Listing files".to_string(),
+            ..Default::default()
+        };
+        let transcript = Transcript::new(
+            "test",
+            &metadata,
+            &conversation(),
+            &ExportOptions::default(),
+        );
+        let markdown = transcript.to_markdown();
+
+        assert!(markdown.starts_with("# Listing files\n"));
+        assert!(markdown.contains("## User\n\nList the files\n"));
+        assert!(markdown.contains("**shell** | developer"));
+        assert!(markdown.contains("\"command\": \"ls\""));
+        assert!(markdown.contains("a.txt <b.txt>"));
+        assert!(!markdown.contains("only for the model"));
+        assert!(!markdown.contains("The shell can list them"));
+        assert_eq!(markdown.matches("## Goose").count(), 1);
+    }
+
+    #[test]
+    fn test_export_options() {
+        let options = ExportOptions {
+            thinking: true,
+            max_result_length: Some(5),
+        };
+        let transcript = Transcript::new(
+            "test",
+            &SessionMetadata::default(),
+            &conversation(),
+            &options,
+        );
+
+        assert!(transcript
+            .to_markdown()
+            .contains("> The shell can list them"));
+        assert!(transcript.entries.iter().any(|entry| matches!(
+            entry,
+            TranscriptEntry::ToolResult { output, truncated: true, .. } if output == "a.txt…"
+        )));
+
+        let html = transcript.to_html();
+        assert!(html.contains("<title>test</title>"));
+        assert!(html.contains("a.txt…"));
+        assert!(!html.contains("<b.txt>"));
+
+        let json: Value =
+            serde_json::from_str(&serde_json::to_string(&transcript).unwrap()).unwrap();
+        assert_eq!(json["entries"][0]["type"], "text");
+        assert_eq!(json["entries"][2]["tool"], "shell");
+    }
+
+    #[test]
+    fn test_export_format_from_str() {
+        assert_eq!(
+            "md".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Markdown
+        );
+        assert_eq!("HTML".parse::<ExportFormat>().unwrap(), ExportFormat::Html);
+        assert!("pdf".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::Result;
+use chrono::Utc;
+use mcp_core::role::Role;
+
+use super::storage::{generate_session_id, SessionParent};
+use super::store::StoredSession;
+use crate::message::{Message, MessageContent};
+
+/// Whether the message is a prompt from the user, rather than tool results sent back
+fn is_user_prompt(message: &Message) -> bool {
+    message.role == Role::User
+        && !message
+            .content
+            .iter()
+            .any(|content| matches!(content, MessageContent::ToolResponse(_)))
+}
+
+fn has_tool_requests(message: &Message) -> bool {
+    message
+        .content
+        .iter()
+        .any(|content| matches!(content, MessageContent::ToolRequest(_)))
+}
+
+/// The number of messages to keep to drop the last `turns` user turns, and the number of
+/// turns that drops
+///
+/// A turn starts with a prompt from the user and runs until the next one, so every tool
+/// request is dropped or kept together with its response. Dropping more turns than there
+/// are drops all messages, and only the turns there were.
+pub fn rewind_point(messages: &[Message], turns: usize) -> (usize, usize) {
+    let prompts: Vec<usize> = messages
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, message)| is_user_prompt(message))
+        .map(|(index, _)| index)
+        .take(turns)
+        .collect();
+    match prompts.len() {
+        0 => (messages.len(), 0),
+        dropped if dropped < turns => (0, dropped),
+        dropped => (prompts[dropped - 1], dropped),
+    }
+}
+
+/// Start a new session in the same store from the first `at_message_index` messages of
+/// `source`, the new session records `source` as its parent
+///
+/// Tool requests at the end of the history, whose responses would be cut off, are left out.
+pub fn fork(source: &StoredSession, at_message_index: usize) -> Result<StoredSession> {
+    let messages = source.read_messages()?;
+    if at_message_index > messages.len() {
+        return Err(anyhow::anyhow!(
+            "Cannot fork session {} at message {}, it has {} messages",
+            source.id(),
+            at_message_index,
+            messages.len()
+        ));
+    }
+
+    let mut end = at_message_index;
+    while end > 0 && has_tool_requests(&messages[end - 1]) {
+        end -= 1;
+    }
+    let history = &messages[..end];
+
+    let store = source.store();
+    let mut id = generate_session_id();
+    let mut attempt = 1;
+    while store.exists(&id)? {
+        id = format!("{}_{}", generate_session_id(), attempt);
+        attempt += 1;
+    }
+
+    let mut metadata = source.read_metadata()?;
+    metadata.message_count = history.len();
+    metadata.total_tokens = None;
+    metadata.created_at = Some(Utc::now());
+    metadata.archived = false;
+    metadata.parent = Some(SessionParent {
+        session_id: source.id().to_string(),
+        message_index: end,
+    });
+    store.create(&id, &metadata)?;
+    store.append(&id, history)?;
+
+    Ok(StoredSession::new(store.clone(), id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::JsonlStore;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn conversation() -> Vec<Message> {
+        vec![
+            Message::user().with_text("List the files"),
+            Message::assistant().with_tool_request(
+                "1",
+                Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+            ),
+            Message::user().with_tool_response("1", Ok(vec![])),
+            Message::assistant().with_text("There are no files"),
+            Message::user().with_text("Create one"),
+            Message::assistant().with_text("Done"),
+        ]
+    }
+
+    #[test]
+    fn test_rewind_point_keeps_tool_exchanges_whole() {
+        let messages = conversation();
+        assert_eq!(rewind_point(&messages, 0), (6, 0));
+        assert_eq!(rewind_point(&messages, 1), (4, 1));
+        assert_eq!(rewind_point(&messages, 2), (0, 2));
+        assert_eq!(rewind_point(&messages, 5), (0, 2));
+        assert_eq!(rewind_point(&[], 1), (0, 0));
+    }
+
+    #[test]
+    fn test_fork_records_parent() -> Result<()> {
+        let dir = tempdir()?;
+        let source = StoredSession::new(Arc::new(JsonlStore::new(dir.path())), "source");
+        source.store().append("source", &conversation())?;
+
+        let forked = fork(&source, 4)?;
+        assert_ne!(forked.id(), source.id());
+        assert_eq!(forked.read_messages()?, conversation()[..4]);
+        let parent = forked.read_metadata()?.parent.unwrap();
+        assert_eq!(parent.session_id, "source");
+        assert_eq!(parent.message_index, 4);
+
+        // A tool request without its response is left out
+        let forked = fork(&source, 2)?;
+        assert_eq!(forked.read_messages()?, conversation()[..1]);
+
+        assert!(fork(&source, 7).is_err());
+        assert_eq!(source.read_messages()?, conversation());
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde_json::Value;
+
+use super::doctor::repair;
+use super::storage::{validate_session_id, SessionMetadata};
+use super::store::{SessionStore, StoredSession};
+use crate::message::Message;
+use crate::providers::formats::{anthropic, openai};
+use crate::redaction::Redactor;
+
+/// The formats conversations can be imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// The messages of OpenAI's chat completions API
+    OpenAi,
+    /// The messages of Anthropic's messages API
+    Anthropic,
+}
+
+impl FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "openai" => Ok(ImportFormat::OpenAi),
+            "anthropic" => Ok(ImportFormat::Anthropic),
+            other => Err(anyhow::anyhow!(
+                "Unknown import format '{}', expected 'openai' or 'anthropic'",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFormat::OpenAi => write!(f, "openai"),
+            ImportFormat::Anthropic => write!(f, "anthropic"),
+        }
+    }
+}
+
+// Content block types only Anthropic's format has
+const ANTHROPIC_BLOCKS: &[&str] = &[
+    "tool_use",
+    "tool_result",
+    "thinking",
+    "redacted_thinking",
+    "image",
+];
+
+impl ImportFormat {
+    /// Tell the format from the messages, conversations with nothing but text are read the
+    /// same in both and taken as OpenAI's
+    pub fn detect(messages: &[Value]) -> Self {
+        let anthropic = messages.iter().any(|message| {
+            message["content"].as_array().is_some_and(|blocks| {
+                blocks.iter().any(|block| {
+                    block["type"]
+                        .as_str()
+                        .is_some_and(|kind| ANTHROPIC_BLOCKS.contains(&kind))
+                })
+            })
+        });
+        if anthropic {
+            ImportFormat::Anthropic
+        } else {
+            ImportFormat::OpenAi
+        }
+    }
+}
+
+/// Read the messages of a conversation exported from another tool
+///
+/// The JSON is either an array of messages or a request body with the messages under
+/// `messages`. Without a `format` it is detected, see [`ImportFormat::detect`]. Tool calls
+/// left without results are repaired like [`repair`] does, and secrets are redacted like
+/// in saved sessions.
+pub fn parse_conversation(json: &str, format: Option<ImportFormat>) -> Result<Vec<Message>> {
+    let conversation: Value = serde_json::from_str(json)?;
+    let messages = match &conversation {
+        Value::Array(messages) => messages,
+        Value::Object(body) => body
+            .get("messages")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("The conversation has no messages array"))?,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "The conversation must be an array of messages or an object with messages"
+            ))
+        }
+    };
+
+    let messages = match format.unwrap_or_else(|| ImportFormat::detect(messages)) {
+        ImportFormat::OpenAi => openai::messages_from_openai(messages)?,
+        ImportFormat::Anthropic => anthropic::messages_from_anthropic(messages)?,
+    };
+    let messages = repair(&messages);
+    Ok(match Redactor::global() {
+        Some(redactor) => redactor.redact_messages(&messages).0,
+        None => messages,
+    })
+}
+
+/// Save imported `messages` as the new session `id` of `store`
+pub fn import(
+    store: &Arc<dyn SessionStore>,
+    id: &str,
+    messages: &[Message],
+    mut metadata: SessionMetadata,
+) -> Result<StoredSession> {
+    validate_session_id(id)?;
+    if store.exists(id)? {
+        return Err(anyhow::anyhow!("A session named '{}' already exists", id));
+    }
+    metadata.message_count = messages.len();
+    store.create(id, &metadata)?;
+    store.append(id, messages)?;
+    Ok(StoredSession::new(store.clone(), id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageContent;
+    use crate::session::JsonlStore;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_import_conversations() -> Result<()> {
+        let openai = r#"{"model": "gpt-4o", "messages": [
+            {"role": "system", "content": "Be brief"},
+            {"role": "user", "content": "List the files"},
+            {"role": "assistant", "content": null, "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "shell", "arguments": "{\"command\": \"ls\"}"}
+            }]},
+            {"role": "tool", "tool_call_id": "call_1", "content": "a.txt"},
+            {"role": "assistant", "content": "There is one file"}
+        ]}"#;
+        let anthropic = r#"[
+            {"role": "user", "content": "List the files"},
+            {"role": "assistant", "content": [
+                {"type": "tool_use", "id": "call_1", "name": "shell", "input": {"command": "ls"}}
+            ]},
+            {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": "call_1", "content": "a.txt"}
+            ]},
+            {"role": "assistant", "content": [{"type": "text", "text": "There is one file"}]}
+        ]"#;
+
+        let from_openai = parse_conversation(openai, None)?;
+        let from_anthropic = parse_conversation(anthropic, None)?;
+        assert_eq!(from_openai.len(), 4);
+        for (a, b) in from_openai.iter().zip(&from_anthropic) {
+            assert_eq!(a.role, b.role);
+            assert_eq!(a.content, b.content);
+        }
+        assert!(matches!(
+            from_openai[1].content[0],
+            MessageContent::ToolRequest(_)
+        ));
+
+        // A conversation cut off after a tool call is repaired
+        let cut_off: Vec<Value> = serde_json::from_str(anthropic)?;
+        let cut_off = serde_json::to_string(&cut_off[..2])?;
+        let repaired = parse_conversation(&cut_off, Some(ImportFormat::Anthropic))?;
+        assert_eq!(repaired.len(), 3);
+
+        let dir = tempdir()?;
+        let store: Arc<dyn SessionStore> = Arc::new(JsonlStore::new(dir.path()));
+        let stored = import(&store, "imported", &from_openai, SessionMetadata::default())?;
+        assert_eq!(stored.read_messages()?.len(), 4);
+        assert_eq!(stored.read_metadata()?.message_count, 4);
+        assert!(import(&store, "imported", &from_openai, SessionMetadata::default()).is_err());
+        assert!(import(
+            &store,
+            "../escaped",
+            &from_openai,
+            SessionMetadata::default()
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,651 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::encryption::{decode_line, is_encrypted, SessionCipher};
+use super::migration::{migrate_message, migrate_metadata, schema_version, SCHEMA_VERSION};
+use super::storage::SessionMetadata;
+use super::store::{unsaved, SessionInfo, SessionStore, Unsaved};
+use crate::message::Message;
+use crate::redaction::{Redactions, Redactor};
+
+/// Keeps every session in a JSONL file of its own, `{id}.jsonl`, with a line per message
+///
+/// Messages are only ever appended, and the metadata is kept next to them in
+/// `{id}.meta.json`, so saving a session never rewrites what is already on disk. Files
+/// that are rewritten, when messages are replaced or the metadata changes, are written to
+/// a temporary file first and renamed over the old one. Writers hold the lock file
+/// `{id}.lock`, so two processes resuming the same session do not interleave their writes.
+/// Lock files are left in place when sessions are deleted or renamed, as a writer waiting
+/// for one would otherwise go on to lock a file no one else sees.
+///
+/// Sessions written by older versions keep their metadata on the first line of the JSONL
+/// file, and are read through the migrations to the current schema. They are rewritten in
+/// the current schema, with a metadata file, before they are first written to.
+///
+/// With a [`SessionCipher`], every line and the metadata file can be encrypted, see
+/// [`JsonlStore::with_cipher`].
+pub struct JsonlStore {
+    dir: PathBuf,
+    cipher: Option<Arc<SessionCipher>>,
+    encrypt: bool,
+}
+
+impl JsonlStore {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            cipher: None,
+            encrypt: false,
+        }
+    }
+
+    /// Decrypt encrypted sessions with `cipher`, and if `encrypt` is set also encrypt
+    /// everything written
+    ///
+    /// Sessions written without encryption are encrypted as a whole before they are first
+    /// written to.
+    pub fn with_cipher(mut self, cipher: Arc<SessionCipher>, encrypt: bool) -> Self {
+        self.cipher = Some(cipher);
+        self.encrypt = encrypt;
+        self
+    }
+
+    fn cipher(&self) -> Option<&SessionCipher> {
+        self.cipher.as_deref()
+    }
+
+    /// A value as written on a line of its own, without the line break
+    fn encode<T: Serialize>(&self, value: &T) -> Result<Vec<u8>> {
+        let line = serde_json::to_vec(value)?;
+        match self.cipher() {
+            Some(cipher) if self.encrypt => Ok(cipher.encrypt(&line)?.into_bytes()),
+            _ => Ok(line),
+        }
+    }
+
+    fn to_lines(&self, messages: &[Message]) -> Result<Vec<u8>> {
+        let mut lines = Vec::new();
+        for message in messages {
+            lines.extend(self.encode(message)?);
+            lines.push(b'\n');
+        }
+        Ok(lines)
+    }
+
+    fn metadata_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.meta.json", id))
+    }
+
+    fn lock_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.lock", id))
+    }
+
+    /// Lock the session against other writers until the returned file is dropped
+    fn lock(&self, id: &str) -> Result<File> {
+        fs::create_dir_all(&self.dir)?;
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_path(id))?;
+        file.lock()?;
+        Ok(file)
+    }
+
+    /// Add messages to the end of the session, with the lock held
+    fn append_locked(&self, id: &str, messages: &[Message]) -> Result<()> {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(self.path(id))?;
+        repair_last_line(&mut file, self.cipher())?;
+
+        // Write the messages at once, so an interruption can cut off at most the last line
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(&self.to_lines(messages)?)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn write_metadata(&self, id: &str, metadata: &SessionMetadata) -> Result<()> {
+        write_atomically(&self.metadata_path(id), &self.encode(metadata)?)
+    }
+
+    fn write_messages(&self, id: &str, messages: &[Message]) -> Result<()> {
+        write_atomically(&self.path(id), &self.to_lines(messages)?)
+    }
+
+    fn stored_metadata(&self, id: &str) -> Result<Option<Value>> {
+        stored_metadata(&self.path(id), &self.metadata_path(id), self.cipher())
+    }
+
+    /// Bring the files of a session up to date before writing to it
+    ///
+    /// A session from an older version, or one not encrypted yet when encrypting, is
+    /// rewritten, and a session without a metadata file gets one.
+    fn upgrade(&self, id: &str) -> Result<()> {
+        let stored = self.stored_metadata(id)?;
+        let current = stored.as_ref().map(schema_version) == Some(SCHEMA_VERSION);
+        let unencrypted = self.encrypt
+            && !fs::read_to_string(self.metadata_path(id)).is_ok_and(|m| is_encrypted(&m));
+        if self.path(id).exists() && (!current || unencrypted) {
+            // The messages go first, their migrations can run again if writing the
+            // metadata is interrupted
+            let metadata = self.read_metadata(id)?;
+            self.write_messages(id, &self.read_messages(id)?)?;
+            self.write_metadata(id, &metadata)?;
+        } else if stored.is_none() {
+            self.write_metadata(id, &SessionMetadata::default())?;
+        }
+        Ok(())
+    }
+}
+
+/// Replace the file at `path` with `contents`, all at once even if writing is interrupted
+fn write_atomically(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Make sure the session file ends with a complete line, so appended lines start on one
+/// of their own
+///
+/// A write that was interrupted can leave part of a message on the last line, which is
+/// cut off. A last line that is a whole message only gets its line break.
+fn repair_last_line(file: &mut File, cipher: Option<&SessionCipher>) -> Result<()> {
+    if file.metadata()?.len() == 0 {
+        return Ok(());
+    }
+    let mut last = [0];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    if last[0] == b'\n' {
+        return Ok(());
+    }
+
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(0))?;
+    file.read_to_end(&mut contents)?;
+    let line_start = contents
+        .iter()
+        .rposition(|&byte| byte == b'\n')
+        .map_or(0, |position| position + 1);
+    let complete = std::str::from_utf8(&contents[line_start..])
+        .is_ok_and(|line| decode_line(line, cipher).is_ok());
+    if complete {
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(b"\n")?;
+    } else {
+        tracing::warn!("Dropping the incomplete last line of a session file");
+        file.set_len(line_start as u64)?;
+    }
+    Ok(())
+}
+
+/// Whether the first line of a session file is the metadata older versions wrote there,
+/// rather than a message
+fn is_metadata(line: &Value) -> bool {
+    line.get("message_count").is_some()
+}
+
+/// The metadata of a session as it was written, from its metadata file or else the first
+/// line of its session file, none if it has neither
+fn stored_metadata(
+    session_file: &Path,
+    metadata_file: &Path,
+    cipher: Option<&SessionCipher>,
+) -> Result<Option<Value>> {
+    if metadata_file.exists() {
+        return Ok(Some(decode_line(
+            &fs::read_to_string(metadata_file)?,
+            cipher,
+        )?));
+    }
+    let file = match File::open(session_file) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    // Read just the first line
+    let mut first_line = String::new();
+    io::BufReader::new(file).read_line(&mut first_line)?;
+    Ok(decode_line(&first_line, cipher).ok().filter(is_metadata))
+}
+
+/// Read the messages of a session file written with schema `version`
+///
+/// An incomplete last line, left by a write that was interrupted, is skipped.
+fn read_messages(
+    session_file: &Path,
+    version: u32,
+    cipher: Option<&SessionCipher>,
+) -> Result<Vec<Message>> {
+    let file = match File::open(session_file) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let reader = io::BufReader::new(file);
+    let mut lines = reader.lines().enumerate().peekable();
+    let mut messages = Vec::new();
+
+    while let Some((index, line)) = lines.next() {
+        let line = line?;
+        let line = match decode_line(&line, cipher) {
+            Ok(line) => line,
+            // A missing key is no interrupted write
+            Err(e) if lines.peek().is_none() && (cipher.is_some() || !is_encrypted(&line)) => {
+                tracing::warn!("Skipping the incomplete last line of a session file: {}", e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        if index == 0 && is_metadata(&line) {
+            continue;
+        }
+        messages.push(migrate_message(line, version)?);
+    }
+
+    Ok(messages)
+}
+
+/// The number of messages of a session file written with schema `version`, and the last
+/// of them
+///
+/// The lines are counted without decoding them, only the first line, which may be the
+/// metadata of older versions, and the last message are decoded. An incomplete last line
+/// is skipped, as by [`read_messages`].
+fn last_message(
+    session_file: &Path,
+    version: u32,
+    cipher: Option<&SessionCipher>,
+) -> Result<Option<(usize, Message)>> {
+    let file = match File::open(session_file) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut reader = io::BufReader::new(file);
+    let mut count = 0;
+    let (mut previous, mut last, mut line) = (String::new(), String::new(), String::new());
+    while reader.read_line(&mut line)? > 0 {
+        if count == 0 && decode_line(trim_line(&line), cipher).is_ok_and(|l| is_metadata(&l)) {
+            line.clear();
+            continue;
+        }
+        count += 1;
+        std::mem::swap(&mut previous, &mut last);
+        std::mem::swap(&mut last, &mut line);
+        line.clear();
+    }
+
+    if count == 0 {
+        return Ok(None);
+    }
+    let line = match decode_line(trim_line(&last), cipher) {
+        Ok(line) => line,
+        // A missing key is no interrupted write
+        Err(e) if cipher.is_some() || !is_encrypted(&last) => {
+            tracing::warn!("Skipping the incomplete last line of a session file: {}", e);
+            count -= 1;
+            if count == 0 {
+                return Ok(None);
+            }
+            decode_line(trim_line(&previous), cipher)?
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(Some((count, migrate_message(line, version)?)))
+}
+
+/// A line as read by `read_line`, without its line break
+fn trim_line(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+/// Read the metadata of a session, the default metadata if it has none
+///
+/// The session was last updated when either of its files was last written.
+fn read_metadata(
+    session_file: &Path,
+    metadata_file: &Path,
+    cipher: Option<&SessionCipher>,
+) -> Result<SessionMetadata> {
+    let mut metadata = match stored_metadata(session_file, metadata_file, cipher)? {
+        Some(stored) => migrate_metadata(stored)?,
+        None => SessionMetadata::default(),
+    };
+
+    metadata.updated_at = [session_file, metadata_file]
+        .into_iter()
+        .filter_map(|path| path.metadata().and_then(|m| m.modified()).ok())
+        .max()
+        .map(DateTime::<Utc>::from);
+    Ok(metadata)
+}
+
+impl SessionStore for JsonlStore {
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", id))
+    }
+
+    fn exists(&self, id: &str) -> Result<bool> {
+        Ok(self.path(id).exists())
+    }
+
+    fn create(&self, id: &str, metadata: &SessionMetadata) -> Result<()> {
+        let _lock = self.lock(id)?;
+        self.write_metadata(id, metadata)?;
+        self.write_messages(id, &[])
+    }
+
+    fn append(&self, id: &str, messages: &[Message]) -> Result<()> {
+        let _lock = self.lock(id)?;
+        self.upgrade(id)?;
+        self.append_locked(id, messages)
+    }
+
+    fn replace_messages(&self, id: &str, messages: &[Message]) -> Result<()> {
+        let _lock = self.lock(id)?;
+        self.upgrade(id)?;
+        self.write_messages(id, messages)
+    }
+
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>> {
+        let version = self
+            .stored_metadata(id)?
+            .map_or(0, |stored| schema_version(&stored));
+        read_messages(&self.path(id), version, self.cipher())
+    }
+
+    fn last_message(&self, id: &str) -> Result<Option<(usize, Message)>> {
+        let version = self
+            .stored_metadata(id)?
+            .map_or(0, |stored| schema_version(&stored));
+        last_message(&self.path(id), version, self.cipher())
+    }
+
+    fn save(
+        &self,
+        id: &str,
+        messages: &[Message],
+        redactor: Option<&Redactor>,
+    ) -> Result<Redactions> {
+        let _lock = self.lock(id)?;
+        self.upgrade(id)?;
+        let (unsaved, redactions) = unsaved(self.last_message(id)?, messages, redactor);
+        match unsaved {
+            Unsaved::Append(messages) => self.append_locked(id, &messages)?,
+            Unsaved::Replace(messages) => self.write_messages(id, &messages)?,
+        }
+        Ok(redactions)
+    }
+
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata> {
+        read_metadata(&self.path(id), &self.metadata_path(id), self.cipher())
+    }
+
+    fn update_metadata(&self, id: &str, metadata: &SessionMetadata) -> Result<()> {
+        let _lock = self.lock(id)?;
+        self.upgrade(id)?;
+        self.write_metadata(id, metadata)?;
+        if !self.path(id).exists() {
+            self.write_messages(id, &[])?;
+        }
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<SessionInfo>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut sessions = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+            let Some(id) = path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+            else {
+                continue;
+            };
+
+            // A session that cannot be read should not hide all the others
+            let metadata = match self.read_metadata(&id) {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::warn!("Skipping session {}: {}", id, e);
+                    continue;
+                }
+            };
+            sessions.push(SessionInfo {
+                modified: metadata.updated_at.unwrap_or_default(),
+                metadata,
+                id,
+                path,
+            });
+        }
+
+        // Most recently modified first
+        sessions.sort_by_key(|session| std::cmp::Reverse(session.modified));
+        Ok(sessions)
+    }
+
+    fn rename(&self, id: &str, new_id: &str) -> Result<()> {
+        let _lock = self.lock(id)?;
+        let path = self.path(id);
+        let new_path = self.path(new_id);
+        if !path.exists() {
+            return Err(anyhow::anyhow!("No session named '{}'", id));
+        }
+        if new_path.exists() {
+            return Err(anyhow::anyhow!(
+                "A session named '{}' already exists",
+                new_id
+            ));
+        }
+        if self.metadata_path(id).exists() {
+            fs::rename(self.metadata_path(id), self.metadata_path(new_id))?;
+        }
+        fs::rename(path, new_path)?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        let _lock = self.lock(id)?;
+        for path in [self.path(id), self.metadata_path(id)] {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageContent;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_write_messages() -> Result<()> {
+        let dir = tempdir()?;
+        let store = JsonlStore::new(dir.path());
+
+        // Create some test messages
+        let messages = vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi there"),
+        ];
+
+        // Write messages
+        store.append("test", &messages)?;
+
+        // Read them back
+        let read_messages = store.read_messages("test")?;
+
+        // Compare
+        assert_eq!(messages.len(), read_messages.len());
+        for (orig, read) in messages.iter().zip(read_messages.iter()) {
+            assert_eq!(orig.role, read.role);
+            assert_eq!(orig.content.len(), read.content.len());
+
+            // Compare first text content
+            if let (Some(MessageContent::Text(orig_text)), Some(MessageContent::Text(read_text))) =
+                (orig.content.first(), read.content.first())
+            {
+                assert_eq!(orig_text.text, read_text.text);
+            } else {
+                panic!("Messages don't match expected structure");
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_file() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("empty.jsonl");
+        File::create(&file_path)?;
+
+        // Reading an empty file should return empty vec
+        let store = JsonlStore::new(dir.path());
+        assert!(store.read_messages("empty")?.is_empty());
+        assert!(store.read_messages("missing")?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_recovers_from_interrupted_write() -> Result<()> {
+        let dir = tempdir()?;
+        let store = JsonlStore::new(dir.path());
+        store.append("test", &[Message::user().with_text("Hello")])?;
+
+        // A write cut off in the middle of the second message
+        let line = serde_json::to_string(&Message::assistant().with_text("Hi there"))?;
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(store.path("test"))?;
+        file.write_all(&line.as_bytes()[..line.len() / 2])?;
+        assert_eq!(store.read_messages("test")?.len(), 1);
+
+        store.append("test", &[Message::user().with_text("Are you there?")])?;
+        let messages = store.read_messages("test")?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].as_concat_text(), "Are you there?");
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_message() -> Result<()> {
+        let dir = tempdir()?;
+        let store = JsonlStore::new(dir.path());
+        assert!(store.last_message("test")?.is_none());
+
+        let messages = vec![
+            Message::user().with_text("Hello"),
+            Message::assistant().with_text("Hi there"),
+        ];
+        store.append("test", &messages)?;
+        assert_eq!(store.last_message("test")?, Some((2, messages[1].clone())));
+
+        // A message cut off while written is not counted
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .open(store.path("test"))?;
+        file.write_all(br#"{"role":"user","#)?;
+        assert_eq!(store.last_message("test")?, Some((2, messages[1].clone())));
+
+        // Nor is the metadata older versions wrote on the first line
+        let mut contents = serde_json::to_vec(&serde_json::json!({"message_count": 1}))?;
+        contents.push(b'\n');
+        contents.extend(store.to_lines(&messages[..1])?);
+        fs::write(store.path("old"), contents)?;
+        assert_eq!(store.last_message("old")?, Some((1, messages[0].clone())));
+        Ok(())
+    }
+
+    #[test]
+    fn test_upgrades_sessions_from_older_versions() -> Result<()> {
+        let dir = tempdir()?;
+        let store = JsonlStore::new(dir.path());
+        let metadata = serde_json::json!({
+            "description": "Old session",
+            "message_count": 1,
+            "total_tokens": null
+        });
+        let mut contents = serde_json::to_vec(&metadata)?;
+        contents.push(b'\n');
+        contents.extend(store.to_lines(&[Message::user().with_text("Hello")])?);
+        fs::write(store.path("old"), contents)?;
+
+        let read = store.read_metadata("old")?;
+        assert_eq!(read.description, "Old session");
+        assert_eq!(read.schema_version, SCHEMA_VERSION);
+        assert_eq!(store.read_messages("old")?.len(), 1);
+        assert!(!store.metadata_path("old").exists());
+
+        // Writing to the session first moves its metadata to a file of its own
+        store.append("old", &[Message::assistant().with_text("Hi")])?;
+        let stored = store.stored_metadata("old")?.unwrap();
+        assert_eq!(schema_version(&stored), SCHEMA_VERSION);
+        assert_eq!(store.read_metadata("old")?.description, "Old session");
+        assert_eq!(store.read_messages("old")?.len(), 2);
+
+        // Neither the metadata nor the lock file is listed as a session
+        let ids: Vec<String> = store.list()?.into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["old"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encrypted_sessions() -> Result<()> {
+        let dir = tempdir()?;
+        let cipher = Arc::new(SessionCipher::new(&SessionCipher::generate_key()?)?);
+        let plain = JsonlStore::new(dir.path());
+        plain.append("test", &[Message::user().with_text("secret plans")])?;
+
+        // Writing with encryption encrypts the whole session
+        let store = JsonlStore::new(dir.path()).with_cipher(Arc::clone(&cipher), true);
+        store.append("test", &[Message::assistant().with_text("more secrets")])?;
+        for path in [store.path("test"), store.metadata_path("test")] {
+            assert!(!fs::read_to_string(path)?.contains("secret"));
+        }
+        let messages = store.read_messages("test")?;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].as_concat_text(), "secret plans");
+
+        // Encrypted sessions need the key, but not encryption turned on
+        assert!(plain.read_messages("test").is_err());
+        let decrypting = JsonlStore::new(dir.path()).with_cipher(cipher, false);
+        assert_eq!(decrypting.read_messages("test")?, messages);
+        assert_eq!(decrypting.list()?.len(), 1);
+        Ok(())
+    }
+}
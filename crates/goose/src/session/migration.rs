@@ -0,0 +1,118 @@
+use anyhow::Result;
+use serde_json::{json, Map, Value};
+
+use super::storage::{get_home_dir, SessionMetadata};
+use crate::message::Message;
+
+/// Upgrades a session from the version of the schema before it, working on the JSON of
+/// its metadata and each of its messages
+///
+/// Migrations must give the same result when run twice, as a file whose messages were
+/// upgraded can still have metadata of the older version if writing it was interrupted.
+struct Migration {
+    metadata: fn(&mut Map<String, Value>),
+    message: Option<fn(&mut Value)>,
+}
+
+// The migration to version `n` is at index `n - 1`
+const MIGRATIONS: &[Migration] = &[
+    // 1: the schema version is recorded
+    Migration {
+        metadata: fill_unversioned_fields,
+        message: None,
+    },
+];
+
+/// The version of the session schema written by this version of goose
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Give the fields added before the schema was versioned their defaults
+fn fill_unversioned_fields(metadata: &mut Map<String, Value>) {
+    // Sessions from before the working directory was recorded ran in the home directory
+    if metadata.get("working_dir").is_none_or(Value::is_null) {
+        metadata.insert("working_dir".to_string(), json!(get_home_dir()));
+    }
+    metadata.entry("tags").or_insert_with(|| json!([]));
+    metadata.entry("archived").or_insert(json!(false));
+}
+
+/// The schema version the metadata was written with, 0 for sessions from before it was
+/// recorded
+pub fn schema_version(metadata: &Value) -> u32 {
+    metadata
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |version| version as u32)
+}
+
+/// Fail for sessions written with a schema version this version of goose cannot read
+pub fn check_version(version: u32) -> Result<()> {
+    if version > SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "The session was written by a newer version of goose (schema version {}, this \
+             version reads up to {}), update goose to open it",
+            version,
+            SCHEMA_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Read metadata written with any schema version up to [`SCHEMA_VERSION`]
+pub fn migrate_metadata(metadata: Value) -> Result<SessionMetadata> {
+    let version = schema_version(&metadata);
+    check_version(version)?;
+    let Value::Object(mut metadata) = metadata else {
+        return Err(anyhow::anyhow!("Session metadata must be a JSON object"));
+    };
+
+    for migration in &MIGRATIONS[version as usize..] {
+        (migration.metadata)(&mut metadata);
+    }
+    metadata.insert("schema_version".to_string(), json!(SCHEMA_VERSION));
+    Ok(serde_json::from_value(Value::Object(metadata))?)
+}
+
+/// Read a message of a session written with schema `version`
+pub fn migrate_message(mut message: Value, version: u32) -> Result<Message> {
+    check_version(version)?;
+    for migration in &MIGRATIONS[version as usize..] {
+        if let Some(migrate) = migration.message {
+            migrate(&mut message);
+        }
+    }
+    Ok(serde_json::from_value(message)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_metadata() -> Result<()> {
+        let metadata = migrate_metadata(json!({
+            "description": "Old session",
+            "message_count": 2,
+            "total_tokens": null
+        }))?;
+        assert_eq!(metadata.schema_version, SCHEMA_VERSION);
+        assert_eq!(metadata.working_dir, get_home_dir());
+        assert!(metadata.tags.is_empty());
+
+        // Current metadata reads back unchanged
+        let value = serde_json::to_value(&metadata)?;
+        assert_eq!(schema_version(&value), SCHEMA_VERSION);
+        assert_eq!(
+            serde_json::to_value(migrate_metadata(value.clone())?)?,
+            value
+        );
+
+        let newer = json!({"schema_version": SCHEMA_VERSION + 1});
+        assert!(migrate_metadata(newer)
+            .unwrap_err()
+            .to_string()
+            .contains("newer version of goose"));
+        assert!(migrate_message(json!({}), SCHEMA_VERSION + 1).is_err());
+        Ok(())
+    }
+}
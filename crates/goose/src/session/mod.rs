@@ -1,8 +1,27 @@
+pub mod doctor;
+pub mod encryption;
+pub mod export;
+pub mod fork;
+pub mod import;
+pub mod jsonl;
+pub mod migration;
+pub mod search;
+pub mod sqlite;
 pub mod storage;
+pub mod store;
 
 // Re-export common session types and functions
+pub use doctor::{diagnose, repair, Problem};
+pub use encryption::SessionCipher;
+pub use export::{export, ExportFormat, ExportOptions, Transcript};
+pub use fork::{fork, rewind_point};
+pub use import::{import, parse_conversation, ImportFormat};
+pub use jsonl::JsonlStore;
+pub use migration::SCHEMA_VERSION;
+pub use search::{SearchHit, SearchIndex};
+pub use sqlite::SqliteStore;
 pub use storage::{
-    ensure_session_dir, generate_description, generate_session_id, get_most_recent_session,
-    get_path, list_sessions, persist_messages, read_messages, read_metadata, update_metadata,
-    Identifier, SessionMetadata,
+    ensure_session_dir, generate_session_id, validate_session_id, Identifier, SessionMetadata,
+    SessionParent,
 };
+pub use store::{default_store, SessionInfo, SessionStore, StoredSession};
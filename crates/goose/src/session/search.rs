@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use super::storage::SessionMetadata;
+use crate::message::{Message, MessageContent};
+
+// Parameters of the BM25 ranking
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+// How much text to show around the first match in a snippet
+const SNIPPET_BEFORE: usize = 60;
+const SNIPPET_AFTER: usize = 100;
+
+/// A match for a search over sessions, better matches have a higher score
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    /// The index of the matching message, none if the session description matched
+    pub message_index: Option<usize>,
+    pub description: String,
+    pub score: f64,
+    /// The matching text around the first match
+    pub snippet: String,
+}
+
+/// The text of a message that can be searched: its text and the names of the tools it calls
+pub fn searchable_text(message: &Message) -> String {
+    message
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            MessageContent::Text(text) => Some(text.text.clone()),
+            MessageContent::ToolRequest(request) => request
+                .tool_call
+                .as_ref()
+                .ok()
+                .map(|call| call.name.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Split text into lowercase words, with the byte offset of each word
+pub fn tokenize(text: &str) -> Vec<(usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push((s, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, text[s..].to_lowercase()));
+    }
+    tokens
+}
+
+/// The text around the first of `terms` found in `text`, on a single line
+pub fn snippet(text: &str, terms: &[String]) -> String {
+    let position = tokenize(text)
+        .into_iter()
+        .find(|(_, token)| terms.contains(token))
+        .map(|(position, _)| position)
+        .unwrap_or(0);
+
+    let mut start = position.saturating_sub(SNIPPET_BEFORE);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (position + SNIPPET_AFTER).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+
+    let mut snippet = text[start..end]
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < text.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+struct Document {
+    session_id: String,
+    message_index: Option<usize>,
+    text: String,
+    length: usize,
+}
+
+/// An in-memory full-text index over sessions
+///
+/// Every message and every session description is a document, documents containing all
+/// words of the query are ranked with BM25.
+#[derive(Default)]
+pub struct SearchIndex {
+    documents: Vec<Document>,
+    descriptions: HashMap<String, String>,
+    // For each word, the documents containing it and how often
+    postings: HashMap<String, Vec<(usize, usize)>>,
+    total_length: usize,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_session(&mut self, id: &str, metadata: &SessionMetadata, messages: &[Message]) {
+        self.descriptions
+            .insert(id.to_string(), metadata.description.clone());
+        self.add_document(id, None, metadata.description.clone());
+        for (index, message) in messages.iter().enumerate() {
+            self.add_document(id, Some(index), searchable_text(message));
+        }
+    }
+
+    fn add_document(&mut self, session_id: &str, message_index: Option<usize>, text: String) {
+        let tokens = tokenize(&text);
+        if tokens.is_empty() {
+            return;
+        }
+
+        let document = self.documents.len();
+        let mut frequencies: HashMap<String, usize> = HashMap::new();
+        for (_, token) in &tokens {
+            *frequencies.entry(token.clone()).or_default() += 1;
+        }
+        for (token, frequency) in frequencies {
+            self.postings
+                .entry(token)
+                .or_default()
+                .push((document, frequency));
+        }
+
+        self.total_length += tokens.len();
+        self.documents.push(Document {
+            session_id: session_id.to_string(),
+            message_index,
+            text,
+            length: tokens.len(),
+        });
+    }
+
+    /// The best `limit` documents containing every word of `query`
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let mut terms: Vec<String> = tokenize(query).into_iter().map(|(_, t)| t).collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() || self.documents.is_empty() {
+            return Vec::new();
+        }
+
+        let count = self.documents.len() as f64;
+        let average_length = self.total_length as f64 / count;
+
+        let mut scores: HashMap<usize, (usize, f64)> = HashMap::new();
+        for term in &terms {
+            let Some(postings) = self.postings.get(term) else {
+                return Vec::new();
+            };
+            let matching = postings.len() as f64;
+            let idf = ((count - matching + 0.5) / (matching + 0.5) + 1.0).ln();
+            for &(document, frequency) in postings {
+                let length = self.documents[document].length as f64;
+                let frequency = frequency as f64;
+                let score = idf * frequency * (K1 + 1.0)
+                    / (frequency + K1 * (1.0 - B + B * length / average_length));
+                let entry = scores.entry(document).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = scores
+            .into_iter()
+            .filter(|(_, (matched, _))| *matched == terms.len())
+            .map(|(document, (_, score))| (document, score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked.truncate(limit);
+
+        ranked
+            .into_iter()
+            .map(|(document, score)| {
+                let document = &self.documents[document];
+                SearchHit {
+                    session_id: document.session_id.clone(),
+                    message_index: document.message_index,
+                    description: self
+                        .descriptions
+                        .get(&document.session_id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    score,
+                    snippet: snippet(&document.text, &terms),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::ToolCall;
+    use serde_json::json;
+
+    #[test]
+    fn test_search_ranks_matching_messages() {
+        let mut index = SearchIndex::new();
+        let mut metadata = SessionMetadata {
+            description: "Fix Kafka consumer".to_string(),
+            ..Default::default()
+        };
+        index.add_session(
+            "kafka",
+            &metadata,
+            &[
+                Message::user().with_text("The kafka consumer keeps lagging behind"),
+                Message::assistant().with_tool_request(
+                    "1",
+                    Ok(ToolCall::new("developer__shell", json!({"command": "ls"}))),
+                ),
+            ],
+        );
+        metadata.description = "Plan the week".to_string();
+        index.add_session(
+            "other",
+            &metadata,
+            &[Message::user().with_text("What is on the agenda for the consumer meeting?")],
+        );
+
+        let hits = index.search("Kafka consumer", 10);
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.session_id == "kafka"));
+        assert!(hits[0].score >= hits[1].score);
+        assert_eq!(hits[0].description, "Fix Kafka consumer");
+
+        let hits = index.search("shell", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_index, Some(1));
+
+        assert!(index.search("kafka agenda", 10).is_empty());
+        assert!(index.search("", 10).is_empty());
+    }
+
+    #[test]
+    fn test_snippet_around_first_match() {
+        let text = format!("{} needle {}", "a ".repeat(100), "b ".repeat(100));
+        let around = snippet(&text, &["needle".to_string()]);
+        assert!(around.starts_with('…'));
+        assert!(around.ends_with('…'));
+        assert!(around.contains("needle"));
+
+        let text = "héllo wörld";
+        assert_eq!(snippet(text, &["wörld".to_string()]), text);
+    }
+}
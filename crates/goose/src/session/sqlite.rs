@@ -0,0 +1,634 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+
+use super::encryption::{decode_line, decode_text, is_encrypted, SessionCipher, PREFIX};
+use super::migration::{check_version, migrate_message, SCHEMA_VERSION};
+use super::search::{searchable_text, snippet, tokenize, SearchHit};
+use super::storage::{SessionMetadata, SessionParent};
+use super::store::{search_sessions, unsaved, SessionInfo, SessionStore, Unsaved};
+use crate::message::Message;
+use crate::redaction::{Redactions, Redactor};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS sessions (
+    id TEXT PRIMARY KEY,
+    working_dir TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    message_count INTEGER NOT NULL DEFAULT 0,
+    total_tokens INTEGER,
+    updated_at INTEGER NOT NULL,
+    parent_id TEXT,
+    parent_message_index INTEGER,
+    created_at INTEGER,
+    tags TEXT NOT NULL DEFAULT '[]',
+    archived INTEGER NOT NULL DEFAULT 0
+);
+CREATE INDEX IF NOT EXISTS sessions_by_updated_at ON sessions (updated_at);
+CREATE TABLE IF NOT EXISTS messages (
+    session_id TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    message TEXT NOT NULL,
+    PRIMARY KEY (session_id, seq)
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS message_search USING fts5 (
+    session_id UNINDEXED,
+    seq UNINDEXED,
+    text
+);
+";
+
+// Columns added to the sessions table before the schema version was recorded
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("parent_id", "TEXT"),
+    ("parent_message_index", "INTEGER"),
+    ("created_at", "INTEGER"),
+    ("tags", "TEXT NOT NULL DEFAULT '[]'"),
+    ("archived", "INTEGER NOT NULL DEFAULT 0"),
+];
+
+// How long to wait for another process writing to the database
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+// The columns read into the metadata of a session
+const METADATA_COLUMNS: &str = "working_dir, description, message_count, total_tokens, updated_at,
+    parent_id, parent_message_index, created_at, tags, archived";
+
+/// Keeps all sessions in one SQLite database
+///
+/// Messages are rows numbered within their session, so appending messages only inserts
+/// the new ones and updating the metadata leaves the messages alone. The text of messages
+/// and descriptions is kept in a full-text index for searching.
+///
+/// With a [`SessionCipher`], messages and descriptions can be encrypted, see
+/// [`SqliteStore::open_with_cipher`]. Encrypted text is left out of the full-text index, and
+/// a database holding any is searched by decrypting its sessions instead.
+pub struct SqliteStore {
+    path: PathBuf,
+    conn: Mutex<Connection>,
+    codec: Codec,
+}
+
+/// How messages and descriptions are stored, encrypted with a cipher or as they are
+#[derive(Default)]
+struct Codec {
+    cipher: Option<Arc<SessionCipher>>,
+    encrypt: bool,
+}
+
+impl Codec {
+    fn cipher(&self) -> Option<&SessionCipher> {
+        self.cipher.as_deref()
+    }
+
+    fn encode(&self, text: &str) -> Result<String> {
+        match self.cipher() {
+            Some(cipher) if self.encrypt => cipher.encrypt(text.as_bytes()),
+            _ => Ok(text.to_string()),
+        }
+    }
+
+    fn encode_message(&self, message: &Message) -> Result<String> {
+        self.encode(&serde_json::to_string(message)?)
+    }
+
+    fn decode_message(&self, stored: &str) -> Result<Message> {
+        Ok(serde_json::from_value(decode_line(stored, self.cipher())?)?)
+    }
+
+    fn decode_metadata(&self, mut metadata: SessionMetadata) -> Result<SessionMetadata> {
+        metadata.description = decode_text(&metadata.description, self.cipher())?;
+        Ok(metadata)
+    }
+
+    /// Index text in plaintext only, encrypted text stays out of the full-text index
+    fn index_text(&self, conn: &Connection, id: &str, seq: Option<i64>, text: &str) -> Result<()> {
+        match self.encrypt {
+            true => Ok(()),
+            false => index_text(conn, id, seq, text),
+        }
+    }
+}
+
+fn now() -> i64 {
+    Utc::now().timestamp_millis()
+}
+
+/// Make sure the session has a row, with default metadata if it is new
+fn ensure_session(tx: &Transaction, id: &str) -> Result<()> {
+    let metadata = SessionMetadata::default();
+    tx.execute(
+        "INSERT OR IGNORE INTO sessions (id, working_dir, created_at, updated_at)
+         VALUES (?1, ?2, ?3, ?3)",
+        params![id, metadata.working_dir.to_string_lossy(), now()],
+    )?;
+    Ok(())
+}
+
+fn insert_messages(
+    tx: &Transaction,
+    codec: &Codec,
+    id: &str,
+    first_seq: i64,
+    messages: &[Message],
+) -> Result<()> {
+    let mut insert =
+        tx.prepare("INSERT INTO messages (session_id, seq, message) VALUES (?1, ?2, ?3)")?;
+    for (seq, message) in (first_seq..).zip(messages) {
+        insert.execute(params![id, seq, codec.encode_message(message)?])?;
+        codec.index_text(tx, id, Some(seq), &searchable_text(message))?;
+    }
+    tx.execute(
+        "UPDATE sessions SET updated_at = ?2 WHERE id = ?1",
+        params![id, now()],
+    )?;
+    Ok(())
+}
+
+fn append_messages(tx: &Transaction, codec: &Codec, id: &str, messages: &[Message]) -> Result<()> {
+    ensure_session(tx, id)?;
+    let next_seq: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(seq) + 1, 0) FROM messages WHERE session_id = ?1",
+        [id],
+        |row| row.get(0),
+    )?;
+    insert_messages(tx, codec, id, next_seq, messages)
+}
+
+fn replace_messages(tx: &Transaction, codec: &Codec, id: &str, messages: &[Message]) -> Result<()> {
+    ensure_session(tx, id)?;
+    tx.execute("DELETE FROM messages WHERE session_id = ?1", [id])?;
+    tx.execute(
+        "DELETE FROM message_search WHERE session_id = ?1 AND seq IS NOT NULL",
+        [id],
+    )?;
+    insert_messages(tx, codec, id, 0, messages)
+}
+
+/// The number of messages in the session and the last of them
+fn last_message(conn: &Connection, codec: &Codec, id: &str) -> Result<Option<(usize, Message)>> {
+    let last = conn
+        .query_row(
+            "SELECT seq, message FROM messages WHERE session_id = ?1 ORDER BY seq DESC LIMIT 1",
+            [id],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+        )
+        .optional()?;
+
+    match last {
+        Some((seq, message)) => Ok(Some((seq as usize + 1, codec.decode_message(&message)?))),
+        None => Ok(None),
+    }
+}
+
+fn index_text(conn: &Connection, id: &str, seq: Option<i64>, text: &str) -> Result<()> {
+    if !text.trim().is_empty() {
+        conn.execute(
+            "INSERT INTO message_search (session_id, seq, text) VALUES (?1, ?2, ?3)",
+            params![id, seq, text],
+        )?;
+    }
+    Ok(())
+}
+
+/// Fill the full-text index from the sessions already in the database, leaving out what is
+/// encrypted
+fn index_sessions(conn: &Connection) -> Result<()> {
+    let mut query = conn.prepare("SELECT session_id, seq, message FROM messages")?;
+    let rows = query.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, String>(2)?,
+        ))
+    })?;
+    for row in rows {
+        let (id, seq, message) = row?;
+        if is_encrypted(&message) {
+            continue;
+        }
+        let message: Message = serde_json::from_str(&message)?;
+        index_text(conn, &id, Some(seq), &searchable_text(&message))?;
+    }
+
+    let mut query = conn.prepare("SELECT id, description FROM sessions")?;
+    let rows = query.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+    for row in rows {
+        let (id, description) = row?;
+        if !is_encrypted(&description) {
+            index_text(conn, &id, None, &description)?;
+        }
+    }
+    Ok(())
+}
+
+/// Encrypt the messages and descriptions stored in plaintext, dropping them from the
+/// full-text index
+fn encrypt_sessions(conn: &mut Connection, cipher: &SessionCipher) -> Result<()> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let encrypted = format!("{}%", PREFIX);
+    let rows: Vec<(String, i64, String)> = tx
+        .prepare("SELECT session_id, seq, message FROM messages WHERE message NOT LIKE ?1")?
+        .query_map([&encrypted], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, seq, message) in rows {
+        tx.execute(
+            "UPDATE messages SET message = ?3 WHERE session_id = ?1 AND seq = ?2",
+            params![id, seq, cipher.encrypt(message.as_bytes())?],
+        )?;
+    }
+    let rows: Vec<(String, String)> = tx
+        .prepare("SELECT id, description FROM sessions WHERE description NOT LIKE ?1")?
+        .query_map([&encrypted], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, description) in rows {
+        tx.execute(
+            "UPDATE sessions SET description = ?2 WHERE id = ?1",
+            params![id, cipher.encrypt(description.as_bytes())?],
+        )?;
+    }
+    tx.execute("DELETE FROM message_search", [])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Whether any message or description is encrypted, and so missing from the full-text index
+fn has_encrypted(conn: &Connection) -> Result<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM messages WHERE message LIKE ?1)
+             OR EXISTS (SELECT 1 FROM sessions WHERE description LIKE ?1)",
+        [format!("{}%", PREFIX)],
+        |row| row.get(0),
+    )?)
+}
+
+/// Bring a database written by an older version to the current schema
+///
+/// The `user_version` of the database is the session schema version of its messages,
+/// databases from before it was recorded also lack some columns. Encrypted messages stay
+/// encrypted.
+fn migrate(conn: &mut Connection, cipher: Option<&SessionCipher>) -> Result<()> {
+    let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let version: u32 = tx.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+    check_version(version)?;
+
+    if version == 0 {
+        add_missing_columns(&tx)?;
+    }
+    let rows: Vec<(String, i64, String)> = tx
+        .prepare("SELECT session_id, seq, message FROM messages")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<_>>()?;
+    for (id, seq, message) in rows {
+        let migrated = migrate_message(decode_line(&message, cipher)?, version)?;
+        let migrated = serde_json::to_string(&migrated)?;
+        let migrated = match cipher {
+            Some(cipher) if is_encrypted(&message) => cipher.encrypt(migrated.as_bytes())?,
+            _ => migrated,
+        };
+        tx.execute(
+            "UPDATE messages SET message = ?3 WHERE session_id = ?1 AND seq = ?2",
+            params![id, seq, migrated],
+        )?;
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Add the columns databases from before the schema version was recorded lack
+fn add_missing_columns(conn: &Connection) -> Result<()> {
+    for (name, definition) in ADDED_COLUMNS {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM pragma_table_info('sessions') WHERE name = ?1)",
+            [name],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            conn.execute_batch(&format!(
+                "ALTER TABLE sessions ADD COLUMN {} {}",
+                name, definition
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+fn timestamp(millis: Option<i64>) -> Option<DateTime<Utc>> {
+    millis.and_then(DateTime::from_timestamp_millis)
+}
+
+fn metadata_from_row(row: &rusqlite::Row) -> rusqlite::Result<SessionMetadata> {
+    Ok(SessionMetadata {
+        schema_version: SCHEMA_VERSION,
+        working_dir: PathBuf::from(row.get::<_, String>("working_dir")?),
+        description: row.get("description")?,
+        message_count: row.get::<_, i64>("message_count")? as usize,
+        total_tokens: row.get("total_tokens")?,
+        parent: match row.get::<_, Option<String>>("parent_id")? {
+            Some(session_id) => Some(SessionParent {
+                session_id,
+                message_index: row.get::<_, i64>("parent_message_index")? as usize,
+            }),
+            None => None,
+        },
+        created_at: timestamp(row.get("created_at")?),
+        updated_at: timestamp(row.get("updated_at")?),
+        tags: serde_json::from_str(&row.get::<_, String>("tags")?).unwrap_or_default(),
+        archived: row.get("archived")?,
+    })
+}
+
+impl SqliteStore {
+    /// Open the database at `path`, creating it if needed
+    pub fn open<P: Into<PathBuf>>(path: P) -> Result<Self> {
+        Self::open_with_cipher(path, None, false)
+    }
+
+    /// Open the database at `path`, decrypting encrypted sessions with `cipher`, and if
+    /// `encrypt` is set also encrypting everything written
+    ///
+    /// When encrypting, the sessions stored in plaintext are encrypted right away and their
+    /// text is dropped from the full-text index.
+    pub fn open_with_cipher<P: Into<PathBuf>>(
+        path: P,
+        cipher: Option<Arc<SessionCipher>>,
+        encrypt: bool,
+    ) -> Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut conn = Connection::open(&path)?;
+        // Survive crashes without corrupting the database, and wait for other processes
+        // using the same sessions instead of failing
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        let indexed: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE name = 'message_search')",
+            [],
+            |row| row.get(0),
+        )?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&mut conn, cipher.as_deref())?;
+        if !indexed {
+            index_sessions(&conn)?;
+        }
+        let encrypt = encrypt && cipher.is_some();
+        if let Some(cipher) = cipher.as_deref().filter(|_| encrypt) {
+            encrypt_sessions(&mut conn, cipher)?;
+        }
+        Ok(Self {
+            path,
+            conn: Mutex::new(conn),
+            codec: Codec { cipher, encrypt },
+        })
+    }
+
+    fn transaction<T>(&self, f: impl FnOnce(&Transaction) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().unwrap();
+        // Take the write lock up front, rather than failing to upgrade a read lock
+        let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let result = f(&tx)?;
+        tx.commit()?;
+        Ok(result)
+    }
+}
+
+impl SessionStore for SqliteStore {
+    /// The database, which keeps every session
+    fn path(&self, _id: &str) -> PathBuf {
+        self.path.clone()
+    }
+
+    fn exists(&self, id: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let found = conn
+            .query_row("SELECT 1 FROM sessions WHERE id = ?1", [id], |_| Ok(()))
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    fn create(&self, id: &str, metadata: &SessionMetadata) -> Result<()> {
+        self.transaction(|tx| {
+            tx.execute("DELETE FROM messages WHERE session_id = ?1", [id])?;
+            tx.execute("DELETE FROM sessions WHERE id = ?1", [id])?;
+            tx.execute("DELETE FROM message_search WHERE session_id = ?1", [id])?;
+            ensure_session(tx, id)?;
+            Ok(())
+        })?;
+        self.update_metadata(id, metadata)
+    }
+
+    fn append(&self, id: &str, messages: &[Message]) -> Result<()> {
+        self.transaction(|tx| append_messages(tx, &self.codec, id, messages))
+    }
+
+    fn replace_messages(&self, id: &str, messages: &[Message]) -> Result<()> {
+        self.transaction(|tx| replace_messages(tx, &self.codec, id, messages))
+    }
+
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>> {
+        let conn = self.conn.lock().unwrap();
+        let mut query =
+            conn.prepare("SELECT message FROM messages WHERE session_id = ?1 ORDER BY seq")?;
+        let rows = query.query_map([id], |row| row.get::<_, String>(0))?;
+
+        let mut messages = Vec::new();
+        for row in rows {
+            messages.push(self.codec.decode_message(&row?)?);
+        }
+        Ok(messages)
+    }
+
+    fn last_message(&self, id: &str) -> Result<Option<(usize, Message)>> {
+        last_message(&self.conn.lock().unwrap(), &self.codec, id)
+    }
+
+    fn save(
+        &self,
+        id: &str,
+        messages: &[Message],
+        redactor: Option<&Redactor>,
+    ) -> Result<Redactions> {
+        self.transaction(|tx| {
+            let last = last_message(tx, &self.codec, id)?;
+            let (unsaved, redactions) = unsaved(last, messages, redactor);
+            match unsaved {
+                Unsaved::Append(messages) => append_messages(tx, &self.codec, id, &messages)?,
+                Unsaved::Replace(messages) => replace_messages(tx, &self.codec, id, &messages)?,
+            }
+            Ok(redactions)
+        })
+    }
+
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata> {
+        let conn = self.conn.lock().unwrap();
+        let metadata = conn
+            .query_row(
+                &format!("SELECT {} FROM sessions WHERE id = ?1", METADATA_COLUMNS),
+                [id],
+                metadata_from_row,
+            )
+            .optional()?;
+        match metadata {
+            Some(metadata) => self.codec.decode_metadata(metadata),
+            None => Ok(SessionMetadata::default()),
+        }
+    }
+
+    fn update_metadata(&self, id: &str, metadata: &SessionMetadata) -> Result<()> {
+        let parent = metadata.parent.as_ref();
+        self.transaction(|tx| {
+            tx.execute(
+                "INSERT INTO sessions (id, working_dir, description, message_count, total_tokens,
+                                       updated_at, parent_id, parent_message_index, created_at,
+                                       tags, archived)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+                 ON CONFLICT (id) DO UPDATE SET
+                    working_dir = excluded.working_dir,
+                    description = excluded.description,
+                    message_count = excluded.message_count,
+                    total_tokens = excluded.total_tokens,
+                    updated_at = excluded.updated_at,
+                    parent_id = excluded.parent_id,
+                    parent_message_index = excluded.parent_message_index,
+                    created_at = excluded.created_at,
+                    tags = excluded.tags,
+                    archived = excluded.archived",
+                params![
+                    id,
+                    metadata.working_dir.to_string_lossy(),
+                    self.codec.encode(&metadata.description)?,
+                    metadata.message_count as i64,
+                    metadata.total_tokens,
+                    now(),
+                    parent.map(|parent| &parent.session_id),
+                    parent.map(|parent| parent.message_index as i64),
+                    metadata
+                        .created_at
+                        .map(|created| created.timestamp_millis()),
+                    serde_json::to_string(&metadata.tags)?,
+                    metadata.archived,
+                ],
+            )?;
+            tx.execute(
+                "DELETE FROM message_search WHERE session_id = ?1 AND seq IS NULL",
+                [id],
+            )?;
+            self.codec.index_text(tx, id, None, &metadata.description)
+        })
+    }
+
+    fn list(&self) -> Result<Vec<SessionInfo>> {
+        let conn = self.conn.lock().unwrap();
+        let mut query = conn.prepare(&format!(
+            "SELECT id, {} FROM sessions ORDER BY updated_at DESC",
+            METADATA_COLUMNS
+        ))?;
+        let rows = query.query_map([], |row| {
+            Ok(SessionInfo {
+                id: row.get("id")?,
+                path: self.path.clone(),
+                modified: DateTime::from_timestamp_millis(row.get("updated_at")?)
+                    .unwrap_or_default(),
+                metadata: metadata_from_row(row)?,
+            })
+        })?;
+        rows.map(|info| {
+            let info = info?;
+            Ok(SessionInfo {
+                metadata: self.codec.decode_metadata(info.metadata)?,
+                ..info
+            })
+        })
+        .collect()
+    }
+
+    fn rename(&self, id: &str, new_id: &str) -> Result<()> {
+        self.transaction(|tx| {
+            let exists = |id: &str| -> rusqlite::Result<bool> {
+                tx.query_row(
+                    "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = ?1)",
+                    [id],
+                    |row| row.get(0),
+                )
+            };
+            if !exists(id)? {
+                return Err(anyhow::anyhow!("No session named '{}'", id));
+            }
+            if exists(new_id)? {
+                return Err(anyhow::anyhow!(
+                    "A session named '{}' already exists",
+                    new_id
+                ));
+            }
+            for statement in [
+                "UPDATE sessions SET id = ?2 WHERE id = ?1",
+                "UPDATE messages SET session_id = ?2 WHERE session_id = ?1",
+                "UPDATE message_search SET session_id = ?2 WHERE session_id = ?1",
+            ] {
+                tx.execute(statement, params![id, new_id])?;
+            }
+            Ok(())
+        })
+    }
+
+    fn delete(&self, id: &str) -> Result<()> {
+        self.transaction(|tx| {
+            tx.execute("DELETE FROM messages WHERE session_id = ?1", [id])?;
+            tx.execute("DELETE FROM sessions WHERE id = ?1", [id])?;
+            tx.execute("DELETE FROM message_search WHERE session_id = ?1", [id])?;
+            Ok(())
+        })
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        // Encrypted text is not in the full-text index
+        if has_encrypted(&self.conn.lock().unwrap())? {
+            return search_sessions(self, query, limit);
+        }
+        let terms: Vec<String> = tokenize(query).into_iter().map(|(_, t)| t).collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Quote every word so none of them is read as query syntax
+        let query = terms
+            .iter()
+            .map(|term| format!("\"{}\"", term))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let conn = self.conn.lock().unwrap();
+        let mut search = conn.prepare(
+            "SELECT message_search.session_id, message_search.seq, sessions.description,
+                    bm25(message_search), message_search.text
+             FROM message_search JOIN sessions ON sessions.id = message_search.session_id
+             WHERE message_search MATCH ?1
+             ORDER BY bm25(message_search)
+             LIMIT ?2",
+        )?;
+        let rows = search.query_map(params![query, limit as i64], |row| {
+            Ok(SearchHit {
+                session_id: row.get(0)?,
+                message_index: row.get::<_, Option<i64>>(1)?.map(|seq| seq as usize),
+                description: row.get(2)?,
+                // bm25 is lower for better matches
+                score: -row.get::<_, f64>(3)?,
+                snippet: snippet(&row.get::<_, String>(4)?, &terms),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
@@ -1,24 +1,28 @@
-use crate::message::Message;
-use crate::providers::base::Provider;
 use anyhow::Result;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use etcetera::{choose_app_strategy, AppStrategy, AppStrategyArgs};
 use serde::{Deserialize, Serialize};
-use std::fs::{self, File};
-use std::io::{self, BufRead, Write};
-use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::fs;
+use std::path::PathBuf;
 
-fn get_home_dir() -> PathBuf {
+use super::migration::SCHEMA_VERSION;
+
+pub(crate) fn get_home_dir() -> PathBuf {
     choose_app_strategy(crate::config::APP_STRATEGY.clone())
         .expect("goose requires a home dir")
         .home_dir()
         .to_path_buf()
 }
 
-/// Metadata for a session, stored as the first line in the session file
-#[derive(Debug, Clone, Serialize)]
+/// Metadata for a session, kept alongside its messages
+///
+/// Metadata written by older versions is read through
+/// [`migrate_metadata`](super::migration::migrate_metadata), which fills in the fields
+/// they lack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionMetadata {
+    /// The version of the schema the session was written with, see [`SCHEMA_VERSION`]
+    pub schema_version: u32,
     /// Working directory for the session
     pub working_dir: PathBuf,
     /// A short description of the session, typically 3 words or less
@@ -27,40 +31,42 @@ pub struct SessionMetadata {
     pub message_count: usize,
     /// The total number of tokens used in the session. Retrieved from the provider's last usage.
     pub total_tokens: Option<i32>,
-}
-
-// Custom deserializer to handle old sessions without working_dir
-impl<'de> Deserialize<'de> for SessionMetadata {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: serde::Deserializer<'de>,
-    {
-        #[derive(Deserialize)]
-        struct Helper {
-            description: String,
-            message_count: usize,
-            total_tokens: Option<i32>,
-            working_dir: Option<PathBuf>,
-        }
-
-        let helper = Helper::deserialize(deserializer)?;
-
-        Ok(SessionMetadata {
-            description: helper.description,
-            message_count: helper.message_count,
-            total_tokens: helper.total_tokens,
-            working_dir: helper.working_dir.unwrap_or_else(get_home_dir),
-        })
-    }
+    /// The session this one was forked from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent: Option<SessionParent>,
+    /// When the session was started, unknown for sessions from before it was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the session last changed, filled in by the store it is read from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Tags given by the user to find the session later
+    pub tags: Vec<String>,
+    /// Archived sessions are kept but not listed with the others
+    pub archived: bool,
+}
+
+/// Where a forked session branched off
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionParent {
+    pub session_id: String,
+    /// The number of messages taken from the parent session
+    pub message_index: usize,
 }
 
 impl SessionMetadata {
     pub fn new(working_dir: PathBuf) -> Self {
         Self {
+            schema_version: SCHEMA_VERSION,
             working_dir,
             description: String::new(),
             message_count: 0,
             total_tokens: None,
+            parent: None,
+            created_at: Some(Utc::now()),
+            updated_at: None,
+            tags: Vec::new(),
+            archived: false,
         }
     }
 }
@@ -80,14 +86,19 @@ pub enum Identifier {
     Path(PathBuf),
 }
 
-pub fn get_path(id: Identifier) -> PathBuf {
-    match id {
-        Identifier::Name(name) => {
-            let session_dir = ensure_session_dir().expect("Failed to create session directory");
-            session_dir.join(format!("{}.jsonl", name))
-        }
-        Identifier::Path(path) => path,
+/// Check that `id` can name a session, it is used in file names so it must not reach
+/// outside the session directory
+pub fn validate_session_id(id: &str) -> Result<()> {
+    if id.trim().is_empty() {
+        return Err(anyhow::anyhow!("A session name must not be empty"));
+    }
+    if id.contains(['/', '\\', '\0']) || id.contains("..") {
+        return Err(anyhow::anyhow!(
+            "Invalid session name '{}', it must not contain path separators or '..'",
+            id
+        ));
     }
+    Ok(())
 }
 
 /// Ensure the session directory exists and return its path
@@ -110,321 +121,14 @@ pub fn ensure_session_dir() -> Result<PathBuf> {
     Ok(data_dir)
 }
 
-/// Get the path to the most recently modified session file
-pub fn get_most_recent_session() -> Result<PathBuf> {
-    let session_dir = ensure_session_dir()?;
-    let mut entries = fs::read_dir(&session_dir)?
-        .filter_map(|entry| entry.ok())
-        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
-        .collect::<Vec<_>>();
-
-    if entries.is_empty() {
-        return Err(anyhow::anyhow!("No session files found"));
-    }
-
-    // Sort by modification time, most recent first
-    entries.sort_by(|a, b| {
-        b.metadata()
-            .and_then(|m| m.modified())
-            .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-            .cmp(
-                &a.metadata()
-                    .and_then(|m| m.modified())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
-            )
-    });
-
-    Ok(entries[0].path())
-}
-
-/// List all available session files
-pub fn list_sessions() -> Result<Vec<(String, PathBuf)>> {
-    let session_dir = ensure_session_dir()?;
-    let entries = fs::read_dir(&session_dir)?
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            let path = entry.path();
-
-            if path.extension().is_some_and(|ext| ext == "jsonl") {
-                let name = path.file_stem()?.to_string_lossy().to_string();
-                Some((name, path))
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<_>>();
-
-    Ok(entries)
-}
-
 /// Generate a session ID using timestamp format (yyyymmdd_hhmmss)
 pub fn generate_session_id() -> String {
     Local::now().format("%Y%m%d_%H%M%S").to_string()
 }
 
-/// Read messages from a session file
-///
-/// Creates the file if it doesn't exist, reads and deserializes all messages if it does.
-/// The first line of the file is expected to be metadata, and the rest are messages.
-pub fn read_messages(session_file: &Path) -> Result<Vec<Message>> {
-    let file = fs::OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .open(session_file)?;
-
-    let reader = io::BufReader::new(file);
-    let mut lines = reader.lines();
-    let mut messages = Vec::new();
-
-    // Read the first line as metadata or create default if empty/missing
-    if let Some(line) = lines.next() {
-        let line = line?;
-        // Try to parse as metadata, but if it fails, treat it as a message
-        if let Ok(_metadata) = serde_json::from_str::<SessionMetadata>(&line) {
-            // Metadata successfully parsed, continue with the rest of the lines as messages
-        } else {
-            // This is not metadata, it's a message
-            messages.push(serde_json::from_str::<Message>(&line)?);
-        }
-    }
-
-    // Read the rest of the lines as messages
-    for line in lines {
-        messages.push(serde_json::from_str::<Message>(&line?)?);
-    }
-
-    Ok(messages)
-}
-
-/// Read session metadata from a session file
-///
-/// Returns default empty metadata if the file doesn't exist or has no metadata.
-pub fn read_metadata(session_file: &Path) -> Result<SessionMetadata> {
-    if !session_file.exists() {
-        return Ok(SessionMetadata::default());
-    }
-
-    let file = fs::File::open(session_file)?;
-    let mut reader = io::BufReader::new(file);
-    let mut first_line = String::new();
-
-    // Read just the first line
-    if reader.read_line(&mut first_line)? > 0 {
-        // Try to parse as metadata
-        match serde_json::from_str::<SessionMetadata>(&first_line) {
-            Ok(metadata) => Ok(metadata),
-            Err(_) => {
-                // If the first line isn't metadata, return default
-                Ok(SessionMetadata::default())
-            }
-        }
-    } else {
-        // Empty file, return default
-        Ok(SessionMetadata::default())
-    }
-}
-
-/// Write messages to a session file with metadata
-///
-/// Overwrites the file with metadata as the first line, followed by all messages in JSONL format.
-/// If a provider is supplied, it will automatically generate a description when appropriate.
-pub async fn persist_messages(
-    session_file: &Path,
-    messages: &[Message],
-    provider: Option<Arc<Box<dyn Provider>>>,
-) -> Result<()> {
-    // Read existing metadata
-    let mut metadata = read_metadata(session_file)?;
-
-    // Count user messages
-    let user_message_count = messages
-        .iter()
-        .filter(|m| m.role == mcp_core::role::Role::User)
-        .filter(|m| !m.as_concat_text().trim().is_empty())
-        .count();
-
-    // Check if we need to update the description (after 1st or 3rd user message)
-    if let Some(provider) = provider {
-        if user_message_count < 4 {
-            // Generate description
-            let mut description_prompt = "Based on the conversation so far, provide a concise header for this session in 4 words or less. This will be used for finding the session later in a UI with limited space - reply *ONLY* with the header. Avoid filler words such as help, summary, exchange, request etc that do not help distinguish different conversations.".to_string();
-
-            // get context from messages so far
-            let context: Vec<String> = messages.iter().map(|m| m.as_concat_text()).collect();
-
-            if !context.is_empty() {
-                description_prompt = format!(
-                    "Here are the first few user messages:\n{}\n\n{}",
-                    context.join("\n"),
-                    description_prompt
-                );
-            }
-
-            // Generate the description
-            let message = Message::user().with_text(&description_prompt);
-            match provider
-                .complete(
-                    "Reply with only a description in four words or less.",
-                    &[message],
-                    &[],
-                )
-                .await
-            {
-                Ok((response, _)) => {
-                    metadata.description = response.as_concat_text();
-                }
-                Err(e) => {
-                    tracing::error!("Failed to generate session description: {:?}", e);
-                }
-            }
-        }
-    }
-
-    // Write the file with metadata and messages
-    save_messages_with_metadata(session_file, &metadata, messages)
-}
-
-/// Write messages to a session file with the provided metadata
-///
-/// Overwrites the file with metadata as the first line, followed by all messages in JSONL format.
-pub fn save_messages_with_metadata(
-    session_file: &Path,
-    metadata: &SessionMetadata,
-    messages: &[Message],
-) -> Result<()> {
-    let file = File::create(session_file).expect("The path specified does not exist");
-    let mut writer = io::BufWriter::new(file);
-
-    // Write metadata as the first line
-    serde_json::to_writer(&mut writer, &metadata)?;
-    writeln!(writer)?;
-
-    // Write all messages
-    for message in messages {
-        serde_json::to_writer(&mut writer, &message)?;
-        writeln!(writer)?;
-    }
-
-    writer.flush()?;
-    Ok(())
-}
-
-/// Generate a description for the session using the provider
-///
-/// This function is called when appropriate to generate a short description
-/// of the session based on the conversation history.
-pub async fn generate_description(
-    session_file: &Path,
-    messages: &[Message],
-    provider: &dyn Provider,
-) -> Result<()> {
-    // Create a special message asking for a 3-word description
-    let mut description_prompt = "Based on the conversation so far, provide a concise description of this session in 4 words or less. This will be used for finding the session later in a UI with limited space - reply *ONLY* with the description".to_string();
-
-    // get context from messages so far
-    let context: Vec<String> = messages
-        .iter()
-        .filter(|m| m.role == mcp_core::role::Role::User)
-        .take(3) // Use up to first 3 user messages for context
-        .map(|m| m.as_concat_text())
-        .collect();
-
-    if !context.is_empty() {
-        description_prompt = format!(
-            "Here are the first few user messages:\n{}\n\n{}",
-            context.join("\n"),
-            description_prompt
-        );
-    }
-
-    // Generate the description
-    let message = Message::user().with_text(&description_prompt);
-    let result = provider
-        .complete(
-            "Reply with only a description in four words or less",
-            &[message],
-            &[],
-        )
-        .await?;
-
-    let description = result.0.as_concat_text();
-
-    // Read current metadata
-    let mut metadata = read_metadata(session_file)?;
-
-    // Update description
-    metadata.description = description;
-
-    // Update the file with the new metadata and existing messages
-    update_metadata(session_file, &metadata).await?;
-
-    Ok(())
-}
-
-/// Update only the metadata in a session file, preserving all messages
-pub async fn update_metadata(session_file: &Path, metadata: &SessionMetadata) -> Result<()> {
-    // Read all messages from the file
-    let messages = read_messages(session_file)?;
-
-    // Rewrite the file with the new metadata and existing messages
-    save_messages_with_metadata(session_file, metadata, &messages)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::message::MessageContent;
-    use tempfile::tempdir;
-
-    #[tokio::test]
-    async fn test_read_write_messages() -> Result<()> {
-        let dir = tempdir()?;
-        let file_path = dir.path().join("test.jsonl");
-
-        // Create some test messages
-        let messages = vec![
-            Message::user().with_text("Hello"),
-            Message::assistant().with_text("Hi there"),
-        ];
-
-        // Write messages
-        persist_messages(&file_path, &messages, None).await?;
-
-        // Read them back
-        let read_messages = read_messages(&file_path)?;
-
-        // Compare
-        assert_eq!(messages.len(), read_messages.len());
-        for (orig, read) in messages.iter().zip(read_messages.iter()) {
-            assert_eq!(orig.role, read.role);
-            assert_eq!(orig.content.len(), read.content.len());
-
-            // Compare first text content
-            if let (Some(MessageContent::Text(orig_text)), Some(MessageContent::Text(read_text))) =
-                (orig.content.first(), read.content.first())
-            {
-                assert_eq!(orig_text.text, read_text.text);
-            } else {
-                panic!("Messages don't match expected structure");
-            }
-        }
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_empty_file() -> Result<()> {
-        let dir = tempdir()?;
-        let file_path = dir.path().join("empty.jsonl");
-
-        // Reading an empty file should return empty vec
-        let messages = read_messages(&file_path)?;
-        assert!(messages.is_empty());
-
-        Ok(())
-    }
 
     #[test]
     fn test_generate_session_id() {
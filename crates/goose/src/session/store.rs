@@ -0,0 +1,629 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use super::encryption::{keyring_cipher, SessionCipher};
+use super::jsonl::JsonlStore;
+use super::search::{SearchHit, SearchIndex};
+use super::sqlite::SqliteStore;
+use super::storage::{ensure_session_dir, validate_session_id, Identifier, SessionMetadata};
+use crate::config::Config;
+use crate::message::Message;
+use crate::providers::base::Provider;
+use crate::redaction::{Redactions, Redactor};
+
+/// A session as listed by a [`SessionStore`]
+#[derive(Debug, Clone)]
+pub struct SessionInfo {
+    pub id: String,
+    /// Where the session is kept, see [`SessionStore::path`]
+    pub path: PathBuf,
+    pub modified: DateTime<Utc>,
+    pub metadata: SessionMetadata,
+}
+
+/// Keeps sessions: their metadata and the messages of their conversation
+pub trait SessionStore: Send + Sync {
+    /// Where the session is kept, e.g. its file. Stores keeping all sessions in one file,
+    /// like [`SqliteStore`], give that file for every id, so it is no path to remove or
+    /// copy a single session by.
+    fn path(&self, id: &str) -> PathBuf;
+
+    fn exists(&self, id: &str) -> Result<bool>;
+
+    /// Start a session without messages, replacing any session with the same id
+    fn create(&self, id: &str, metadata: &SessionMetadata) -> Result<()>;
+
+    /// Add messages to the end of the session, creating it with default metadata if needed
+    fn append(&self, id: &str, messages: &[Message]) -> Result<()>;
+
+    /// Replace all messages of the session, e.g. once messages were removed
+    fn replace_messages(&self, id: &str, messages: &[Message]) -> Result<()>;
+
+    /// The messages of the session, none if it does not exist
+    fn read_messages(&self, id: &str) -> Result<Vec<Message>>;
+
+    /// The number of messages in the session and the last of them, to tell which
+    /// messages are not saved yet
+    fn last_message(&self, id: &str) -> Result<Option<(usize, Message)>> {
+        let mut messages = self.read_messages(id)?;
+        let count = messages.len();
+        Ok(messages.pop().map(|message| (count, message)))
+    }
+
+    /// Save the conversation `messages`, adding those not saved yet to the end of the
+    /// session, or replacing all of its messages if some were removed or replaced since
+    ///
+    /// Messages are saved redacted by `redactor`, and only those that might need saving
+    /// are redacted. Telling which messages to save and saving them is a single step, so
+    /// two writers saving the same session do not save messages twice.
+    fn save(
+        &self,
+        id: &str,
+        messages: &[Message],
+        redactor: Option<&Redactor>,
+    ) -> Result<Redactions>;
+
+    /// The metadata of the session, the default metadata if it does not exist
+    fn read_metadata(&self, id: &str) -> Result<SessionMetadata>;
+
+    /// Replace the metadata of the session, creating it if needed
+    fn update_metadata(&self, id: &str, metadata: &SessionMetadata) -> Result<()>;
+
+    /// All sessions, the most recently modified first
+    fn list(&self) -> Result<Vec<SessionInfo>>;
+
+    /// Give the session another id, failing if a session already has it
+    fn rename(&self, id: &str, new_id: &str) -> Result<()>;
+
+    fn delete(&self, id: &str) -> Result<()>;
+
+    /// The best `limit` matches for `query` in the messages and descriptions of all
+    /// sessions, ranked as by [`SearchIndex`]
+    fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        search_sessions(self, query, limit)
+    }
+}
+
+/// Search all sessions of `store` by reading them into a [`SearchIndex`]
+pub(super) fn search_sessions<S: SessionStore + ?Sized>(
+    store: &S,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<SearchHit>> {
+    let mut index = SearchIndex::new();
+    for session in store.list()? {
+        let messages = store.read_messages(&session.id)?;
+        index.add_session(&session.id, &session.metadata, &messages);
+    }
+    Ok(index.search(query, limit))
+}
+
+/// What [`SessionStore::save`] writes
+pub(super) enum Unsaved {
+    /// Messages to add to the end of the session
+    Append(Vec<Message>),
+    /// All messages of the session, as some of the saved ones were removed or replaced
+    Replace(Vec<Message>),
+}
+
+/// Tell which of `messages` are not saved yet from `last`, the number of saved messages
+/// and the last of them, redacting them with `redactor`
+pub(super) fn unsaved(
+    last: Option<(usize, Message)>,
+    messages: &[Message],
+    redactor: Option<&Redactor>,
+) -> (Unsaved, Redactions) {
+    let redact = |messages: &[Message]| match redactor {
+        Some(redactor) => redactor.redact_messages(messages),
+        None => (messages.to_vec(), Redactions::default()),
+    };
+
+    match last {
+        None => {
+            let (new, redactions) = redact(messages);
+            (Unsaved::Append(new), redactions)
+        }
+        Some((count, last))
+            if count <= messages.len() && redact(&messages[count - 1..count]).0[0] == last =>
+        {
+            let (new, redactions) = redact(&messages[count..]);
+            (Unsaved::Append(new), redactions)
+        }
+        // Only the messages that were not saved before are reported as redacted
+        Some((count, _)) => {
+            let (old, _) = redact(&messages[..count.min(messages.len())]);
+            let (new, redactions) = redact(&messages[old.len()..]);
+            (Unsaved::Replace([old, new].concat()), redactions)
+        }
+    }
+}
+
+// The sqlite store shares its connection between everyone using it
+static SQLITE_STORE: Mutex<Option<Arc<SqliteStore>>> = Mutex::new(None);
+
+/// The cipher for the sessions, and whether to encrypt them as `GOOSE_SESSION_ENCRYPTION`
+/// asks
+///
+/// The key is kept in the keyring, and generated the first time it is needed. Encrypted
+/// sessions can be read as long as the key is there, even with encryption turned off.
+fn session_cipher() -> Result<(Option<Arc<SessionCipher>>, bool)> {
+    let encrypt = Config::global()
+        .get::<bool>("GOOSE_SESSION_ENCRYPTION")
+        .unwrap_or(false);
+    match keyring_cipher(encrypt) {
+        Ok(cipher) => Ok((cipher, encrypt)),
+        // Without encryption, sessions that are not encrypted are readable without a keyring
+        Err(e) if !encrypt => {
+            tracing::warn!("Failed to read the session key from the keyring: {}", e);
+            Ok((None, false))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// A store for the session files in `dir`, encrypted if `GOOSE_SESSION_ENCRYPTION` is set
+fn jsonl_store(dir: PathBuf) -> Result<JsonlStore> {
+    let store = JsonlStore::new(dir);
+    Ok(match session_cipher()? {
+        (Some(cipher), encrypt) => store.with_cipher(cipher, encrypt),
+        (None, _) => store,
+    })
+}
+
+/// The store configured through `GOOSE_SESSION_STORE`, `jsonl` (the default) for a file
+/// per session or `sqlite` for a single database, in the session directory. Either is
+/// encrypted if `GOOSE_SESSION_ENCRYPTION` is set.
+pub fn default_store() -> Result<Arc<dyn SessionStore>> {
+    let config = Config::global();
+    let backend = config
+        .get::<String>("GOOSE_SESSION_STORE")
+        .unwrap_or_else(|_| "jsonl".to_string());
+    let session_dir = ensure_session_dir()?;
+
+    match backend.as_str() {
+        "jsonl" => Ok(Arc::new(jsonl_store(session_dir)?)),
+        "sqlite" => {
+            let mut store = SQLITE_STORE.lock().unwrap();
+            if let Some(store) = store.as_ref() {
+                return Ok(Arc::clone(store) as Arc<dyn SessionStore>);
+            }
+            let (cipher, encrypt) = session_cipher()?;
+            let opened = Arc::new(SqliteStore::open_with_cipher(
+                session_dir.join("sessions.db"),
+                cipher,
+                encrypt,
+            )?);
+            *store = Some(Arc::clone(&opened));
+            Ok(opened)
+        }
+        other => Err(anyhow::anyhow!(
+            "Unknown session store '{}', expected 'jsonl' or 'sqlite'",
+            other
+        )),
+    }
+}
+
+/// A session in the store keeping it
+#[derive(Clone)]
+pub struct StoredSession {
+    store: Arc<dyn SessionStore>,
+    id: String,
+}
+
+impl StoredSession {
+    pub fn new<S: Into<String>>(store: Arc<dyn SessionStore>, id: S) -> Self {
+        Self {
+            store,
+            id: id.into(),
+        }
+    }
+
+    /// Sessions identified by name are kept in the [`default_store`], those identified by
+    /// path are the JSONL file `{id}.jsonl` in that directory, encrypted like in the
+    /// default store. Names that could reach outside the store are rejected.
+    pub fn open(identifier: Identifier) -> Result<Self> {
+        match identifier {
+            Identifier::Name(name) => {
+                validate_session_id(&name)?;
+                Ok(Self::new(default_store()?, name))
+            }
+            Identifier::Path(path) => {
+                let id = path
+                    .file_stem()
+                    .ok_or_else(|| anyhow::anyhow!("No session file in {}", path.display()))?
+                    .to_string_lossy()
+                    .to_string();
+                let dir = path
+                    .parent()
+                    .map(|dir| dir.to_path_buf())
+                    .unwrap_or_default();
+                Ok(Self::new(Arc::new(jsonl_store(dir)?), id))
+            }
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn store(&self) -> &Arc<dyn SessionStore> {
+        &self.store
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.store.path(&self.id)
+    }
+
+    pub fn exists(&self) -> Result<bool> {
+        self.store.exists(&self.id)
+    }
+
+    pub fn read_messages(&self) -> Result<Vec<Message>> {
+        self.store.read_messages(&self.id)
+    }
+
+    pub fn read_metadata(&self) -> Result<SessionMetadata> {
+        self.store.read_metadata(&self.id)
+    }
+
+    pub fn update_metadata(&self, metadata: &SessionMetadata) -> Result<()> {
+        self.store.update_metadata(&self.id, metadata)
+    }
+
+    pub fn replace_messages(&self, messages: &[Message]) -> Result<()> {
+        self.store.replace_messages(&self.id, messages)
+    }
+
+    pub fn rename(&mut self, new_id: &str) -> Result<()> {
+        validate_session_id(new_id)?;
+        self.store.rename(&self.id, new_id)?;
+        self.id = new_id.to_string();
+        Ok(())
+    }
+
+    pub fn delete(&self) -> Result<()> {
+        self.store.delete(&self.id)
+    }
+
+    /// Save `messages` as the conversation of the session
+    ///
+    /// Only the messages the store does not have yet are appended, unless the conversation
+    /// changed before them. Secrets are redacted from the saved messages, see
+    /// [`Redactor::global`], what was redacted from the new messages is returned. If a
+    /// provider is supplied, it will automatically generate a description when appropriate.
+    pub async fn persist(
+        &self,
+        messages: &[Message],
+        provider: Option<Arc<Box<dyn Provider>>>,
+    ) -> Result<Redactions> {
+        // Count user messages
+        let user_message_count = messages
+            .iter()
+            .filter(|m| m.role == mcp_core::role::Role::User)
+            .filter(|m| !m.as_concat_text().trim().is_empty())
+            .count();
+
+        // Check if we need to update the description (after 1st or 3rd user message)
+        if let Some(provider) = provider {
+            if user_message_count < 4 {
+                // Generate description
+                let mut description_prompt = "Based on the conversation so far, provide a concise header for this session in 4 words or less. This will be used for finding the session later in a UI with limited space - reply *ONLY* with the header. Avoid filler words such as help, summary, exchange, request etc that do not help distinguish different conversations.".to_string();
+
+                // get context from messages so far
+                let context: Vec<String> = messages.iter().map(|m| m.as_concat_text()).collect();
+
+                if !context.is_empty() {
+                    description_prompt = format!(
+                        "Here are the first few user messages:\n{}\n\n{}",
+                        context.join("\n"),
+                        description_prompt
+                    );
+                }
+
+                // Generate the description
+                let message = Message::user().with_text(&description_prompt);
+                match provider
+                    .complete(
+                        "Reply with only a description in four words or less.",
+                        &[message],
+                        &[],
+                    )
+                    .await
+                {
+                    Ok((response, _)) => {
+                        let mut metadata = self.read_metadata()?;
+                        metadata.description = response.as_concat_text();
+                        self.update_metadata(&metadata)?;
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to generate session description: {:?}", e);
+                    }
+                }
+            }
+        }
+
+        self.save_messages(messages)
+    }
+
+    fn save_messages(&self, messages: &[Message]) -> Result<Redactions> {
+        self.store.save(&self.id, messages, Redactor::global())
+    }
+
+    /// Generate a description for the session using the provider
+    ///
+    /// This function is called when appropriate to generate a short description
+    /// of the session based on the conversation history.
+    pub async fn generate_description(
+        &self,
+        messages: &[Message],
+        provider: &dyn Provider,
+    ) -> Result<()> {
+        // Create a special message asking for a 3-word description
+        let mut description_prompt = "Based on the conversation so far, provide a concise description of this session in 4 words or less. This will be used for finding the session later in a UI with limited space - reply *ONLY* with the description".to_string();
+
+        // get context from messages so far
+        let context: Vec<String> = messages
+            .iter()
+            .filter(|m| m.role == mcp_core::role::Role::User)
+            .take(3) // Use up to first 3 user messages for context
+            .map(|m| m.as_concat_text())
+            .collect();
+
+        if !context.is_empty() {
+            description_prompt = format!(
+                "Here are the first few user messages:\n{}\n\n{}",
+                context.join("\n"),
+                description_prompt
+            );
+        }
+
+        // Generate the description
+        let message = Message::user().with_text(&description_prompt);
+        let result = provider
+            .complete(
+                "Reply with only a description in four words or less",
+                &[message],
+                &[],
+            )
+            .await?;
+
+        let mut metadata = self.read_metadata()?;
+        metadata.description = result.0.as_concat_text();
+        self.update_metadata(&metadata)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::SessionParent;
+    use tempfile::tempdir;
+
+    fn cipher() -> Arc<SessionCipher> {
+        Arc::new(SessionCipher::new(&SessionCipher::generate_key().unwrap()).unwrap())
+    }
+
+    fn stores(dir: &std::path::Path) -> Vec<Arc<dyn SessionStore>> {
+        vec![
+            Arc::new(JsonlStore::new(dir.join("jsonl"))),
+            Arc::new(SqliteStore::open(dir.join("sessions.db")).unwrap()),
+            Arc::new(JsonlStore::new(dir.join("encrypted")).with_cipher(cipher(), true)),
+            Arc::new(
+                SqliteStore::open_with_cipher(dir.join("encrypted.db"), Some(cipher()), true)
+                    .unwrap(),
+            ),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_persist_appends_only_new_messages() -> Result<()> {
+        let dir = tempdir()?;
+        for store in stores(dir.path()) {
+            let session = StoredSession::new(store, "test");
+            let mut messages = vec![Message::user().with_text("Hello")];
+            session.persist(&messages, None).await?;
+
+            messages.push(Message::assistant().with_text("Hi there"));
+            session.persist(&messages, None).await?;
+            assert_eq!(session.read_messages()?, messages);
+
+            // A conversation that changed is saved as a whole
+            messages.pop();
+            messages.pop();
+            messages.push(Message::user().with_text("Hello again"));
+            session.persist(&messages, None).await?;
+            assert_eq!(session.read_messages()?, messages);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_persist_redacts_secrets() -> Result<()> {
+        let dir = tempdir()?;
+        let session = StoredSession::new(Arc::new(JsonlStore::new(dir.path())), "test");
+        let key = "sk-ant-REDACTED";
+        let mut messages = vec![Message::user().with_text(format!("My key is {}", key))];
+        let redactions = session.persist(&messages, None).await?;
+        assert_eq!(redactions.total(), 1);
+
+        // Saved messages stay redacted, and are not reported again
+        messages.push(Message::assistant().with_text("Thanks"));
+        assert!(session.persist(&messages, None).await?.is_empty());
+        let saved = session.read_messages()?;
+        assert_eq!(saved.len(), 2);
+        assert_eq!(
+            saved[0].as_concat_text(),
+            "My key is [REDACTED:anthropic_key]"
+        );
+        assert_eq!(saved[1], messages[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_saves_do_not_save_messages_twice() -> Result<()> {
+        let dir = tempdir()?;
+        for store in stores(dir.path()) {
+            let messages = vec![
+                Message::user().with_text("Hello"),
+                Message::assistant().with_text("Hi there"),
+            ];
+            store.save("test", &messages[..1], None)?;
+            std::thread::scope(|scope| {
+                for _ in 0..4 {
+                    scope.spawn(|| store.save("test", &messages, None).unwrap());
+                }
+            });
+            assert_eq!(store.read_messages("test")?, messages);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_open_rejects_names_outside_the_store() {
+        for name in ["../../something", "..", "a/b", ""] {
+            assert!(StoredSession::open(Identifier::Name(name.to_string())).is_err());
+        }
+    }
+
+    #[test]
+    fn test_stores_keep_metadata_and_list_sessions() -> Result<()> {
+        let dir = tempdir()?;
+        for store in stores(dir.path()) {
+            let mut metadata = SessionMetadata::new(dir.path().to_path_buf());
+            metadata.description = "First session".to_string();
+            store.create("first", &metadata)?;
+            store.append("second", &[Message::user().with_text("Hello")])?;
+            metadata.total_tokens = Some(42);
+            metadata.parent = Some(SessionParent {
+                session_id: "second".to_string(),
+                message_index: 1,
+            });
+            store.update_metadata("first", &metadata)?;
+
+            let read = store.read_metadata("first")?;
+            assert_eq!(read.description, "First session");
+            assert_eq!(read.total_tokens, Some(42));
+            assert_eq!(read.parent, metadata.parent);
+            assert!(store.read_messages("first")?.is_empty());
+            assert!(!store.exists("third")?);
+
+            let mut ids: Vec<String> = store.list()?.into_iter().map(|s| s.id).collect();
+            ids.sort();
+            assert_eq!(ids, vec!["first", "second"]);
+
+            store.delete("first")?;
+            assert!(!store.exists("first")?);
+            assert_eq!(store.list()?.len(), 1);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_rename_and_session_details() -> Result<()> {
+        let dir = tempdir()?;
+        for store in stores(dir.path()) {
+            store.append("old", &[Message::user().with_text("Hello")])?;
+            let mut metadata = store.read_metadata("old")?;
+            assert!(metadata.created_at.is_some());
+            assert!(metadata.updated_at.is_some());
+
+            metadata.tags = vec!["work".to_string()];
+            metadata.archived = true;
+            store.update_metadata("old", &metadata)?;
+
+            let mut session = StoredSession::new(store.clone(), "old");
+            session.rename("new")?;
+            assert_eq!(session.id(), "new");
+            assert!(!store.exists("old")?);
+            assert_eq!(session.read_messages()?.len(), 1);
+
+            let read = session.read_metadata()?;
+            assert_eq!(read.tags, vec!["work"]);
+            assert!(read.archived);
+            assert_eq!(read.created_at, metadata.created_at);
+
+            store.append("other", &[])?;
+            assert!(session.rename("other").is_err());
+            assert!(session.rename("../outside").is_err());
+            assert!(store.rename("missing", "found").is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_sessions() -> Result<()> {
+        let dir = tempdir()?;
+        for store in stores(dir.path()) {
+            let mut metadata = SessionMetadata::new(dir.path().to_path_buf());
+            metadata.description = "Kafka consumer fix".to_string();
+            store.create("kafka", &metadata)?;
+            store.append(
+                "kafka",
+                &[
+                    Message::user().with_text("Why does the consumer lag?"),
+                    Message::assistant().with_text("The Kafka consumer commits too often."),
+                ],
+            )?;
+            store.append("other", &[Message::user().with_text("Plan the week")])?;
+
+            let hits = store.search("kafka consumer", 10)?;
+            assert_eq!(hits.len(), 2);
+            assert!(hits.iter().all(|hit| hit.session_id == "kafka"));
+            assert!(hits.iter().any(|hit| hit.message_index.is_none()));
+            assert!(hits.iter().any(|hit| hit.message_index == Some(1)));
+            assert_eq!(hits[0].description, "Kafka consumer fix");
+
+            // Replaced messages are no longer found
+            store.replace_messages("kafka", &[Message::user().with_text("Nothing")])?;
+            assert_eq!(store.search("commits", 10)?.len(), 0);
+            assert_eq!(store.search("week", 10)?[0].session_id, "other");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_store_encrypts_sessions() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("sessions.db");
+        let mut metadata = SessionMetadata::new(dir.path().to_path_buf());
+        metadata.description = "secret description".to_string();
+        let plain = SqliteStore::open(&path)?;
+        plain.create("test", &metadata)?;
+        plain.append("test", &[Message::user().with_text("secret plans")])?;
+        drop(plain);
+
+        // Opening with encryption encrypts what was stored before, and drops it from the
+        // full-text index
+        let cipher = cipher();
+        let store = SqliteStore::open_with_cipher(&path, Some(Arc::clone(&cipher)), true)?;
+        store.append("test", &[Message::assistant().with_text("more secrets")])?;
+        let conn = rusqlite::Connection::open(&path)?;
+        let stored: Vec<String> = conn
+            .prepare("SELECT message FROM messages UNION ALL SELECT description FROM sessions")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        assert_eq!(stored.len(), 3);
+        assert!(stored.iter().all(|text| !text.contains("secret")));
+        let indexed: i64 =
+            conn.query_row("SELECT COUNT(*) FROM message_search", [], |row| row.get(0))?;
+        assert_eq!(indexed, 0);
+
+        let messages = store.read_messages("test")?;
+        assert_eq!(messages[0].as_concat_text(), "secret plans");
+        assert_eq!(
+            store.read_metadata("test")?.description,
+            "secret description"
+        );
+        assert_eq!(store.search("secrets", 10)?[0].message_index, Some(1));
+        drop(store);
+
+        // Encrypted sessions need the key, but not encryption turned on
+        assert!(SqliteStore::open(&path)?.read_messages("test").is_err());
+        let decrypting = SqliteStore::open_with_cipher(&path, Some(cipher), false)?;
+        assert_eq!(decrypting.read_messages("test")?, messages);
+        Ok(())
+    }
+}
@@ -293,10 +293,10 @@ mod tests {
             },
         ];
 
-        let tools = vec![Tool {
-            name: "get_current_weather".to_string(),
-            description: "Get the current weather in a given location".to_string(),
-            input_schema: json!({
+        let tools = vec![Tool::new(
+            "get_current_weather",
+            "Get the current weather in a given location",
+            json!({
                 "properties": {
                     "location": {
                         "type": "string",
@@ -310,7 +310,7 @@ mod tests {
                 },
                 "required": ["location"]
             }),
-        }];
+        )];
 
         let token_count_without_tools = counter.count_chat_tokens(system_prompt, &messages, &[]);
         println!("Total tokens without tools: {}", token_count_without_tools);
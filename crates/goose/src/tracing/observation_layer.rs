@@ -11,6 +11,8 @@ use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::Layer;
 use uuid::Uuid;
 
+use crate::redaction::Redactor;
+
 #[derive(Debug, Clone)]
 pub struct SpanData {
     pub observation_id: String, // Langfuse requires ids to be UUID v4 strings
@@ -31,6 +33,17 @@ pub fn map_level(level: &Level) -> &'static str {
     }
 }
 
+/// Redact secrets from recorded fields before they are sent anywhere, see
+/// [`Redactor::global`]
+fn redact_fields(mut fields: serde_json::Map<String, Value>) -> serde_json::Map<String, Value> {
+    if let Some(redactor) = Redactor::global() {
+        for value in fields.values_mut() {
+            redactor.redact_json(value);
+        }
+    }
+    fields
+}
+
 pub fn flatten_metadata(
     metadata: serde_json::Map<String, Value>,
 ) -> serde_json::Map<String, Value> {
@@ -101,7 +114,8 @@ pub struct ObservationLayer {
 }
 
 impl ObservationLayer {
-    pub async fn handle_span(&self, span_id: u64, span_data: SpanData) {
+    pub async fn handle_span(&self, span_id: u64, mut span_data: SpanData) {
+        span_data.metadata = redact_fields(span_data.metadata);
         let observation_id = span_data.observation_id.clone();
 
         {
@@ -184,6 +198,7 @@ impl ObservationLayer {
     }
 
     pub async fn handle_record(&self, span_id: u64, metadata: serde_json::Map<String, Value>) {
+        let metadata = redact_fields(metadata);
         let observation_id = {
             let spans = self.span_tracker.lock().await;
             spans.get_span(span_id).cloned()
@@ -1,7 +1,10 @@
 use mcp_core::protocol::{
-    CallToolResult, GetPromptResult, Implementation, InitializeResult, JsonRpcError,
-    JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
-    ListResourcesResult, ListToolsResult, ReadResourceResult, ServerCapabilities, METHOD_NOT_FOUND,
+    CallToolResult, CompleteParams, CompleteResult, CompletionArgument, CompletionReference,
+    EmptyResult, GetPromptResult, Implementation, InitializeResult, JsonRpcError, JsonRpcMessage,
+    JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
+    ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, LoggingLevel,
+    ReadResourceResult, RootsCapability, ServerCapabilities, SetLevelParams,
+    LATEST_PROTOCOL_VERSION, METHOD_NOT_FOUND, SUPPORTED_PROTOCOL_VERSIONS,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -30,6 +33,9 @@ pub enum Error {
     #[error("Not initialized")]
     NotInitialized,
 
+    #[error("Server speaks unsupported protocol version {0}")]
+    UnsupportedProtocolVersion(String),
+
     #[error("Timeout or service not ready")]
     NotReady,
 
@@ -48,6 +54,27 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Whether the call may succeed if made again, e.g. once a dropped connection is back
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::Transport(e) => e.is_retriable(),
+            Error::ServerBoxError(e) => e
+                .downcast_ref::<super::transport::Error>()
+                .is_some_and(|e| e.is_retriable()),
+            Error::McpServerError { source, .. } => {
+                source
+                    .downcast_ref::<super::transport::Error>()
+                    .is_some_and(|e| e.is_retriable())
+                    || source
+                        .downcast_ref::<Error>()
+                        .is_some_and(|e| e.is_retriable())
+            }
+            _ => false,
+        }
+    }
+}
+
 // BoxError from mcp-server gets converted to our Error type
 impl From<BoxError> for Error {
     fn from(err: BoxError) -> Self {
@@ -55,15 +82,17 @@ impl From<BoxError> for Error {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ClientInfo {
     pub name: String,
     pub version: String,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct ClientCapabilities {
-    // Add fields as needed. For now, empty capabilities are fine.
+    /// Set when the client answers `roots/list`, see [`crate::roots::serve_roots`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roots: Option<RootsCapability>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -83,6 +112,9 @@ pub trait McpClientTrait: Send + Sync {
         capabilities: ClientCapabilities,
     ) -> Result<InitializeResult, Error>;
 
+    /// Check the server is still there, succeeds as long as it answers at all
+    async fn ping(&self) -> Result<(), Error>;
+
     async fn list_resources(
         &self,
         next_cursor: Option<String>,
@@ -90,6 +122,16 @@ pub trait McpClientTrait: Send + Sync {
 
     async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, Error>;
 
+    async fn list_resource_templates(
+        &self,
+        next_cursor: Option<String>,
+    ) -> Result<ListResourceTemplatesResult, Error>;
+
+    /// Ask the server to send `notifications/resources/updated` when the resource changes
+    async fn subscribe_resource(&self, uri: &str) -> Result<(), Error>;
+
+    async fn unsubscribe_resource(&self, uri: &str) -> Result<(), Error>;
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error>;
 
     async fn call_tool(&self, name: &str, arguments: Value) -> Result<CallToolResult, Error>;
@@ -97,6 +139,19 @@ pub trait McpClientTrait: Send + Sync {
     async fn list_prompts(&self, next_cursor: Option<String>) -> Result<ListPromptsResult, Error>;
 
     async fn get_prompt(&self, name: &str, arguments: Value) -> Result<GetPromptResult, Error>;
+
+    /// Ask the server for suggested values of a prompt or resource template argument
+    async fn complete(
+        &self,
+        reference: CompletionReference,
+        argument: CompletionArgument,
+    ) -> Result<CompleteResult, Error>;
+
+    /// Ask the server to only send `notifications/message` logs at or above `level`
+    async fn set_logging_level(&self, level: LoggingLevel) -> Result<(), Error>;
+
+    /// Tell the server the roots it gets from `roots/list` changed
+    async fn notify_roots_list_changed(&self) -> Result<(), Error>;
 }
 
 /// The MCP client is the interface for MCP operations.
@@ -226,6 +281,32 @@ where
         Ok(())
     }
 
+    async fn set_resource_subscription(&self, method: &str, uri: &str) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+
+        // If subscriptions are not supported, return an error
+        let supports_subscribe = self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .resources
+            .as_ref()
+            .and_then(|r| r.subscribe)
+            .unwrap_or(false);
+        if !supports_subscribe {
+            return Err(Error::RpcError {
+                code: METHOD_NOT_FOUND,
+                message: "Server does not support resource subscriptions".to_string(),
+            });
+        }
+
+        let params = serde_json::json!({ "uri": uri });
+        let _: EmptyResult = self.send_request(method, params).await?;
+        Ok(())
+    }
+
     // Check if the client has completed initialization
     fn completed_initialization(&self) -> bool {
         self.server_capabilities.is_some()
@@ -245,7 +326,7 @@ where
         capabilities: ClientCapabilities,
     ) -> Result<InitializeResult, Error> {
         let params = InitializeParams {
-            protocol_version: "1.0.0".into(),
+            protocol_version: LATEST_PROTOCOL_VERSION.into(),
             client_info: info,
             capabilities,
         };
@@ -253,6 +334,11 @@ where
             .send_request("initialize", serde_json::to_value(params)?)
             .await?;
 
+        // The server answers with another version if it cannot speak ours
+        if !SUPPORTED_PROTOCOL_VERSIONS.contains(&result.protocol_version.as_str()) {
+            return Err(Error::UnsupportedProtocolVersion(result.protocol_version));
+        }
+
         self.send_notification("notifications/initialized", serde_json::json!({}))
             .await?;
 
@@ -263,6 +349,21 @@ where
         Ok(result)
     }
 
+    async fn ping(&self) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+
+        match self
+            .send_request::<EmptyResult>("ping", serde_json::json!({}))
+            .await
+        {
+            // Servers that predate ping still answer, with an error, which is as good
+            Ok(_) | Err(Error::RpcError { .. }) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     async fn list_resources(
         &self,
         next_cursor: Option<String>,
@@ -313,6 +414,44 @@ where
         self.send_request("resources/read", params).await
     }
 
+    async fn list_resource_templates(
+        &self,
+        next_cursor: Option<String>,
+    ) -> Result<ListResourceTemplatesResult, Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+        // If resources is not supported, return an empty list
+        if self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .resources
+            .is_none()
+        {
+            return Ok(ListResourceTemplatesResult {
+                resource_templates: vec![],
+                next_cursor: None,
+            });
+        }
+
+        let payload = next_cursor
+            .map(|cursor| serde_json::json!({"cursor": cursor}))
+            .unwrap_or_else(|| serde_json::json!({}));
+
+        self.send_request("resources/templates/list", payload).await
+    }
+
+    async fn subscribe_resource(&self, uri: &str) -> Result<(), Error> {
+        self.set_resource_subscription("resources/subscribe", uri)
+            .await
+    }
+
+    async fn unsubscribe_resource(&self, uri: &str) -> Result<(), Error> {
+        self.set_resource_subscription("resources/unsubscribe", uri)
+            .await
+    }
+
     async fn list_tools(&self, next_cursor: Option<String>) -> Result<ListToolsResult, Error> {
         if !self.completed_initialization() {
             return Err(Error::NotInitialized);
@@ -388,4 +527,57 @@ where
 
         self.send_request("prompts/get", params).await
     }
+
+    async fn set_logging_level(&self, level: LoggingLevel) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+
+        // If logging is not supported, return an error
+        if self.server_capabilities.as_ref().unwrap().logging.is_none() {
+            return Err(Error::RpcError {
+                code: METHOD_NOT_FOUND,
+                message: "Server does not support 'logging' capability".to_string(),
+            });
+        }
+
+        let params = serde_json::to_value(SetLevelParams { level })?;
+        let _: EmptyResult = self.send_request("logging/setLevel", params).await?;
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        reference: CompletionReference,
+        argument: CompletionArgument,
+    ) -> Result<CompleteResult, Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+        // If completions are not supported, there are no suggestions
+        if self
+            .server_capabilities
+            .as_ref()
+            .unwrap()
+            .completions
+            .is_none()
+        {
+            return Ok(CompleteResult::default());
+        }
+
+        let params = serde_json::to_value(CompleteParams {
+            reference,
+            argument,
+        })?;
+        self.send_request("completion/complete", params).await
+    }
+
+    async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+        if !self.completed_initialization() {
+            return Err(Error::NotInitialized);
+        }
+
+        self.send_notification("notifications/roots/list_changed", serde_json::json!({}))
+            .await
+    }
 }
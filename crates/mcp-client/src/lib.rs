@@ -1,7 +1,16 @@
 pub mod client;
+pub mod manager;
+pub mod roots;
 pub mod service;
 pub mod transport;
 
 pub use client::{ClientCapabilities, ClientInfo, Error, McpClient, McpClientTrait};
+pub use manager::{
+    Connection, Connector, ManagerError, ManagerEvent, McpClientManager, ServerConfig, ServerStatus,
+};
+pub use roots::{serve_roots, Roots};
 pub use service::McpService;
-pub use transport::{SseTransport, StdioTransport, Transport, TransportHandle};
+pub use transport::{
+    InProcessTransport, RecordingTransport, ReplayTransport, SseTransport, StdioTransport,
+    Transport, TransportHandle,
+};
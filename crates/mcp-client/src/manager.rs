@@ -0,0 +1,1050 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
+use mcp_core::protocol::{
+    CallToolResult, GetPromptResult, InitializeResult, JsonRpcNotification, ReadResourceResult,
+};
+use mcp_core::{prompt::Prompt, Resource, Tool};
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+use tokio::sync::{broadcast, watch, Mutex};
+
+use crate::client::{BoxError, ClientCapabilities, ClientInfo, Error, McpClient, McpClientTrait};
+use crate::roots::{serve_roots, Roots};
+use crate::service::McpService;
+use crate::transport::{ConnectionState, SseTransport, StdioTransport, Transport, TransportHandle};
+
+/// A client shared between the manager and the tasks watching its server
+pub type SharedClient = Arc<Mutex<Box<dyn McpClientTrait>>>;
+
+type Statuses = Arc<Mutex<HashMap<String, ServerStatus>>>;
+
+/// How long requests to servers started from a [`ServerConfig`] may take by default
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often servers are pinged by default to check they are alive
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a server gets to answer a ping before it counts as unresponsive
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Capacity of the event channel, slow subscribers skip the oldest events
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Health of a server, as seen by the periodic pings and its connection
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "snake_case")]
+pub enum ServerStatus {
+    Healthy,
+    /// The last ping failed, but the server cannot be restarted
+    Unresponsive(String),
+    /// The server process died and is being started again
+    Restarting,
+    /// The connection to a remote server dropped and is being established again
+    Reconnecting,
+    /// The server process died and could not be started again
+    Failed(String),
+}
+
+impl std::fmt::Display for ServerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServerStatus::Healthy => write!(f, "healthy"),
+            ServerStatus::Unresponsive(reason) => write!(f, "unresponsive: {}", reason),
+            ServerStatus::Restarting => write!(f, "restarting"),
+            ServerStatus::Reconnecting => write!(f, "reconnecting"),
+            ServerStatus::Failed(reason) => write!(f, "failed: {}", reason),
+        }
+    }
+}
+
+/// A change to the managed servers, see [`McpClientManager::subscribe`]
+#[derive(Clone, Debug)]
+pub enum ManagerEvent {
+    ServerAdded(String),
+    ServerRemoved(String),
+    StatusChanged {
+        server: String,
+        status: ServerStatus,
+    },
+    /// The server reported its tools changed, through `notifications/tools/list_changed`
+    ToolsChanged(String),
+    /// The server reported its prompts changed, through `notifications/prompts/list_changed`
+    PromptsChanged(String),
+    /// The server reported its resources changed, through `notifications/resources/list_changed`
+    ResourcesChanged(String),
+    /// Any other notification of the server, e.g. logs or resource updates
+    Notification {
+        server: String,
+        notification: JsonRpcNotification,
+    },
+}
+
+/// Errors of routing requests to the managed servers
+#[derive(Debug, Error)]
+pub enum ManagerError {
+    #[error("Server {0} is not managed")]
+    UnknownServer(String),
+    #[error("No server provides the tool {0}")]
+    UnknownTool(String),
+    #[error("Failed to start server {0}: {1}")]
+    Start(String, BoxError),
+    #[error(transparent)]
+    Client(#[from] Error),
+}
+
+/// A started server, with its client initialized
+pub struct Connection {
+    pub client: Box<dyn McpClientTrait>,
+    pub init_result: InitializeResult,
+    pub notifications: broadcast::Receiver<JsonRpcNotification>,
+    pub connection: watch::Receiver<ConnectionState>,
+}
+
+/// Starts a server and initializes a client for it. The manager connects again through it
+/// to restart servers whose process died.
+#[async_trait]
+pub trait Connector: Send + Sync + 'static {
+    async fn connect(&self) -> Result<Connection, BoxError>;
+
+    /// Whether the server can be started again once it stops answering. Remote servers
+    /// reconnect through their transport instead, and are only reported unresponsive.
+    fn restartable(&self) -> bool {
+        true
+    }
+}
+
+/// A server the manager starts itself
+#[derive(Clone, Debug)]
+pub enum ServerConfig {
+    Stdio {
+        name: String,
+        cmd: String,
+        args: Vec<String>,
+        envs: HashMap<String, String>,
+    },
+    Sse {
+        name: String,
+        uri: String,
+        headers: HashMap<String, String>,
+    },
+}
+
+impl ServerConfig {
+    pub fn stdio<N: Into<String>, C: Into<String>>(name: N, cmd: C, args: Vec<String>) -> Self {
+        ServerConfig::Stdio {
+            name: name.into(),
+            cmd: cmd.into(),
+            args,
+            envs: HashMap::new(),
+        }
+    }
+
+    pub fn sse<N: Into<String>, U: Into<String>>(name: N, uri: U) -> Self {
+        ServerConfig::Sse {
+            name: name.into(),
+            uri: uri.into(),
+            headers: HashMap::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ServerConfig::Stdio { name, .. } | ServerConfig::Sse { name, .. } => name,
+        }
+    }
+}
+
+/// Initialize a client for the server behind `handle`, answering its `roots/list`
+/// requests with `roots`
+pub async fn connect<H: TransportHandle>(
+    handle: H,
+    roots: &Roots,
+    timeout: Duration,
+    client_info: ClientInfo,
+    capabilities: ClientCapabilities,
+) -> Result<Connection, Error> {
+    let notifications = handle.subscribe();
+    let connection = handle.connection_state();
+    serve_roots(&handle, roots.clone());
+
+    let service = McpService::with_timeout(handle, timeout);
+    let mut client: Box<dyn McpClientTrait> = Box::new(McpClient::new(service));
+    let init_result = client.initialize(client_info, capabilities).await?;
+
+    Ok(Connection {
+        client,
+        init_result,
+        notifications,
+        connection,
+    })
+}
+
+/// Connects to the server of a [`ServerConfig`] with the client settings of the manager
+struct ConfigConnector {
+    config: ServerConfig,
+    roots: Roots,
+    timeout: Duration,
+    client_info: ClientInfo,
+    capabilities: ClientCapabilities,
+}
+
+#[async_trait]
+impl Connector for ConfigConnector {
+    async fn connect(&self) -> Result<Connection, BoxError> {
+        let client_info = self.client_info.clone();
+        let capabilities = self.capabilities.clone();
+        let connection = match &self.config {
+            ServerConfig::Stdio {
+                cmd, args, envs, ..
+            } => {
+                let transport = StdioTransport::new(cmd, args.clone(), envs.clone());
+                let handle = transport.start().await?;
+                connect(handle, &self.roots, self.timeout, client_info, capabilities).await?
+            }
+            ServerConfig::Sse { uri, headers, .. } => {
+                let transport = SseTransport::new(uri, headers.clone());
+                let handle = transport.start().await?;
+                connect(handle, &self.roots, self.timeout, client_info, capabilities).await?
+            }
+        };
+        Ok(connection)
+    }
+
+    fn restartable(&self) -> bool {
+        matches!(self.config, ServerConfig::Stdio { .. })
+    }
+}
+
+struct ManagedServer {
+    client: SharedClient,
+    // none for clients connected by other means, see `add_client`
+    init_result: Option<InitializeResult>,
+}
+
+/// Runs several MCP servers side by side and uses them as one.
+///
+/// Tools are listed with the name of their server as prefix, `{server}__{tool}`, and calls
+/// to them are routed back to that server. Servers are pinged periodically, those whose
+/// process died are started again, and changes are published as [`ManagerEvent`]s.
+pub struct McpClientManager {
+    servers: HashMap<String, ManagedServer>,
+    statuses: Statuses,
+    events: broadcast::Sender<ManagerEvent>,
+    roots: Roots,
+    client_info: ClientInfo,
+    capabilities: ClientCapabilities,
+    timeout: Duration,
+    ping_interval: Option<Duration>,
+}
+
+impl McpClientManager {
+    pub fn new(client_info: ClientInfo, capabilities: ClientCapabilities) -> Self {
+        Self {
+            servers: HashMap::new(),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            roots: Roots::default(),
+            client_info,
+            capabilities,
+            timeout: DEFAULT_TIMEOUT,
+            ping_interval: Some(DEFAULT_PING_INTERVAL),
+        }
+    }
+
+    /// Serve `roots` to the servers the manager starts
+    pub fn with_roots(mut self, roots: Roots) -> Self {
+        self.roots = roots;
+        self
+    }
+
+    /// How long requests to the servers the manager starts may take
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// How often servers are pinged, `None` turns pinging and thereby restarts off
+    pub fn with_ping_interval(mut self, ping_interval: Option<Duration>) -> Self {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    pub fn roots(&self) -> &Roots {
+        &self.roots
+    }
+
+    /// Subscribe to changes of the servers, only changes after subscribing are delivered
+    pub fn subscribe(&self) -> broadcast::Receiver<ManagerEvent> {
+        self.events.subscribe()
+    }
+
+    /// Start the server of `config` and manage it under the name of the config
+    pub async fn start_server(&mut self, config: ServerConfig) -> Result<(), ManagerError> {
+        let name = config.name().to_string();
+        let connector = self.config_connector(config);
+        let connection = connector
+            .connect()
+            .await
+            .map_err(|e| ManagerError::Start(name.clone(), e))?;
+        self.add_server(name, connection, Some(Arc::new(connector)))
+            .await;
+        Ok(())
+    }
+
+    /// Start the servers of `configs` concurrently, returning the errors of those that failed
+    pub async fn start_servers(&mut self, configs: Vec<ServerConfig>) -> Vec<ManagerError> {
+        let connectors: Vec<_> = configs
+            .into_iter()
+            .map(|config| self.config_connector(config))
+            .collect();
+        let connections =
+            futures::future::join_all(connectors.iter().map(|connector| connector.connect())).await;
+
+        let mut errors = Vec::new();
+        for (connector, connection) in connectors.into_iter().zip(connections) {
+            let name = connector.config.name().to_string();
+            match connection {
+                Ok(connection) => {
+                    self.add_server(name, connection, Some(Arc::new(connector)))
+                        .await
+                }
+                Err(e) => errors.push(ManagerError::Start(name, e)),
+            }
+        }
+        errors
+    }
+
+    fn config_connector(&self, config: ServerConfig) -> ConfigConnector {
+        ConfigConnector {
+            config,
+            roots: self.roots.clone(),
+            timeout: self.timeout,
+            client_info: self.client_info.clone(),
+            capabilities: self.capabilities.clone(),
+        }
+    }
+
+    /// Manage a server connected by the caller. The server is restarted through `connector`
+    /// when it stops answering, or only reported unresponsive without one.
+    pub async fn add_server<N: Into<String>>(
+        &mut self,
+        name: N,
+        connection: Connection,
+        connector: Option<Arc<dyn Connector>>,
+    ) {
+        let name = name.into();
+        let client = Arc::new(Mutex::new(connection.client));
+
+        self.statuses
+            .lock()
+            .await
+            .insert(name.clone(), ServerStatus::Healthy);
+        forward_notifications(name.clone(), connection.notifications, self.events.clone());
+        track_connection(
+            name.clone(),
+            connection.connection,
+            Arc::downgrade(&client),
+            self.status_updates(),
+            self.client_info.clone(),
+            self.capabilities.clone(),
+        );
+        if let Some(interval) = self.ping_interval {
+            monitor_server(
+                name.clone(),
+                connector,
+                Arc::downgrade(&client),
+                self.status_updates(),
+                interval,
+                self.client_info.clone(),
+                self.capabilities.clone(),
+            );
+        }
+
+        self.servers.insert(
+            name.clone(),
+            ManagedServer {
+                client,
+                init_result: Some(connection.init_result),
+            },
+        );
+        let _ = self.events.send(ManagerEvent::ServerAdded(name));
+    }
+
+    /// Manage a client connected by other means, which is neither monitored nor restarted
+    pub fn add_client<N: Into<String>>(&mut self, name: N, client: Box<dyn McpClientTrait>) {
+        let name = name.into();
+        self.servers.insert(
+            name.clone(),
+            ManagedServer {
+                client: Arc::new(Mutex::new(client)),
+                init_result: None,
+            },
+        );
+        let _ = self.events.send(ManagerEvent::ServerAdded(name));
+    }
+
+    /// Stop managing a server, which stops it once nobody uses its client anymore.
+    /// Returns whether the server was managed.
+    pub async fn remove_server(&mut self, name: &str) -> bool {
+        self.statuses.lock().await.remove(name);
+        let removed = self.servers.remove(name).is_some();
+        if removed {
+            let _ = self
+                .events
+                .send(ManagerEvent::ServerRemoved(name.to_string()));
+        }
+        removed
+    }
+
+    fn status_updates(&self) -> StatusUpdates {
+        StatusUpdates {
+            statuses: Arc::clone(&self.statuses),
+            events: self.events.clone(),
+        }
+    }
+
+    pub fn server_names(&self) -> impl Iterator<Item = &String> {
+        self.servers.keys()
+    }
+
+    pub fn clients(&self) -> impl Iterator<Item = (&String, &SharedClient)> {
+        self.servers
+            .iter()
+            .map(|(name, server)| (name, &server.client))
+    }
+
+    pub fn client(&self, name: &str) -> Option<SharedClient> {
+        self.servers
+            .get(name)
+            .map(|server| Arc::clone(&server.client))
+    }
+
+    /// What the server answered to `initialize`, none for clients added through `add_client`
+    pub fn init_result(&self, name: &str) -> Option<&InitializeResult> {
+        self.servers
+            .get(name)
+            .and_then(|server| server.init_result.as_ref())
+    }
+
+    /// The health of every server, clients added through `add_client` count as healthy
+    pub async fn statuses(&self) -> Vec<(String, ServerStatus)> {
+        let statuses = self.statuses.lock().await;
+        self.servers
+            .keys()
+            .map(|name| {
+                let status = statuses.get(name).cloned().unwrap_or(ServerStatus::Healthy);
+                (name.clone(), status)
+            })
+            .collect()
+    }
+
+    /// The server of a prefixed tool name, with its client
+    ///
+    /// Server names may contain the separator themselves, so the longest name matching wins,
+    /// e.g. `dev__x__echo` is a tool of `dev__x` rather than of `dev`.
+    pub fn client_for_tool(&self, prefixed_name: &str) -> Option<(&str, SharedClient)> {
+        self.servers
+            .iter()
+            .filter(|(name, _)| prefixed_name.starts_with(&format!("{name}__")))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(name, server)| (name.as_str(), Arc::clone(&server.client)))
+    }
+
+    /// The tools of all servers, named `{server}__{tool}`
+    pub async fn list_tools(&self) -> Result<Vec<Tool>, Error> {
+        let mut tools = Vec::new();
+        for (name, server) in &self.servers {
+            let client = server.client.lock().await;
+            let mut next_cursor = None;
+            loop {
+                let page = client.list_tools(next_cursor).await?;
+                tools.extend(page.tools.into_iter().map(|tool| Tool {
+                    name: format!("{}__{}", name, tool.name),
+                    ..tool
+                }));
+
+                next_cursor = page.next_cursor;
+                if next_cursor.is_none() {
+                    break;
+                }
+            }
+        }
+        Ok(tools)
+    }
+
+    /// Call a tool by its prefixed name on the server providing it
+    pub async fn call_tool(
+        &self,
+        prefixed_name: &str,
+        arguments: Value,
+    ) -> Result<CallToolResult, ManagerError> {
+        let (server, client) = self
+            .client_for_tool(prefixed_name)
+            .ok_or_else(|| ManagerError::UnknownTool(prefixed_name.to_string()))?;
+        let tool_name = prefixed_name
+            .strip_prefix(server)
+            .and_then(|name| name.strip_prefix("__"))
+            .ok_or_else(|| ManagerError::UnknownTool(prefixed_name.to_string()))?;
+
+        let client = client.lock().await;
+        Ok(client.call_tool(tool_name, arguments).await?)
+    }
+
+    /// The prompts of every server, servers failing to list them are left out
+    pub async fn list_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut futures: FuturesUnordered<_> = self
+            .servers
+            .iter()
+            .map(|(name, server)| async move {
+                let prompts = server.client.lock().await.list_prompts(None).await;
+                (name.clone(), prompts)
+            })
+            .collect();
+
+        let mut all_prompts = HashMap::new();
+        while let Some((name, prompts)) = futures.next().await {
+            match prompts {
+                Ok(prompts) => {
+                    all_prompts.insert(name, prompts.prompts);
+                }
+                Err(e) => tracing::debug!(server = %name, error = %e, "failed to list prompts"),
+            }
+        }
+        all_prompts
+    }
+
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        name: &str,
+        arguments: Value,
+    ) -> Result<GetPromptResult, ManagerError> {
+        let client = self
+            .client(server)
+            .ok_or_else(|| ManagerError::UnknownServer(server.to_string()))?;
+        let client = client.lock().await;
+        Ok(client.get_prompt(name, arguments).await?)
+    }
+
+    /// The resources of every server, servers failing to list them are left out
+    pub async fn list_resources(&self) -> HashMap<String, Vec<Resource>> {
+        let mut futures: FuturesUnordered<_> = self
+            .servers
+            .iter()
+            .map(|(name, server)| async move {
+                let resources = server.client.lock().await.list_resources(None).await;
+                (name.clone(), resources)
+            })
+            .collect();
+
+        let mut all_resources = HashMap::new();
+        while let Some((name, resources)) = futures.next().await {
+            match resources {
+                Ok(resources) => {
+                    all_resources.insert(name, resources.resources);
+                }
+                Err(e) => tracing::debug!(server = %name, error = %e, "failed to list resources"),
+            }
+        }
+        all_resources
+    }
+
+    pub async fn read_resource(
+        &self,
+        server: &str,
+        uri: &str,
+    ) -> Result<ReadResourceResult, ManagerError> {
+        let client = self
+            .client(server)
+            .ok_or_else(|| ManagerError::UnknownServer(server.to_string()))?;
+        let client = client.lock().await;
+        Ok(client.read_resource(uri).await?)
+    }
+}
+
+/// Where the tasks watching a server record its health
+#[derive(Clone)]
+struct StatusUpdates {
+    statuses: Statuses,
+    events: broadcast::Sender<ManagerEvent>,
+}
+
+impl StatusUpdates {
+    async fn set(&self, name: &str, status: ServerStatus) {
+        let mut statuses = self.statuses.lock().await;
+        // a removed server has no status left to update
+        let Some(current) = statuses.get_mut(name) else {
+            return;
+        };
+        if *current != status {
+            *current = status.clone();
+            let _ = self.events.send(ManagerEvent::StatusChanged {
+                server: name.to_string(),
+                status,
+            });
+        }
+    }
+
+    async fn get(&self, name: &str) -> Option<ServerStatus> {
+        self.statuses.lock().await.get(name).cloned()
+    }
+}
+
+/// Publish the notifications of a server as events, until its transport closes
+fn forward_notifications(
+    server: String,
+    mut notifications: broadcast::Receiver<JsonRpcNotification>,
+    events: broadcast::Sender<ManagerEvent>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(server = %server, skipped, "dropped server notifications");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let event = match notification.method.as_str() {
+                "notifications/tools/list_changed" => ManagerEvent::ToolsChanged(server.clone()),
+                "notifications/prompts/list_changed" => {
+                    ManagerEvent::PromptsChanged(server.clone())
+                }
+                "notifications/resources/list_changed" => {
+                    ManagerEvent::ResourcesChanged(server.clone())
+                }
+                _ => ManagerEvent::Notification {
+                    server: server.clone(),
+                    notification,
+                },
+            };
+            let _ = events.send(event);
+        }
+    });
+}
+
+/// Follow the connection of a server, initializing it again when a reconnect ended up
+/// in a new session on the server. Stops once the connection is closed.
+fn track_connection(
+    name: String,
+    mut connection: watch::Receiver<ConnectionState>,
+    client: Weak<Mutex<Box<dyn McpClientTrait>>>,
+    statuses: StatusUpdates,
+    client_info: ClientInfo,
+    capabilities: ClientCapabilities,
+) {
+    tokio::spawn(async move {
+        while connection.changed().await.is_ok() {
+            let state = *connection.borrow_and_update();
+            let status = match state {
+                ConnectionState::Connected => ServerStatus::Healthy,
+                ConnectionState::Reconnecting { attempt } => {
+                    tracing::debug!(server = %name, attempt, "reconnecting to server");
+                    ServerStatus::Reconnecting
+                }
+                ConnectionState::Reconnected { new_session } => {
+                    if new_session {
+                        let Some(client) = client.upgrade() else {
+                            break;
+                        };
+                        let mut client = client.lock().await;
+                        if let Err(e) = client
+                            .initialize(client_info.clone(), capabilities.clone())
+                            .await
+                        {
+                            tracing::warn!(server = %name, error = %e, "failed to initialize the new session");
+                        }
+                    }
+                    ServerStatus::Healthy
+                }
+                ConnectionState::Closed => {
+                    let reason = "connection closed".to_string();
+                    statuses.set(&name, ServerStatus::Failed(reason)).await;
+                    break;
+                }
+            };
+            statuses.set(&name, status).await;
+        }
+    });
+}
+
+/// Ping a server every `interval` and record its health. Servers whose process died are
+/// started again in place through their connector, others can only be reported
+/// unresponsive. Stops once the server is removed.
+fn monitor_server(
+    name: String,
+    connector: Option<Arc<dyn Connector>>,
+    weak_client: Weak<Mutex<Box<dyn McpClientTrait>>>,
+    statuses: StatusUpdates,
+    interval: Duration,
+    client_info: ClientInfo,
+    capabilities: ClientCapabilities,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // the first tick completes immediately, and the server just started
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            let Some(client) = weak_client.upgrade() else {
+                break;
+            };
+            // A client busy with a call is evidently in use, check it next time
+            let Ok(mut client) = client.try_lock() else {
+                continue;
+            };
+
+            let error = match tokio::time::timeout(PING_TIMEOUT, client.ping()).await {
+                Ok(Ok(())) => {
+                    statuses.set(&name, ServerStatus::Healthy).await;
+                    continue;
+                }
+                // A slow answer does not mean the process is gone
+                Err(_) => {
+                    let reason = "ping timed out".to_string();
+                    statuses
+                        .set(&name, ServerStatus::Unresponsive(reason))
+                        .await;
+                    continue;
+                }
+                Ok(Err(e)) => e,
+            };
+
+            let Some(connector) = connector.as_ref().filter(|c| c.restartable()) else {
+                // the transport may already be reconnecting and reports progress itself
+                if statuses.get(&name).await == Some(ServerStatus::Reconnecting) {
+                    continue;
+                }
+                let reason = error.to_string();
+                statuses
+                    .set(&name, ServerStatus::Unresponsive(reason))
+                    .await;
+                continue;
+            };
+
+            tracing::warn!(server = %name, error = %error, "server stopped responding, restarting it");
+            statuses.set(&name, ServerStatus::Restarting).await;
+
+            match connector.connect().await {
+                Ok(connection) => {
+                    *client = connection.client;
+                    forward_notifications(
+                        name.clone(),
+                        connection.notifications,
+                        statuses.events.clone(),
+                    );
+                    track_connection(
+                        name.clone(),
+                        connection.connection,
+                        weak_client.clone(),
+                        statuses.clone(),
+                        client_info.clone(),
+                        capabilities.clone(),
+                    );
+                    statuses.set(&name, ServerStatus::Healthy).await;
+                }
+                Err(e) => {
+                    tracing::warn!(server = %name, error = %e, "failed to restart server");
+                    statuses
+                        .set(&name, ServerStatus::Failed(e.to_string()))
+                        .await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::InProcessTransport;
+    use mcp_core::content::Content;
+    use mcp_server::router::RouterService;
+    use mcp_server::testing::EchoRouter;
+    use mcp_server::Server;
+    use serde_json::json;
+
+    /// Runs an echo server in process, counting how often it was started
+    struct EchoConnector {
+        starts: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Connector for EchoConnector {
+        async fn connect(&self) -> Result<Connection, BoxError> {
+            self.starts
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let (to_server, from_server) = Server::new(RouterService(EchoRouter)).spawn();
+            let transport = InProcessTransport::new(to_server, from_server);
+            let handle = transport.start().await?;
+            Ok(connect(
+                handle,
+                &Roots::default(),
+                DEFAULT_TIMEOUT,
+                client_info(),
+                ClientCapabilities::default(),
+            )
+            .await?)
+        }
+    }
+
+    fn client_info() -> ClientInfo {
+        ClientInfo {
+            name: "test".to_string(),
+            version: "1.0.0".to_string(),
+        }
+    }
+
+    /// Wait until a server changes to a status matching `status`
+    async fn wait_for_status(
+        events: &mut broadcast::Receiver<ManagerEvent>,
+        status: fn(&ServerStatus) -> bool,
+    ) {
+        let changed = async {
+            loop {
+                match events.recv().await {
+                    Ok(ManagerEvent::StatusChanged { status: s, .. }) if status(&s) => return,
+                    Ok(_) => continue,
+                    Err(e) => panic!("no more events: {}", e),
+                }
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), changed)
+            .await
+            .expect("status not reached");
+    }
+
+    #[tokio::test]
+    async fn test_tools_route_to_the_longest_server_name() {
+        let mut manager = McpClientManager::new(client_info(), ClientCapabilities::default())
+            .with_ping_interval(None);
+        for name in ["dev", "dev__x"] {
+            let connector = EchoConnector {
+                starts: Default::default(),
+            };
+            let connection = connector.connect().await.unwrap();
+            manager.add_server(name, connection, None).await;
+        }
+
+        assert_eq!(
+            manager
+                .client_for_tool("dev__x__echo")
+                .map(|(name, _)| name),
+            Some("dev__x")
+        );
+        assert_eq!(
+            manager.client_for_tool("dev__echo").map(|(name, _)| name),
+            Some("dev")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manager_aggregates_and_routes_tools() {
+        let mut manager = McpClientManager::new(client_info(), ClientCapabilities::default())
+            .with_ping_interval(None);
+        let mut events = manager.subscribe();
+
+        for name in ["first", "second"] {
+            let connector = EchoConnector {
+                starts: Default::default(),
+            };
+            let connection = connector.connect().await.unwrap();
+            manager
+                .add_server(name, connection, Some(Arc::new(connector)))
+                .await;
+        }
+        assert!(
+            matches!(events.recv().await, Ok(ManagerEvent::ServerAdded(name)) if name == "first")
+        );
+        assert_eq!(
+            manager
+                .init_result("first")
+                .unwrap()
+                .instructions
+                .as_deref(),
+            Some("Echoes its input")
+        );
+
+        let mut names: Vec<String> = manager
+            .list_tools()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["first__echo", "second__echo"]);
+
+        let result = manager
+            .call_tool("second__echo", json!({"text": "hi"}))
+            .await
+            .unwrap();
+        assert_eq!(result.content, vec![Content::text(r#"{"text":"hi"}"#)]);
+        assert!(matches!(
+            manager.call_tool("third__echo", json!({})).await,
+            Err(ManagerError::UnknownTool(_))
+        ));
+        // a server name is only a prefix up to the separator
+        assert!(manager.client_for_tool("firstly__echo").is_none());
+        assert_eq!(
+            manager.client_for_tool("first__echo").map(|(name, _)| name),
+            Some("first")
+        );
+
+        assert!(manager.remove_server("first").await);
+        assert_eq!(manager.list_tools().await.unwrap().len(), 1);
+        assert_eq!(manager.statuses().await.len(), 1);
+    }
+
+    /// A client whose server stopped answering
+    struct DeadClient;
+
+    #[async_trait]
+    impl McpClientTrait for DeadClient {
+        async fn initialize(
+            &mut self,
+            _info: ClientInfo,
+            _capabilities: ClientCapabilities,
+        ) -> Result<InitializeResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn ping(&self) -> Result<(), Error> {
+            Err(Error::Transport(crate::transport::Error::ChannelClosed))
+        }
+
+        async fn list_resources(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<mcp_core::protocol::ListResourcesResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn read_resource(&self, _uri: &str) -> Result<ReadResourceResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_resource_templates(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<mcp_core::protocol::ListResourceTemplatesResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn subscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn unsubscribe_resource(&self, _uri: &str) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_tools(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<mcp_core::protocol::ListToolsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn call_tool(&self, _name: &str, _arguments: Value) -> Result<CallToolResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn list_prompts(
+            &self,
+            _next_cursor: Option<String>,
+        ) -> Result<mcp_core::protocol::ListPromptsResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn get_prompt(
+            &self,
+            _name: &str,
+            _arguments: Value,
+        ) -> Result<GetPromptResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn complete(
+            &self,
+            _reference: mcp_core::protocol::CompletionReference,
+            _argument: mcp_core::protocol::CompletionArgument,
+        ) -> Result<mcp_core::protocol::CompleteResult, Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn set_logging_level(
+            &self,
+            _level: mcp_core::protocol::LoggingLevel,
+        ) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+
+        async fn notify_roots_list_changed(&self) -> Result<(), Error> {
+            Err(Error::NotInitialized)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_manager_restarts_dead_servers() {
+        let mut manager = McpClientManager::new(client_info(), ClientCapabilities::default())
+            .with_ping_interval(Some(Duration::from_millis(10)));
+        let mut events = manager.subscribe();
+
+        let starts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let connector = EchoConnector {
+            starts: Arc::clone(&starts),
+        };
+        let mut connection = connector.connect().await.unwrap();
+        connection.client = Box::new(DeadClient);
+        manager
+            .add_server("echo", connection, Some(Arc::new(connector)))
+            .await;
+
+        // The dead client is replaced by a newly started server
+        wait_for_status(&mut events, |status| *status == ServerStatus::Restarting).await;
+        wait_for_status(&mut events, |status| *status == ServerStatus::Healthy).await;
+        assert_eq!(starts.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(manager.call_tool("echo__echo", json!({})).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_manager_reports_servers_it_cannot_restart() {
+        let mut manager = McpClientManager::new(client_info(), ClientCapabilities::default())
+            .with_ping_interval(Some(Duration::from_millis(10)));
+        let mut events = manager.subscribe();
+        let connection = EchoConnector {
+            starts: Default::default(),
+        }
+        .connect()
+        .await
+        .unwrap();
+        manager
+            .add_server(
+                "remote",
+                Connection {
+                    client: Box::new(DeadClient),
+                    ..connection
+                },
+                None,
+            )
+            .await;
+
+        wait_for_status(&mut events, |status| {
+            matches!(status, ServerStatus::Unresponsive(_))
+        })
+        .await;
+
+        // Once removed, the status is gone along with the server
+        manager.remove_server("remote").await;
+        assert!(manager.statuses.lock().await.is_empty());
+    }
+}
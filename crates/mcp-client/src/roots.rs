@@ -0,0 +1,95 @@
+use std::sync::{Arc, RwLock};
+
+use mcp_core::protocol::{ErrorData, ListRootsResult, Root, INTERNAL_ERROR, METHOD_NOT_FOUND};
+use tokio::sync::broadcast;
+
+use crate::transport::{ServerRequest, TransportHandle};
+
+/// The directories a client exposes to servers.
+///
+/// Clones share the same list, so a client can update its roots once and every
+/// server it serves them to through [`serve_roots`] sees the change.
+#[derive(Clone, Default)]
+pub struct Roots {
+    roots: Arc<RwLock<Vec<Root>>>,
+}
+
+impl Roots {
+    pub fn new(roots: Vec<Root>) -> Self {
+        Self {
+            roots: Arc::new(RwLock::new(roots)),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Root> {
+        self.roots.read().unwrap().clone()
+    }
+
+    /// Replace the roots, returns whether they changed
+    pub fn set(&self, roots: Vec<Root>) -> bool {
+        let mut current = self.roots.write().unwrap();
+        if *current == roots {
+            return false;
+        }
+        *current = roots;
+        true
+    }
+}
+
+/// Answer the `roots/list` requests the server sends through `handle` with the current roots
+pub fn serve_roots<T: TransportHandle>(handle: &T, roots: Roots) {
+    let mut requests = handle.subscribe_requests();
+    tokio::spawn(async move {
+        loop {
+            let request = match requests.recv().await {
+                Ok(request) => request,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "dropped server requests");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let result = answer(&request, &roots);
+            if let Err(e) = request.respond(result).await {
+                tracing::debug!(error = %e, "failed to answer server request");
+            }
+        }
+    });
+}
+
+fn answer(request: &ServerRequest, roots: &Roots) -> Result<serde_json::Value, ErrorData> {
+    if request.request.method != "roots/list" {
+        return Err(ErrorData {
+            code: METHOD_NOT_FOUND,
+            message: format!("Client does not handle '{}'", request.request.method),
+            data: None,
+        });
+    }
+
+    serde_json::to_value(ListRootsResult {
+        roots: roots.list(),
+    })
+    .map_err(|e| ErrorData {
+        code: INTERNAL_ERROR,
+        message: e.to_string(),
+        data: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_set_reports_changes() {
+        let roots = Roots::default();
+        let root = Root::from_path(Path::new("/project")).unwrap();
+
+        assert!(roots.set(vec![root.clone()]));
+        assert!(!roots.set(vec![root.clone()]));
+        assert_eq!(roots.list(), vec![root]);
+        assert!(roots.set(vec![]));
+    }
+}
@@ -0,0 +1,255 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use mcp_core::protocol::{JsonRpcMessage, JsonRpcNotification};
+use tokio::sync::{broadcast, mpsc, watch, Notify};
+
+use super::{
+    dispatch_server_request, notification_channel, request_channel, send_message, ConnectionState,
+    Error, PendingRequests, ServerRequest, Transport, TransportHandle, TransportMessage,
+};
+
+/// An `InProcessActor` drives a server running on a task of the same process,
+/// passing messages through channels without serializing them.
+pub struct InProcessActor {
+    receiver: mpsc::Receiver<TransportMessage>,
+    pending_requests: Arc<PendingRequests>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    requests: broadcast::Sender<ServerRequest>,
+    // handed to server requests to answer them, weak so dropping the handle still
+    // closes the channel and stops the actor
+    outgoing: mpsc::WeakSender<TransportMessage>,
+    state: watch::Sender<ConnectionState>,
+    shutdown: Arc<Notify>,
+    // messages to and from the server, dropping the sender stops the server
+    to_server: mpsc::Sender<JsonRpcMessage>,
+    from_server: mpsc::Receiver<JsonRpcMessage>,
+}
+
+impl InProcessActor {
+    pub async fn run(mut self) {
+        loop {
+            tokio::select! {
+                message = self.receiver.recv() => {
+                    let Some(transport_msg) = message else {
+                        break;
+                    };
+                    self.handle_outgoing_message(transport_msg).await;
+                }
+                message = self.from_server.recv() => {
+                    let Some(message) = message else {
+                        tracing::error!("In-process server stopped");
+                        break;
+                    };
+                    self.handle_incoming_message(message).await;
+                }
+                _ = self.shutdown.notified() => {
+                    tracing::debug!("Transport closed, stopping the server");
+                    break;
+                }
+            }
+        }
+
+        self.state.send_replace(ConnectionState::Closed);
+        self.pending_requests.fail_all(|| Error::NotConnected).await;
+    }
+
+    async fn handle_outgoing_message(&self, mut transport_msg: TransportMessage) {
+        tracing::debug!(message = ?transport_msg.message, "Sending outgoing message");
+
+        if let Some(response_tx) = transport_msg.response_tx.take() {
+            if let JsonRpcMessage::Request(request) = &transport_msg.message {
+                if let Some(id) = &request.id {
+                    self.pending_requests
+                        .insert(id.to_string(), response_tx)
+                        .await;
+                }
+            }
+        }
+
+        if self.to_server.send(transport_msg.message).await.is_err() {
+            // the server is gone, the actor stops once its channel is drained
+            tracing::error!("Error sending message to the in-process server");
+        }
+    }
+
+    async fn handle_incoming_message(&self, message: JsonRpcMessage) {
+        tracing::debug!(message = ?message, "Received incoming message");
+
+        match &message {
+            JsonRpcMessage::Response(response) => {
+                if let Some(id) = &response.id {
+                    self.pending_requests
+                        .respond(&id.to_string(), Ok(message))
+                        .await;
+                }
+            }
+            JsonRpcMessage::Error(error) => {
+                if let Some(id) = &error.id {
+                    self.pending_requests
+                        .respond(&id.to_string(), Ok(message))
+                        .await;
+                }
+            }
+            JsonRpcMessage::Notification(notification) => {
+                // no subscribers is not an error, the notification is dropped
+                let _ = self.notifications.send(notification.clone());
+            }
+            JsonRpcMessage::Request(request) => {
+                dispatch_server_request(request.clone(), &self.requests, &self.outgoing).await;
+            }
+            JsonRpcMessage::Nil => {}
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct InProcessTransportHandle {
+    sender: mpsc::Sender<TransportMessage>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    requests: broadcast::Sender<ServerRequest>,
+    state: watch::Receiver<ConnectionState>,
+}
+
+#[async_trait]
+impl TransportHandle for InProcessTransportHandle {
+    async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error> {
+        send_message(&self.sender, message).await
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.notifications.subscribe()
+    }
+
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest> {
+        self.requests.subscribe()
+    }
+
+    fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
+}
+
+/// The channels to send messages to a server in this process, and to receive its messages
+type ServerChannels = (mpsc::Sender<JsonRpcMessage>, mpsc::Receiver<JsonRpcMessage>);
+
+/// An `InProcessTransport` connects the client to a server running on a task of this
+/// process, e.g. a `mcp_server::Router` compiled into the host and started with
+/// `mcp_server::Server::spawn`.
+pub struct InProcessTransport {
+    // taken by `start`, a server can only serve one client
+    server: Mutex<Option<ServerChannels>>,
+    // stops the server started by this transport
+    shutdown: Arc<Notify>,
+}
+
+impl InProcessTransport {
+    /// Send messages to the server through `to_server` and receive its messages from
+    /// `from_server`
+    pub fn new(
+        to_server: mpsc::Sender<JsonRpcMessage>,
+        from_server: mpsc::Receiver<JsonRpcMessage>,
+    ) -> Self {
+        Self {
+            server: Mutex::new(Some((to_server, from_server))),
+            shutdown: Arc::new(Notify::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for InProcessTransport {
+    type Handle = InProcessTransportHandle;
+
+    async fn start(&self) -> Result<Self::Handle, Error> {
+        let (to_server, from_server) = self
+            .server
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(Error::AlreadyStarted)?;
+
+        let (message_tx, message_rx) = mpsc::channel(32);
+        let notifications = notification_channel();
+        let requests = request_channel();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
+
+        let actor = InProcessActor {
+            receiver: message_rx,
+            pending_requests: Arc::new(PendingRequests::new()),
+            notifications: notifications.clone(),
+            requests: requests.clone(),
+            outgoing: message_tx.downgrade(),
+            state: state_tx,
+            shutdown: Arc::clone(&self.shutdown),
+            to_server,
+            from_server,
+        };
+
+        tokio::spawn(actor.run());
+
+        Ok(InProcessTransportHandle {
+            sender: message_tx,
+            notifications,
+            requests,
+            state: state_rx,
+        })
+    }
+
+    async fn close(&self) -> Result<(), Error> {
+        self.shutdown.notify_waiters();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
+    use crate::service::McpService;
+    use mcp_core::Content;
+    use mcp_server::router::RouterService;
+    use mcp_server::testing::EchoRouter;
+    use mcp_server::{Peer, Server};
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_in_process_round_trip() {
+        let (peer, messages) = Peer::new();
+        let (to_server, from_server) = Server::new(RouterService(EchoRouter))
+            .with_peer(peer, messages)
+            .spawn();
+        let transport = InProcessTransport::new(to_server, from_server);
+
+        let handle = transport.start().await.unwrap();
+        assert!(matches!(
+            transport.start().await,
+            Err(Error::AlreadyStarted)
+        ));
+
+        let mut state = handle.connection_state();
+        let service = McpService::with_timeout(handle, Duration::from_secs(5));
+        let mut client = McpClient::new(service);
+
+        let info = ClientInfo {
+            name: "test".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        let init = client
+            .initialize(info, ClientCapabilities::default())
+            .await
+            .unwrap();
+        assert_eq!(init.server_info.name, "echo");
+
+        let result = client
+            .call_tool("echo", json!({"text": "hello"}))
+            .await
+            .unwrap();
+        assert_eq!(result.content, vec![Content::text(r#"{"text":"hello"}"#)]);
+
+        transport.close().await.unwrap();
+        state.changed().await.unwrap();
+        assert_eq!(*state.borrow(), ConnectionState::Closed);
+    }
+}
@@ -1,8 +1,12 @@
 use async_trait::async_trait;
-use mcp_core::protocol::JsonRpcMessage;
+use mcp_core::protocol::{
+    ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse,
+    METHOD_NOT_FOUND,
+};
+use serde_json::Value;
 use std::collections::HashMap;
 use thiserror::Error;
-use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio::sync::{broadcast, mpsc, oneshot, watch, RwLock};
 
 pub type BoxError = Box<dyn std::error::Error + Sync + Send>;
 /// A generic error type for transport operations.
@@ -17,6 +21,12 @@ pub enum Error {
     #[error("Channel closed")]
     ChannelClosed,
 
+    #[error("Transport was already started")]
+    AlreadyStarted,
+
+    #[error("Connection to the server was lost, retry once it is back")]
+    ConnectionLost,
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -31,6 +41,32 @@ pub enum Error {
 
     #[error("HTTP error: {status} - {message}")]
     HttpError { status: u16, message: String },
+
+    #[error("No response was recorded for '{0}' with these params")]
+    NotRecorded(String),
+}
+
+impl Error {
+    /// Whether sending the message again may succeed, e.g. once a dropped connection is back
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, Error::ConnectionLost | Error::NotConnected)
+    }
+}
+
+/// State of the connection between a transport and its server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// The connection dropped and the transport is connecting again
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Connected again. A new session on the server needs to be initialized again.
+    Reconnected {
+        new_session: bool,
+    },
+    /// The transport was closed, or gave up reconnecting
+    Closed,
 }
 
 /// A message that can be sent through the transport
@@ -51,13 +87,95 @@ pub trait Transport {
     /// Returns the transport handle for sending messages.
     async fn start(&self) -> Result<Self::Handle, Error>;
 
-    /// Close the transport and free any resources.
+    /// Close the transport and free any resources, stopping whatever was started.
     async fn close(&self) -> Result<(), Error>;
 }
 
 #[async_trait]
 pub trait TransportHandle: Send + Sync + Clone + 'static {
     async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error>;
+
+    /// Subscribe to notifications sent by the server, e.g. `notifications/message` logs.
+    /// Only notifications received after subscribing are delivered.
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification>;
+
+    /// Subscribe to requests sent by the server, e.g. `roots/list`, answered through
+    /// [`ServerRequest::respond`]. Requests received while nobody is subscribed are
+    /// refused with a method-not-found error.
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest>;
+
+    /// Watch the state of the connection to the server
+    fn connection_state(&self) -> watch::Receiver<ConnectionState>;
+}
+
+// Capacity of the notification channel, slow subscribers skip the oldest notifications
+const NOTIFICATION_CHANNEL_CAPACITY: usize = 256;
+
+/// Create the channel used by transports to publish server notifications
+pub fn notification_channel() -> broadcast::Sender<JsonRpcNotification> {
+    broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0
+}
+
+/// A request the server sent to the client
+#[derive(Clone)]
+pub struct ServerRequest {
+    pub request: JsonRpcRequest,
+    // weak so that pending server requests do not keep the transport alive
+    outgoing: mpsc::WeakSender<TransportMessage>,
+}
+
+impl ServerRequest {
+    /// Send the result, or the error, of the request back to the server
+    pub async fn respond(&self, result: Result<Value, ErrorData>) -> Result<(), Error> {
+        let message = match result {
+            Ok(result) => JsonRpcMessage::Response(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id: self.request.id,
+                result: Some(result),
+                error: None,
+            }),
+            Err(error) => JsonRpcMessage::Error(JsonRpcError {
+                jsonrpc: "2.0".to_string(),
+                id: self.request.id,
+                error,
+            }),
+        };
+
+        let outgoing = self.outgoing.upgrade().ok_or(Error::ChannelClosed)?;
+        outgoing
+            .send(TransportMessage {
+                message,
+                response_tx: None,
+            })
+            .await
+            .map_err(|_| Error::ChannelClosed)
+    }
+}
+
+/// Create the channel used by transports to publish server requests
+pub fn request_channel() -> broadcast::Sender<ServerRequest> {
+    broadcast::channel(NOTIFICATION_CHANNEL_CAPACITY).0
+}
+
+/// Hand a request the server sent to the subscribers of the handle, or refuse it
+/// if there are none
+pub async fn dispatch_server_request(
+    request: JsonRpcRequest,
+    requests: &broadcast::Sender<ServerRequest>,
+    outgoing: &mpsc::WeakSender<TransportMessage>,
+) {
+    let request = ServerRequest {
+        request,
+        outgoing: outgoing.clone(),
+    };
+    if let Err(broadcast::error::SendError(request)) = requests.send(request) {
+        let error = ErrorData {
+            code: METHOD_NOT_FOUND,
+            message: format!("Client does not handle '{}'", request.request.method),
+            data: None,
+        };
+        let _ = request.respond(Err(error)).await;
+    }
 }
 
 // Helper function that contains the common send implementation
@@ -118,10 +236,23 @@ impl PendingRequests {
     pub async fn clear(&self) {
         self.requests.write().await.clear();
     }
+
+    /// Fail every pending request with an error from `error`
+    pub async fn fail_all(&self, error: impl Fn() -> Error) {
+        for (_, tx) in self.requests.write().await.drain() {
+            let _ = tx.send(Err(error()));
+        }
+    }
 }
 
 pub mod stdio;
 pub use stdio::StdioTransport;
 
 pub mod sse;
-pub use sse::SseTransport;
+pub use sse::{HeaderSource, SseTransport};
+
+pub mod in_process;
+pub use in_process::InProcessTransport;
+
+pub mod replay;
+pub use replay::{Fixture, RecordingTransport, ReplayTransport};
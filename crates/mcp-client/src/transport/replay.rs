@@ -0,0 +1,446 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+
+use async_trait::async_trait;
+use mcp_core::protocol::{
+    ErrorData, JsonRpcError, JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::{broadcast, watch};
+
+use super::{
+    notification_channel, request_channel, ConnectionState, Error, ServerRequest, Transport,
+    TransportHandle,
+};
+
+/// A request the client sent and what the server answered, as stored in a [`Fixture`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorData>,
+    /// Notifications the server sent after answering, until the next answer
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<JsonRpcNotification>,
+}
+
+impl RecordedExchange {
+    fn matches(&self, request: &JsonRpcRequest) -> bool {
+        self.method == request.method && self.params == request.params
+    }
+
+    fn response(&self, id: Option<u64>) -> JsonRpcMessage {
+        match &self.error {
+            Some(error) => JsonRpcMessage::Error(JsonRpcError {
+                jsonrpc: "2.0".to_string(),
+                id,
+                error: error.clone(),
+            }),
+            None => JsonRpcMessage::Response(JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id,
+                result: self.result.clone(),
+                error: None,
+            }),
+        }
+    }
+}
+
+/// The JSON-RPC exchange with a server, written by [`RecordingTransport`] and served
+/// back by [`ReplayTransport`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub exchanges: Vec<RecordedExchange>,
+}
+
+impl Fixture {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let contents = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+/// The exchange recorded by a handle, kept in memory until it is saved to `path`
+struct Recorder {
+    path: PathBuf,
+    fixture: Mutex<Fixture>,
+}
+
+impl Recorder {
+    fn record(&self, update: impl FnOnce(&mut Fixture)) {
+        update(&mut self.fixture.lock().unwrap());
+    }
+
+    async fn save(&self) -> Result<(), Error> {
+        let contents = serde_json::to_string_pretty(&*self.fixture.lock().unwrap())?;
+        tokio::fs::write(&self.path, contents).await?;
+        Ok(())
+    }
+}
+
+/// A `RecordingTransport` starts another transport and records the exchange with its
+/// server, written to a fixture file on close to be served back by [`ReplayTransport`] later
+pub struct RecordingTransport<T> {
+    inner: T,
+    path: PathBuf,
+    // the recorder of the handle started last
+    recorder: Mutex<Option<Arc<Recorder>>>,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    pub fn new<P: Into<PathBuf>>(inner: T, path: P) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            recorder: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl<T> Transport for RecordingTransport<T>
+where
+    T: Transport + Send + Sync,
+{
+    type Handle = RecordingTransportHandle<T::Handle>;
+
+    async fn start(&self) -> Result<Self::Handle, Error> {
+        let handle = self.inner.start().await?;
+        let handle = RecordingTransportHandle::new(handle, self.path.clone());
+        *self.recorder.lock().unwrap() = Some(Arc::clone(&handle.recorder));
+        Ok(handle)
+    }
+
+    /// Write the recorded exchange to the fixture file, then close the inner transport
+    async fn close(&self) -> Result<(), Error> {
+        let recorder = self.recorder.lock().unwrap().take();
+        if let Some(recorder) = recorder {
+            recorder.save().await?;
+        }
+        self.inner.close().await
+    }
+}
+
+/// Passes messages through to another handle, recording the requests and their answers.
+/// Requests that fail in the transport itself are not recorded.
+#[derive(Clone)]
+pub struct RecordingTransportHandle<H> {
+    inner: H,
+    recorder: Arc<Recorder>,
+}
+
+impl<H: TransportHandle> RecordingTransportHandle<H> {
+    /// Record what goes through `inner`, to be written to `path` by [`Self::save`].
+    /// Must be called within a tokio runtime, which follows the server notifications.
+    pub fn new<P: Into<PathBuf>>(inner: H, path: P) -> Self {
+        let recorder = Arc::new(Recorder {
+            path: path.into(),
+            fixture: Mutex::new(Fixture::default()),
+        });
+        record_notifications(inner.subscribe(), Arc::downgrade(&recorder));
+        Self { inner, recorder }
+    }
+
+    /// Write what was recorded so far to the fixture file, replacing what it held before
+    pub async fn save(&self) -> Result<(), Error> {
+        self.recorder.save().await
+    }
+}
+
+/// Attach the notifications of the server to the last answered request
+fn record_notifications(
+    mut notifications: broadcast::Receiver<JsonRpcNotification>,
+    recorder: Weak<Recorder>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let notification = match notifications.recv().await {
+                Ok(notification) => notification,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let Some(recorder) = recorder.upgrade() else {
+                break;
+            };
+            recorder.record(|fixture| {
+                if let Some(exchange) = fixture.exchanges.last_mut() {
+                    exchange.notifications.push(notification);
+                }
+            });
+        }
+    });
+}
+
+#[async_trait]
+impl<H: TransportHandle> TransportHandle for RecordingTransportHandle<H> {
+    async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error> {
+        let JsonRpcMessage::Request(request) = &message else {
+            return self.inner.send(message).await;
+        };
+        let (method, params) = (request.method.clone(), request.params.clone());
+
+        let response = self.inner.send(message).await?;
+        let (result, error) = match &response {
+            JsonRpcMessage::Response(response) => (response.result.clone(), response.error.clone()),
+            JsonRpcMessage::Error(error) => (None, Some(error.error.clone())),
+            _ => return Ok(response),
+        };
+        self.recorder.record(|fixture| {
+            fixture.exchanges.push(RecordedExchange {
+                method,
+                params,
+                result,
+                error,
+                notifications: Vec::new(),
+            })
+        });
+        Ok(response)
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.inner.subscribe()
+    }
+
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest> {
+        self.inner.subscribe_requests()
+    }
+
+    fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.inner.connection_state()
+    }
+}
+
+/// A `ReplayTransport` serves the client from a [`Fixture`] rather than a server.
+///
+/// Requests are answered by the recorded exchange with the same method and params, in the
+/// order they were recorded, repeating the last one once all were served. Requests that
+/// were never recorded fail with [`Error::NotRecorded`].
+pub struct ReplayTransport {
+    fixture: Fixture,
+}
+
+impl ReplayTransport {
+    pub fn new(fixture: Fixture) -> Self {
+        Self { fixture }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(Self::new(Fixture::load(path)?))
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    type Handle = ReplayTransportHandle;
+
+    async fn start(&self) -> Result<Self::Handle, Error> {
+        Ok(ReplayTransportHandle {
+            exchanges: Arc::new(Mutex::new(
+                self.fixture
+                    .exchanges
+                    .iter()
+                    .cloned()
+                    .map(|exchange| (exchange, false))
+                    .collect(),
+            )),
+            notifications: notification_channel(),
+            requests: request_channel(),
+            state: Arc::new(watch::channel(ConnectionState::Connected).0),
+        })
+    }
+
+    async fn close(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct ReplayTransportHandle {
+    // the recorded exchanges, and whether each was served already
+    exchanges: Arc<Mutex<Vec<(RecordedExchange, bool)>>>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    // nothing is ever requested, but subscribers expect a live channel
+    requests: broadcast::Sender<ServerRequest>,
+    state: Arc<watch::Sender<ConnectionState>>,
+}
+
+impl ReplayTransportHandle {
+    fn replay(&self, request: &JsonRpcRequest) -> Option<RecordedExchange> {
+        let mut exchanges = self.exchanges.lock().unwrap();
+        let next = exchanges
+            .iter_mut()
+            .find(|(exchange, served)| !served && exchange.matches(request));
+        if let Some((exchange, served)) = next {
+            *served = true;
+            return Some(exchange.clone());
+        }
+        exchanges
+            .iter()
+            .rev()
+            .find(|(exchange, _)| exchange.matches(request))
+            .map(|(exchange, _)| exchange.clone())
+    }
+}
+
+#[async_trait]
+impl TransportHandle for ReplayTransportHandle {
+    async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error> {
+        match message {
+            JsonRpcMessage::Request(request) => {
+                let exchange = self
+                    .replay(&request)
+                    .ok_or_else(|| Error::NotRecorded(request.method.clone()))?;
+                for notification in &exchange.notifications {
+                    let _ = self.notifications.send(notification.clone());
+                }
+                Ok(exchange.response(request.id))
+            }
+            JsonRpcMessage::Notification(_) => Ok(JsonRpcMessage::Nil),
+            _ => Err(Error::UnsupportedMessage),
+        }
+    }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.notifications.subscribe()
+    }
+
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest> {
+        self.requests.subscribe()
+    }
+
+    fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientCapabilities, ClientInfo, McpClient, McpClientTrait};
+    use crate::service::McpService;
+    use crate::transport::InProcessTransport;
+    use mcp_server::router::RouterService;
+    use mcp_server::testing::EchoRouter;
+    use mcp_server::Server;
+    use serde_json::json;
+
+    async fn client<T: Transport>(transport: &T) -> McpClient<McpService<T::Handle>> {
+        let handle = transport.start().await.unwrap();
+        let mut client = McpClient::new(McpService::new(handle));
+        let info = ClientInfo {
+            name: "test".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        client
+            .initialize(info, ClientCapabilities::default())
+            .await
+            .unwrap();
+        client
+    }
+
+    #[tokio::test]
+    async fn test_replay_serves_the_recorded_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("echo.json");
+
+        let (to_server, from_server) = Server::new(RouterService(EchoRouter)).spawn();
+        let recording =
+            RecordingTransport::new(InProcessTransport::new(to_server, from_server), &path);
+        let recorded = client(&recording).await;
+        let first = recorded.call_tool("echo", json!({"n": 1})).await.unwrap();
+        let second = recorded.call_tool("echo", json!({"n": 2})).await.unwrap();
+        assert_ne!(first.content, second.content);
+        let tools = recorded.list_tools(None).await.unwrap();
+
+        // Nothing is written until the transport closes
+        assert!(!path.exists());
+        recording.close().await.unwrap();
+        let fixture = Fixture::load(&path).unwrap();
+        let methods: Vec<&str> = fixture
+            .exchanges
+            .iter()
+            .map(|exchange| exchange.method.as_str())
+            .collect();
+        assert_eq!(
+            methods,
+            vec!["initialize", "tools/call", "tools/call", "tools/list"]
+        );
+
+        // Requests are matched by their params, not the order they were made in
+        let replayed = client(&ReplayTransport::from_file(&path).unwrap()).await;
+        assert_eq!(
+            replayed
+                .call_tool("echo", json!({"n": 2}))
+                .await
+                .unwrap()
+                .content,
+            second.content
+        );
+        assert_eq!(
+            replayed
+                .call_tool("echo", json!({"n": 1}))
+                .await
+                .unwrap()
+                .content,
+            first.content
+        );
+        assert_eq!(replayed.list_tools(None).await.unwrap(), tools);
+        assert_eq!(replayed.list_tools(None).await.unwrap(), tools);
+
+        let error = replayed
+            .call_tool("echo", json!({"n": 3}))
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("No response was recorded"),
+            "{}",
+            error
+        );
+    }
+
+    #[tokio::test]
+    async fn test_replay_sends_the_recorded_notifications() {
+        let fixture: Fixture = serde_json::from_value(json!({
+            "exchanges": [{
+                "method": "ping",
+                "params": {},
+                "result": {},
+                "notifications": [{
+                    "jsonrpc": "2.0",
+                    "method": "notifications/tools/list_changed"
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let handle = ReplayTransport::new(fixture).start().await.unwrap();
+        let mut notifications = handle.subscribe();
+        let response = handle
+            .send(JsonRpcMessage::Request(JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: Some(7),
+                method: "ping".to_string(),
+                params: Some(json!({})),
+            }))
+            .await
+            .unwrap();
+        assert!(matches!(
+            response,
+            JsonRpcMessage::Response(JsonRpcResponse { id: Some(7), .. })
+        ));
+
+        let notification = notifications.recv().await.unwrap();
+        assert_eq!(notification.method, "notifications/tools/list_changed");
+    }
+}
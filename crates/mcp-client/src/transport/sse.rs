@@ -1,154 +1,302 @@
 use crate::transport::{Error, PendingRequests, TransportMessage};
 use async_trait::async_trait;
-use eventsource_client::{Client, SSE};
+use eventsource_client::{Client, ReconnectOptions, SSE};
 use futures::TryStreamExt;
-use mcp_core::protocol::{JsonRpcMessage, JsonRpcRequest};
-use reqwest::Client as HttpClient;
+use mcp_core::protocol::{JsonRpcMessage, JsonRpcNotification, JsonRpcRequest};
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::{Client as HttpClient, StatusCode};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{broadcast, mpsc, watch, Notify, RwLock};
 use tokio::time::{timeout, Duration};
 use tracing::warn;
 use url::Url;
 
-use super::{send_message, Transport, TransportHandle};
+use super::{
+    dispatch_server_request, notification_channel, request_channel, send_message, ConnectionState,
+    ServerRequest, Transport, TransportHandle,
+};
 
 // Timeout for the endpoint discovery
 const ENDPOINT_TIMEOUT_SECS: u64 = 5;
 
+// Backoff between attempts to reconnect to a dropped stream, doubling from the initial delay
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_millis(500);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// How long to wait before the given attempt to reconnect, counting from 1
+fn reconnect_delay(attempt: u32) -> Duration {
+    RECONNECT_INITIAL_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(RECONNECT_MAX_DELAY)
+}
+
+/// Gives the headers of a transport whose credentials expire, e.g. OAuth access tokens
+#[async_trait]
+pub trait HeaderSource: Send + Sync {
+    /// The headers to send from now on
+    async fn headers(&self) -> Result<HashMap<String, String>, Error>;
+}
+
+/// The headers sent to the server, fetched again from their source before reconnecting
+/// and when the server rejects them
+struct Headers {
+    current: RwLock<HashMap<String, String>>,
+    source: Option<Arc<dyn HeaderSource>>,
+}
+
+impl Headers {
+    async fn get(&self) -> HashMap<String, String> {
+        self.current.read().await.clone()
+    }
+
+    /// Fetch the headers again, telling whether there are new ones to try
+    async fn refresh(&self) -> bool {
+        let Some(source) = &self.source else {
+            return false;
+        };
+        match source.headers().await {
+            Ok(headers) => {
+                *self.current.write().await = headers;
+                true
+            }
+            Err(e) => {
+                warn!("Failed to refresh the SSE headers: {}", e);
+                false
+            }
+        }
+    }
+}
+
 /// The SSE-based actor that continuously:
-/// - Reads incoming events from the SSE stream.
+/// - Reads incoming events from the SSE stream, reconnecting when it drops.
 /// - Sends outgoing messages via HTTP POST (once the post endpoint is known).
 pub struct SseActor {
     /// Receives messages (requests/notifications) from the handle
     receiver: mpsc::Receiver<TransportMessage>,
-    /// Map of request-id -> oneshot sender
-    pending_requests: Arc<PendingRequests>,
-    /// Base SSE URL
-    sse_url: String,
     /// For sending HTTP POST requests
     http_client: HttpClient,
+    /// Reads the SSE stream
+    reader: SseReader,
+    /// Stops the actor when the transport is closed
+    shutdown: Arc<Notify>,
+}
+
+/// Reads the SSE stream, and connects to it again when it drops
+struct SseReader {
+    /// Base SSE URL
+    sse_url: String,
+    /// HTTP headers sent when connecting to the stream and with every POST, e.g. credentials
+    headers: Arc<Headers>,
+    /// Map of request-id -> oneshot sender
+    pending_requests: Arc<PendingRequests>,
+    /// Publishes server notifications to subscribers of the handle
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    /// Publishes server requests to subscribers of the handle
+    requests: broadcast::Sender<ServerRequest>,
+    /// Handed to server requests to answer them (weak so dropping the handle stops the actor)
+    outgoing: mpsc::WeakSender<TransportMessage>,
     /// The discovered endpoint for POST requests (once "endpoint" SSE event arrives)
     post_endpoint: Arc<RwLock<Option<String>>>,
+    /// Publishes the state of the connection to the handle
+    state: watch::Sender<ConnectionState>,
 }
 
 impl SseActor {
-    pub fn new(
-        receiver: mpsc::Receiver<TransportMessage>,
-        pending_requests: Arc<PendingRequests>,
-        sse_url: String,
-        post_endpoint: Arc<RwLock<Option<String>>>,
-    ) -> Self {
-        Self {
-            receiver,
-            pending_requests,
-            sse_url,
-            post_endpoint,
-            http_client: HttpClient::new(),
-        }
-    }
-
-    /// The main entry point for the actor. Spawns two concurrent loops:
-    /// 1) handle_incoming_messages (SSE events)
+    /// The main entry point for the actor. Runs two concurrent loops until either ends or the
+    /// transport is closed:
+    /// 1) SseReader::run (SSE events)
     /// 2) handle_outgoing_messages (sending messages via POST)
     pub async fn run(self) {
-        tokio::join!(
-            Self::handle_incoming_messages(
-                self.sse_url.clone(),
-                Arc::clone(&self.pending_requests),
-                Arc::clone(&self.post_endpoint)
-            ),
-            Self::handle_outgoing_messages(
+        let reader = self.reader;
+        let error = tokio::select! {
+            result = reader.run() => result.err(),
+            _ = Self::handle_outgoing_messages(
                 self.receiver,
-                self.http_client.clone(),
-                Arc::clone(&self.post_endpoint),
-                Arc::clone(&self.pending_requests),
-            )
-        );
+                self.http_client,
+                Arc::clone(&reader.headers),
+                Arc::clone(&reader.post_endpoint),
+                Arc::clone(&reader.pending_requests),
+            ) => None,
+            _ = self.shutdown.notified() => {
+                tracing::debug!("SSE transport closed");
+                None
+            }
+        };
+
+        match error {
+            Some(error) => {
+                warn!("SSE transport failed: {}", error);
+                let reason = error.to_string();
+                reader
+                    .pending_requests
+                    .fail_all(|| Error::SseConnection(reason.clone()))
+                    .await;
+            }
+            None => {
+                reader
+                    .pending_requests
+                    .fail_all(|| Error::NotConnected)
+                    .await
+            }
+        }
+        reader.state.send_replace(ConnectionState::Closed);
     }
+}
 
+impl SseReader {
     /// Continuously reads SSE events from `sse_url`.
     /// - If an `endpoint` event is received, store it in `post_endpoint`.
     /// - If a `message` event is received, parse it as `JsonRpcMessage`
-    ///   and respond to pending requests if it's a `Response`.
-    async fn handle_incoming_messages(
-        sse_url: String,
-        pending_requests: Arc<PendingRequests>,
-        post_endpoint: Arc<RwLock<Option<String>>>,
-    ) {
-        let client = match eventsource_client::ClientBuilder::for_url(&sse_url) {
-            Ok(builder) => builder.build(),
-            Err(e) => {
-                pending_requests.clear().await;
-                warn!("Failed to connect SSE client: {}", e);
-                return;
+    ///   and respond to pending requests if it's a `Response`, or publish it if it's a
+    ///   `Notification` or `Request`.
+    ///
+    /// When the stream drops, the pending requests fail with a retriable error and we connect
+    /// again with backoff, asking the server to resume after the last event we saw. Fails
+    /// on what reconnecting cannot fix, like a URL that does not parse.
+    async fn run(&self) -> Result<(), Error> {
+        let mut last_event_id: Option<String> = None;
+        // the endpoint of the session, which tells whether reconnecting resumed it
+        let mut session_endpoint: Option<String> = None;
+        let mut attempt = 0;
+
+        loop {
+            // Credentials may have expired while we were disconnected
+            if attempt > 0 {
+                self.headers.refresh().await;
             }
-        };
-        let mut stream = client.stream();
-
-        // First, wait for the "endpoint" event
-        while let Ok(Some(event)) = stream.try_next().await {
-            match event {
-                SSE::Event(e) if e.event_type == "endpoint" => {
-                    // SSE server uses the "endpoint" event to tell us the POST URL
-                    let base_url = Url::parse(&sse_url).expect("Invalid base URL");
-                    let post_url = base_url
-                        .join(&e.data)
-                        .expect("Failed to resolve endpoint URL");
-
-                    tracing::debug!("Discovered SSE POST endpoint: {}", post_url);
-                    *post_endpoint.write().await = Some(post_url.to_string());
-                    break;
+            let builder = self.headers.get().await.into_iter().fold(
+                eventsource_client::ClientBuilder::for_url(&self.sse_url),
+                |builder, (name, value)| builder.and_then(|builder| builder.header(&name, &value)),
+            );
+            // a bad URL or header does not get better by retrying
+            let builder = builder.map_err(|e| {
+                Error::SseConnection(format!("Failed to connect SSE client: {}", e))
+            })?;
+            let builder = match &last_event_id {
+                Some(id) => builder.last_event_id(id.clone()),
+                None => builder,
+            };
+            // We reconnect ourselves, to discover the endpoint and fail pending requests
+            let client = builder
+                .reconnect(ReconnectOptions::reconnect(false).build())
+                .build();
+            let mut stream = client.stream();
+
+            // First, wait for the "endpoint" event
+            let mut connected = false;
+            while let Ok(Some(event)) = stream.try_next().await {
+                match event {
+                    SSE::Event(e) if e.event_type == "endpoint" => {
+                        // SSE server uses the "endpoint" event to tell us the POST URL
+                        let post_url = Url::parse(&self.sse_url)
+                            .and_then(|base_url| base_url.join(&e.data))
+                            .map_err(|err| {
+                                Error::SseConnection(format!(
+                                    "Failed to resolve the endpoint URL {}: {}",
+                                    e.data, err
+                                ))
+                            })?
+                            .to_string();
+
+                        tracing::debug!("Discovered SSE POST endpoint: {}", post_url);
+                        if attempt > 0 {
+                            let new_session = session_endpoint.as_ref() != Some(&post_url);
+                            self.state
+                                .send_replace(ConnectionState::Reconnected { new_session });
+                        }
+                        session_endpoint = Some(post_url.clone());
+                        *self.post_endpoint.write().await = Some(post_url);
+                        connected = true;
+                        attempt = 0;
+                        break;
+                    }
+                    _ => continue,
                 }
-                _ => continue,
             }
-        }
 
-        // Now handle subsequent events
-        while let Ok(Some(event)) = stream.try_next().await {
-            match event {
-                SSE::Event(e) if e.event_type == "message" => {
-                    // Attempt to parse the SSE data as a JsonRpcMessage
-                    match serde_json::from_str::<JsonRpcMessage>(&e.data) {
-                        Ok(message) => {
-                            match &message {
-                                JsonRpcMessage::Response(response) => {
-                                    if let Some(id) = &response.id {
-                                        pending_requests
-                                            .respond(&id.to_string(), Ok(message))
-                                            .await;
-                                    }
-                                }
-                                JsonRpcMessage::Error(error) => {
-                                    if let Some(id) = &error.id {
-                                        pending_requests
-                                            .respond(&id.to_string(), Ok(message))
-                                            .await;
-                                    }
-                                }
-                                _ => {} // TODO: Handle other variants (Request, etc.)
+            // Now handle subsequent events
+            if connected {
+                while let Ok(Some(event)) = stream.try_next().await {
+                    match event {
+                        SSE::Event(e) if e.event_type == "message" => {
+                            if e.id.is_some() {
+                                last_event_id = e.id;
                             }
+                            self.handle_message(&e.data).await;
                         }
-                        Err(err) => {
-                            warn!("Failed to parse SSE message: {err}");
-                        }
+                        _ => { /* ignore other events */ }
                     }
                 }
-                _ => { /* ignore other events */ }
             }
+
+            // SSE stream ended or errored; the responses to pending requests will not arrive
+            *self.post_endpoint.write().await = None;
+            self.pending_requests
+                .fail_all(|| Error::ConnectionLost)
+                .await;
+
+            attempt += 1;
+            if attempt > MAX_RECONNECT_ATTEMPTS {
+                tracing::error!(
+                    "SSE stream ended; giving up after {} attempts to reconnect.",
+                    MAX_RECONNECT_ATTEMPTS
+                );
+                return Ok(());
+            }
+            let delay = reconnect_delay(attempt);
+            warn!(
+                "SSE stream ended or encountered an error; reconnecting in {:?}.",
+                delay
+            );
+            self.state
+                .send_replace(ConnectionState::Reconnecting { attempt });
+            tokio::time::sleep(delay).await;
         }
+    }
 
-        // SSE stream ended or errored; signal any pending requests
-        tracing::error!("SSE stream ended or encountered an error; clearing pending requests.");
-        pending_requests.clear().await;
+    /// Parse the SSE data as a `JsonRpcMessage` and route it
+    async fn handle_message(&self, data: &str) {
+        match serde_json::from_str::<JsonRpcMessage>(data) {
+            Ok(message) => match &message {
+                JsonRpcMessage::Response(response) => {
+                    if let Some(id) = &response.id {
+                        self.pending_requests
+                            .respond(&id.to_string(), Ok(message))
+                            .await;
+                    }
+                }
+                JsonRpcMessage::Error(error) => {
+                    if let Some(id) = &error.id {
+                        self.pending_requests
+                            .respond(&id.to_string(), Ok(message))
+                            .await;
+                    }
+                }
+                JsonRpcMessage::Notification(notification) => {
+                    let _ = self.notifications.send(notification.clone());
+                }
+                JsonRpcMessage::Request(request) => {
+                    dispatch_server_request(request.clone(), &self.requests, &self.outgoing).await;
+                }
+                JsonRpcMessage::Nil => {}
+            },
+            Err(err) => {
+                warn!("Failed to parse SSE message: {err}");
+            }
+        }
     }
+}
 
+impl SseActor {
     /// Continuously receives messages from the `mpsc::Receiver`.
     /// - If it's a request, store the oneshot in `pending_requests`.
     /// - POST the message to the discovered endpoint (once known).
     async fn handle_outgoing_messages(
         mut receiver: mpsc::Receiver<TransportMessage>,
         http_client: HttpClient,
+        headers: Arc<Headers>,
         post_endpoint: Arc<RwLock<Option<String>>>,
         pending_requests: Arc<PendingRequests>,
     ) {
@@ -183,14 +331,24 @@ impl SseActor {
                 }
             }
 
-            // Perform the HTTP POST
-            match http_client
-                .post(&post_url)
-                .header("Content-Type", "application/json")
-                .body(message_str)
-                .send()
-                .await
+            // Perform the HTTP POST, once more with new headers if the server rejects ours
+            let post = |headers: HashMap<String, String>| {
+                headers
+                    .iter()
+                    .fold(http_client.post(&post_url), |request, (name, value)| {
+                        request.header(name, value)
+                    })
+                    .header("Content-Type", "application/json")
+                    .body(message_str.clone())
+                    .send()
+            };
+            let mut response = post(headers.get().await).await;
+            if matches!(&response, Ok(resp) if resp.status() == StatusCode::UNAUTHORIZED)
+                && headers.refresh().await
             {
+                response = post(headers.get().await).await;
+            }
+            match response {
                 Ok(resp) => {
                     if !resp.status().is_success() {
                         let err = Error::HttpError {
@@ -218,6 +376,9 @@ impl SseActor {
 #[derive(Clone)]
 pub struct SseTransportHandle {
     sender: mpsc::Sender<TransportMessage>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    requests: broadcast::Sender<ServerRequest>,
+    state: watch::Receiver<ConnectionState>,
 }
 
 #[async_trait::async_trait]
@@ -225,23 +386,48 @@ impl TransportHandle for SseTransportHandle {
     async fn send(&self, message: JsonRpcMessage) -> Result<JsonRpcMessage, Error> {
         send_message(&self.sender, message).await
     }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.notifications.subscribe()
+    }
+
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest> {
+        self.requests.subscribe()
+    }
+
+    fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
 }
 
 #[derive(Clone)]
 pub struct SseTransport {
     sse_url: String,
-    env: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    header_source: Option<Arc<dyn HeaderSource>>,
+    // stops the actors started by this transport
+    shutdown: Arc<Notify>,
 }
 
 /// The SSE transport spawns an `SseActor` on `start()`.
 impl SseTransport {
-    pub fn new<S: Into<String>>(sse_url: S, env: HashMap<String, String>) -> Self {
+    /// Connect to `sse_url`, sending `headers` (e.g. `Authorization`) with every request
+    pub fn new<S: Into<String>>(sse_url: S, headers: HashMap<String, String>) -> Self {
         Self {
             sse_url: sse_url.into(),
-            env,
+            headers,
+            header_source: None,
+            shutdown: Arc::new(Notify::new()),
         }
     }
 
+    /// Fetch the headers from `source` again when the server rejects them as unauthorized
+    /// and before reconnecting, e.g. to refresh an access token
+    pub fn with_header_source(mut self, source: Arc<dyn HeaderSource>) -> Self {
+        self.header_source = Some(source);
+        self
+    }
+
     /// Waits for the endpoint to be set, up to 10 attempts.
     async fn wait_for_endpoint(
         post_endpoint: Arc<RwLock<Option<String>>>,
@@ -267,9 +453,13 @@ impl Transport for SseTransport {
     type Handle = SseTransportHandle;
 
     async fn start(&self) -> Result<Self::Handle, Error> {
-        // Set environment variables
-        for (key, value) in &self.env {
-            std::env::set_var(key, value);
+        // Reject bad headers here, rather than failing every request later
+        for (name, value) in &self.headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| Error::SseConnection(format!("Invalid header name {name}: {e}")))?;
+            HeaderValue::from_str(value).map_err(|e| {
+                Error::SseConnection(format!("Invalid value for header {name}: {e}"))
+            })?;
         }
 
         // Create a channel for outgoing TransportMessages
@@ -277,14 +467,29 @@ impl Transport for SseTransport {
 
         let post_endpoint: Arc<RwLock<Option<String>>> = Arc::new(RwLock::new(None));
         let post_endpoint_clone = Arc::clone(&post_endpoint);
+        let notifications = notification_channel();
+        let requests = request_channel();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
 
         // Build the actor
-        let actor = SseActor::new(
-            rx,
-            Arc::new(PendingRequests::new()),
-            self.sse_url.clone(),
-            post_endpoint,
-        );
+        let actor = SseActor {
+            receiver: rx,
+            http_client: HttpClient::new(),
+            reader: SseReader {
+                sse_url: self.sse_url.clone(),
+                headers: Arc::new(Headers {
+                    current: RwLock::new(self.headers.clone()),
+                    source: self.header_source.clone(),
+                }),
+                pending_requests: Arc::new(PendingRequests::new()),
+                notifications: notifications.clone(),
+                requests: requests.clone(),
+                outgoing: tx.downgrade(),
+                post_endpoint,
+                state: state_tx,
+            },
+            shutdown: Arc::clone(&self.shutdown),
+        };
 
         // Spawn the actor task
         tokio::spawn(actor.run());
@@ -296,14 +501,61 @@ impl Transport for SseTransport {
         )
         .await
         {
-            Ok(_) => Ok(SseTransportHandle { sender: tx }),
+            Ok(_) => Ok(SseTransportHandle {
+                sender: tx,
+                notifications,
+                requests,
+                state: state_rx,
+            }),
             Err(e) => Err(Error::SseConnection(e.to_string())),
         }
     }
 
     async fn close(&self) -> Result<(), Error> {
-        // For SSE, you might close the stream or send a shutdown signal to the actor.
-        // Here, we do nothing special.
+        self.shutdown.notify_waiters();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backs_off() {
+        assert_eq!(reconnect_delay(1), Duration::from_millis(500));
+        assert_eq!(reconnect_delay(2), Duration::from_secs(1));
+        assert_eq!(reconnect_delay(4), Duration::from_secs(4));
+        assert_eq!(reconnect_delay(MAX_RECONNECT_ATTEMPTS), RECONNECT_MAX_DELAY);
+    }
+
+    struct FreshToken;
+
+    #[async_trait]
+    impl HeaderSource for FreshToken {
+        async fn headers(&self) -> Result<HashMap<String, String>, Error> {
+            Ok(HashMap::from([(
+                "Authorization".to_string(),
+                "Bearer fresh".to_string(),
+            )]))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headers_refresh_from_their_source() {
+        let stale = HashMap::from([("Authorization".to_string(), "Bearer stale".to_string())]);
+        let fixed = Headers {
+            current: RwLock::new(stale.clone()),
+            source: None,
+        };
+        assert!(!fixed.refresh().await);
+        assert_eq!(fixed.get().await, stale);
+
+        let refreshed = Headers {
+            current: RwLock::new(stale),
+            source: Some(Arc::new(FreshToken)),
+        };
+        assert!(refreshed.refresh().await);
+        assert_eq!(refreshed.get().await["Authorization"], "Bearer fresh");
+    }
+}
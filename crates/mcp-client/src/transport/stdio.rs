@@ -3,11 +3,14 @@ use std::sync::Arc;
 use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
 
 use async_trait::async_trait;
-use mcp_core::protocol::JsonRpcMessage;
+use mcp_core::protocol::{JsonRpcMessage, JsonRpcNotification};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
-use tokio::sync::{mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, Notify};
 
-use super::{send_message, Error, PendingRequests, Transport, TransportHandle, TransportMessage};
+use super::{
+    dispatch_server_request, notification_channel, request_channel, send_message, ConnectionState,
+    Error, PendingRequests, ServerRequest, Transport, TransportHandle, TransportMessage,
+};
 
 /// A `StdioTransport` uses a child process's stdin/stdout as a communication channel.
 ///
@@ -15,6 +18,13 @@ use super::{send_message, Error, PendingRequests, Transport, TransportHandle, Tr
 pub struct StdioActor {
     receiver: mpsc::Receiver<TransportMessage>,
     pending_requests: Arc<PendingRequests>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    requests: broadcast::Sender<ServerRequest>,
+    // handed to server requests to answer them, weak so dropping the handle still
+    // closes the channel and stops the actor
+    outgoing: mpsc::WeakSender<TransportMessage>,
+    state: watch::Sender<ConnectionState>,
+    shutdown: Arc<Notify>,
     _process: Child, // we store the process to keep it alive
     error_sender: mpsc::Sender<Error>,
     stdin: ChildStdin,
@@ -26,7 +36,13 @@ impl StdioActor {
     pub async fn run(mut self) {
         use tokio::pin;
 
-        let incoming = Self::handle_incoming_messages(self.stdout, self.pending_requests.clone());
+        let incoming = Self::handle_incoming_messages(
+            self.stdout,
+            self.pending_requests.clone(),
+            self.notifications.clone(),
+            self.requests.clone(),
+            self.outgoing.clone(),
+        );
         let outgoing = Self::handle_outgoing_messages(
             self.receiver,
             self.stdin,
@@ -49,8 +65,17 @@ impl StdioActor {
             status = self._process.wait() => {
                 tracing::debug!("Process exited with status: {:?}", status);
             }
+            _ = self.shutdown.notified() => {
+                tracing::debug!("Transport closed, stopping the process");
+                let _ = self._process.kill().await;
+                self.state.send_replace(ConnectionState::Closed);
+                self.pending_requests.fail_all(|| Error::NotConnected).await;
+                return;
+            }
         }
 
+        self.state.send_replace(ConnectionState::Closed);
+
         // Then always try to read stderr before cleaning up
         let mut stderr_buffer = Vec::new();
         if let Ok(bytes) = self.stderr.read_to_end(&mut stderr_buffer).await {
@@ -71,7 +96,13 @@ impl StdioActor {
         self.pending_requests.clear().await;
     }
 
-    async fn handle_incoming_messages(stdout: ChildStdout, pending_requests: Arc<PendingRequests>) {
+    async fn handle_incoming_messages(
+        stdout: ChildStdout,
+        pending_requests: Arc<PendingRequests>,
+        notifications: broadcast::Sender<JsonRpcNotification>,
+        requests: broadcast::Sender<ServerRequest>,
+        outgoing: mpsc::WeakSender<TransportMessage>,
+    ) {
         let mut reader = BufReader::new(stdout);
         let mut line = String::new();
         loop {
@@ -98,7 +129,15 @@ impl StdioActor {
                                     pending_requests.respond(&id.to_string(), Ok(message)).await;
                                 }
                             }
-                            _ => {} // TODO: Handle other variants (Request, etc.)
+                            JsonRpcMessage::Notification(notification) => {
+                                // no subscribers is not an error, the notification is dropped
+                                let _ = notifications.send(notification.clone());
+                            }
+                            JsonRpcMessage::Request(request) => {
+                                dispatch_server_request(request.clone(), &requests, &outgoing)
+                                    .await;
+                            }
+                            JsonRpcMessage::Nil => {}
                         }
                     }
                     line.clear();
@@ -159,6 +198,9 @@ impl StdioActor {
 pub struct StdioTransportHandle {
     sender: mpsc::Sender<TransportMessage>,
     error_receiver: Arc<Mutex<mpsc::Receiver<Error>>>,
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    requests: broadcast::Sender<ServerRequest>,
+    state: watch::Receiver<ConnectionState>,
 }
 
 #[async_trait::async_trait]
@@ -169,6 +211,18 @@ impl TransportHandle for StdioTransportHandle {
         self.check_for_errors().await?;
         result
     }
+
+    fn subscribe(&self) -> broadcast::Receiver<JsonRpcNotification> {
+        self.notifications.subscribe()
+    }
+
+    fn subscribe_requests(&self) -> broadcast::Receiver<ServerRequest> {
+        self.requests.subscribe()
+    }
+
+    fn connection_state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.clone()
+    }
 }
 
 impl StdioTransportHandle {
@@ -188,6 +242,8 @@ pub struct StdioTransport {
     command: String,
     args: Vec<String>,
     env: HashMap<String, String>,
+    // stops the processes started by this transport
+    shutdown: Arc<Notify>,
 }
 
 impl StdioTransport {
@@ -200,6 +256,7 @@ impl StdioTransport {
             command: command.into(),
             args,
             env,
+            shutdown: Arc::new(Notify::new()),
         }
     }
 
@@ -252,10 +309,18 @@ impl Transport for StdioTransport {
         let (process, stdin, stdout, stderr) = self.spawn_process().await?;
         let (message_tx, message_rx) = mpsc::channel(32);
         let (error_tx, error_rx) = mpsc::channel(1);
+        let notifications = notification_channel();
+        let requests = request_channel();
+        let (state_tx, state_rx) = watch::channel(ConnectionState::Connected);
 
         let actor = StdioActor {
             receiver: message_rx,
             pending_requests: Arc::new(PendingRequests::new()),
+            notifications: notifications.clone(),
+            requests: requests.clone(),
+            outgoing: message_tx.downgrade(),
+            state: state_tx,
+            shutdown: Arc::clone(&self.shutdown),
             _process: process,
             error_sender: error_tx,
             stdin,
@@ -268,11 +333,15 @@ impl Transport for StdioTransport {
         let handle = StdioTransportHandle {
             sender: message_tx,
             error_receiver: Arc::new(Mutex::new(error_rx)),
+            notifications,
+            requests,
+            state: state_rx,
         };
         Ok(handle)
     }
 
     async fn close(&self) -> Result<(), Error> {
+        self.shutdown.notify_waiters();
         Ok(())
     }
 }
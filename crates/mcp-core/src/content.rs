@@ -50,6 +50,30 @@ pub struct ImageContent {
     pub annotations: Option<Annotations>,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioContent {
+    /// The base64 encoded audio
+    pub data: String,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+}
+
+/// A link to a resource the client can read, rather than the resource itself
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLink {
+    pub uri: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EmbeddedResource {
@@ -72,7 +96,10 @@ impl EmbeddedResource {
 pub enum Content {
     Text(TextContent),
     Image(ImageContent),
+    Audio(AudioContent),
     Resource(EmbeddedResource),
+    #[serde(rename = "resource_link")]
+    ResourceLink(ResourceLink),
 }
 
 impl Content {
@@ -91,6 +118,24 @@ impl Content {
         })
     }
 
+    pub fn audio<S: Into<String>, T: Into<String>>(data: S, mime_type: T) -> Self {
+        Content::Audio(AudioContent {
+            data: data.into(),
+            mime_type: mime_type.into(),
+            annotations: None,
+        })
+    }
+
+    pub fn resource_link<S: Into<String>, T: Into<String>>(uri: S, name: T) -> Self {
+        Content::ResourceLink(ResourceLink {
+            uri: uri.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+            annotations: None,
+        })
+    }
+
     pub fn resource(resource: ResourceContents) -> Self {
         Content::Resource(EmbeddedResource {
             resource,
@@ -125,13 +170,45 @@ impl Content {
         }
     }
 
-    /// Set the audience for the content
-    pub fn with_audience(mut self, audience: Vec<Role>) -> Self {
-        let annotations = match &mut self {
+    /// Get the audio content if this is an AudioContent variant
+    pub fn as_audio(&self) -> Option<(&str, &str)> {
+        match self {
+            Content::Audio(audio) => Some((&audio.data, &audio.mime_type)),
+            _ => None,
+        }
+    }
+
+    /// Get the resource link if this is a ResourceLink variant
+    pub fn as_resource_link(&self) -> Option<&ResourceLink> {
+        match self {
+            Content::ResourceLink(link) => Some(link),
+            _ => None,
+        }
+    }
+
+    fn annotations_mut(&mut self) -> &mut Option<Annotations> {
+        match self {
             Content::Text(text) => &mut text.annotations,
             Content::Image(image) => &mut image.annotations,
+            Content::Audio(audio) => &mut audio.annotations,
             Content::Resource(resource) => &mut resource.annotations,
-        };
+            Content::ResourceLink(link) => &mut link.annotations,
+        }
+    }
+
+    fn annotations(&self) -> Option<&Annotations> {
+        match self {
+            Content::Text(text) => text.annotations.as_ref(),
+            Content::Image(image) => image.annotations.as_ref(),
+            Content::Audio(audio) => audio.annotations.as_ref(),
+            Content::Resource(resource) => resource.annotations.as_ref(),
+            Content::ResourceLink(link) => link.annotations.as_ref(),
+        }
+    }
+
+    /// Set the audience for the content
+    pub fn with_audience(mut self, audience: Vec<Role>) -> Self {
+        let annotations = self.annotations_mut();
         *annotations = Some(match annotations.take() {
             Some(mut a) => {
                 a.audience = Some(audience);
@@ -153,11 +230,7 @@ impl Content {
         if !(0.0..=1.0).contains(&priority) {
             panic!("Priority must be between 0.0 and 1.0");
         }
-        let annotations = match &mut self {
-            Content::Text(text) => &mut text.annotations,
-            Content::Image(image) => &mut image.annotations,
-            Content::Resource(resource) => &mut resource.annotations,
-        };
+        let annotations = self.annotations_mut();
         *annotations = Some(match annotations.take() {
             Some(mut a) => {
                 a.priority = Some(priority);
@@ -174,30 +247,24 @@ impl Content {
 
     /// Get the audience if set
     pub fn audience(&self) -> Option<&Vec<Role>> {
-        match self {
-            Content::Text(text) => text.annotations.as_ref().and_then(|a| a.audience.as_ref()),
-            Content::Image(image) => image.annotations.as_ref().and_then(|a| a.audience.as_ref()),
-            Content::Resource(resource) => resource
-                .annotations
-                .as_ref()
-                .and_then(|a| a.audience.as_ref()),
-        }
+        self.annotations().and_then(|a| a.audience.as_ref())
     }
 
     /// Get the priority if set
     pub fn priority(&self) -> Option<f32> {
-        match self {
-            Content::Text(text) => text.annotations.as_ref().and_then(|a| a.priority),
-            Content::Image(image) => image.annotations.as_ref().and_then(|a| a.priority),
-            Content::Resource(resource) => resource.annotations.as_ref().and_then(|a| a.priority),
-        }
+        self.annotations().and_then(|a| a.priority)
     }
 
     pub fn unannotated(&self) -> Self {
         match self {
             Content::Text(text) => Content::text(text.text.clone()),
             Content::Image(image) => Content::image(image.data.clone(), image.mime_type.clone()),
+            Content::Audio(audio) => Content::audio(audio.data.clone(), audio.mime_type.clone()),
             Content::Resource(resource) => Content::resource(resource.resource.clone()),
+            Content::ResourceLink(link) => Content::ResourceLink(ResourceLink {
+                annotations: None,
+                ..link.clone()
+            }),
         }
     }
 }
@@ -307,4 +374,31 @@ mod tests {
         assert_eq!(content.audience(), Some(&vec![Role::User]));
         assert_eq!(content.priority(), None);
     }
+
+    #[test]
+    fn test_content_audio_and_resource_link() {
+        let content = Content::audio("data", "audio/wav").with_priority(0.5);
+        assert_eq!(content.as_audio(), Some(("data", "audio/wav")));
+        assert_eq!(content.priority(), Some(0.5));
+        assert_eq!(
+            serde_json::to_value(&content).unwrap(),
+            serde_json::json!({
+                "type": "audio",
+                "data": "data",
+                "mimeType": "audio/wav",
+                "annotations": {"priority": 0.5}
+            })
+        );
+
+        let content: Content = serde_json::from_value(serde_json::json!({
+            "type": "resource_link",
+            "uri": "file:///notes.md",
+            "name": "notes.md",
+            "mimeType": "text/markdown"
+        }))
+        .unwrap();
+        let link = content.as_resource_link().unwrap();
+        assert_eq!(link.uri, "file:///notes.md");
+        assert_eq!(link.mime_type.as_deref(), Some("text/markdown"));
+    }
 }
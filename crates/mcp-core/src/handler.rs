@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use thiserror::Error;
 
+use crate::tool::ToolAnnotations;
+
 #[non_exhaustive]
 #[derive(Error, Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum ToolError {
@@ -37,6 +39,14 @@ pub enum PromptError {
     NotFound(String),
 }
 
+#[derive(Error, Debug)]
+pub enum CompletionError {
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(String),
+    #[error("Internal error: {0}")]
+    InternalError(String),
+}
+
 /// Trait for implementing MCP tools
 #[async_trait]
 pub trait ToolHandler: Send + Sync + 'static {
@@ -49,6 +59,21 @@ pub trait ToolHandler: Send + Sync + 'static {
     /// JSON schema describing the tool's parameters
     fn schema(&self) -> Value;
 
+    /// JSON schema describing the tool's parameters, or why it could not be generated
+    fn try_schema(&self) -> ToolResult<Value> {
+        Ok(self.schema())
+    }
+
+    /// Hints about how the tool behaves, none by default
+    fn annotations(&self) -> Option<ToolAnnotations> {
+        None
+    }
+
+    /// JSON schema describing the structured results of the tool, none by default
+    fn output_schema(&self) -> Option<Value> {
+        None
+    }
+
     /// Execute the tool with the given parameters
     async fn call(&self, params: Value) -> ToolResult<Value>;
 }
@@ -66,8 +91,20 @@ pub trait ResourceTemplateHandler: Send + Sync + 'static {
     async fn get(&self, params: Value) -> ToolResult<String>;
 }
 
-/// Helper function to generate JSON schema for a type
+/// Helper function to generate JSON schema for a type.
+/// Nested types, such as enums, are inlined rather than referenced from `definitions`.
 pub fn generate_schema<T: JsonSchema>() -> ToolResult<Value> {
-    let schema = schemars::schema_for!(T);
+    let settings = schemars::gen::SchemaSettings::draft07().with(|s| {
+        s.inline_subschemas = true;
+    });
+    let schema = settings.into_generator().into_root_schema_for::<T>();
     serde_json::to_value(schema).map_err(|e| ToolError::SchemaError(e.to_string()))
 }
+
+/// Generate the output schema of a tool returning `T`.
+/// Structured content has to be an object, so other types have no output schema.
+pub fn generate_output_schema<T: JsonSchema>() -> Option<Value> {
+    generate_schema::<T>()
+        .ok()
+        .filter(|schema| schema.get("type").and_then(Value::as_str) == Some("object"))
+}
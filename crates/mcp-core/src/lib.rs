@@ -1,12 +1,12 @@
 pub mod content;
-pub use content::{Annotations, Content, ImageContent, TextContent};
+pub use content::{Annotations, AudioContent, Content, ImageContent, ResourceLink, TextContent};
 pub mod handler;
 pub mod role;
 pub use role::Role;
 pub mod tool;
-pub use tool::{Tool, ToolCall};
+pub use tool::{Tool, ToolAnnotations, ToolCall};
 pub mod resource;
-pub use resource::{Resource, ResourceContents};
+pub use resource::{Resource, ResourceContents, ResourceTemplate};
 pub mod protocol;
 pub use handler::{ToolError, ToolResult};
 pub mod prompt;
@@ -4,10 +4,13 @@ use crate::{
     prompt::{Prompt, PromptMessage},
     resource::Resource,
     resource::ResourceContents,
+    resource::ResourceTemplate,
     tool::Tool,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::path::{Path, PathBuf};
+use url::Url;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct JsonRpcRequest {
@@ -147,6 +150,12 @@ pub struct ErrorData {
     pub data: Option<Value>,
 }
 
+/// The MCP protocol version clients request and servers answer with by default
+pub const LATEST_PROTOCOL_VERSION: &str = "2024-11-05";
+
+/// The MCP protocol versions we speak, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &[LATEST_PROTOCOL_VERSION];
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeResult {
@@ -171,6 +180,10 @@ pub struct ServerCapabilities {
     pub resources: Option<ResourcesCapability>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapability>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
     // Add other capabilities as needed
 }
 
@@ -193,6 +206,46 @@ pub struct ToolsCapability {
     pub list_changed: Option<bool>,
 }
 
+/// Servers that emit `notifications/message` declare this (empty) capability
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct LoggingCapability {}
+
+/// Servers that suggest argument values through `completion/complete` declare this (empty) capability
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CompletionsCapability {}
+
+/// Severity of a log message, ordered from least to most severe (RFC 5424 syslog levels)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+/// Parameters of a `logging/setLevel` request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SetLevelParams {
+    /// The minimum level the client wants to receive
+    pub level: LoggingLevel,
+}
+
+/// Parameters of a `notifications/message` notification sent from server to client
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LoggingMessageNotification {
+    pub level: LoggingLevel,
+    /// An optional name of the logger issuing this message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// The data to be logged, such as a string message or an object
+    pub data: Value,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListResourcesResult {
@@ -206,6 +259,21 @@ pub struct ReadResourceResult {
     pub contents: Vec<ResourceContents>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourceTemplatesResult {
+    pub resource_templates: Vec<ResourceTemplate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+/// Parameters of a `notifications/resources/updated` notification, sent for subscribed resources
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ResourceUpdatedNotification {
+    /// The URI of the resource that changed, which may be a sub-resource of the subscribed one
+    pub uri: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ListToolsResult {
@@ -220,6 +288,9 @@ pub struct CallToolResult {
     pub content: Vec<Content>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_error: Option<bool>,
+    /// The result as a JSON object matching the tool's output schema, if it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -237,11 +308,130 @@ pub struct GetPromptResult {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EmptyResult {}
 
+/// What the argument being completed belongs to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum CompletionReference {
+    #[serde(rename = "ref/prompt")]
+    Prompt { name: String },
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// The URI template the argument appears in
+        uri: String,
+    },
+}
+
+/// The argument being completed and what the user typed so far
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CompletionArgument {
+    pub name: String,
+    pub value: String,
+}
+
+/// Parameters of a `completion/complete` request
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CompleteParams {
+    #[serde(rename = "ref")]
+    pub reference: CompletionReference,
+    pub argument: CompletionArgument,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    /// Suggested values, at most 100
+    pub values: Vec<String>,
+    /// The number of suggestions available, which may exceed the number of values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct CompleteResult {
+    pub completion: Completion,
+}
+
+/// Clients that expose filesystem roots to servers through `roots/list` declare this capability
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RootsCapability {
+    /// Whether the client sends `notifications/roots/list_changed` when its roots change
+    pub list_changed: Option<bool>,
+}
+
+/// A directory the client allows the server to operate in
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Root {
+    /// A `file://` URI
+    pub uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Root {
+    /// Create a root for a local directory, returns None if the path is not absolute
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let uri = Url::from_directory_path(path).ok()?;
+        Some(Self {
+            uri: uri.to_string(),
+            name: path.file_name().map(|n| n.to_string_lossy().into_owned()),
+        })
+    }
+
+    /// The local directory of the root, if it is a `file://` URI
+    pub fn path(&self) -> Option<PathBuf> {
+        Url::parse(&self.uri).ok()?.to_file_path().ok()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ListRootsResult {
+    pub roots: Vec<Root>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn test_complete_params() {
+        let params: CompleteParams = serde_json::from_value(json!({
+            "ref": {"type": "ref/prompt", "name": "review"},
+            "argument": {"name": "file", "value": "src/ma"}
+        }))
+        .unwrap();
+
+        assert_eq!(
+            params.reference,
+            CompletionReference::Prompt {
+                name: "review".to_string()
+            }
+        );
+        assert_eq!(params.argument.value, "src/ma");
+
+        let resource = CompletionReference::Resource {
+            uri: "file:///{path}".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(resource).unwrap(),
+            json!({"type": "ref/resource", "uri": "file:///{path}"})
+        );
+    }
+
+    #[test]
+    fn test_root_from_path() {
+        let dir = std::env::temp_dir().join("project");
+        let root = Root::from_path(&dir).unwrap();
+        assert!(root.uri.starts_with("file://"));
+        assert_eq!(root.name.as_deref(), Some("project"));
+        assert_eq!(root.path().unwrap(), dir);
+
+        assert!(Root::from_path(Path::new("relative/dir")).is_none());
+    }
+
     #[test]
     fn test_notification_conversion() {
         let raw = JsonRpcRaw {
@@ -286,4 +476,25 @@ mod tests {
             _ => panic!("Expected Request"),
         }
     }
+
+    #[test]
+    fn test_logging_message_notification() {
+        let params: LoggingMessageNotification = serde_json::from_value(json!({
+            "level": "warning",
+            "logger": "database",
+            "data": {"error": "Connection failed"}
+        }))
+        .unwrap();
+
+        assert_eq!(params.level, LoggingLevel::Warning);
+        assert_eq!(params.logger.as_deref(), Some("database"));
+        assert!(params.level > LoggingLevel::Info);
+        assert!(params.level < LoggingLevel::Error);
+
+        let set_level = serde_json::to_value(SetLevelParams {
+            level: LoggingLevel::Debug,
+        })
+        .unwrap();
+        assert_eq!(set_level, json!({"level": "debug"}));
+    }
 }
@@ -43,6 +43,50 @@ pub enum ResourceContents {
     },
 }
 
+/// A parameterized resource the server can read, described by an RFC 6570 URI template
+/// (e.g. "file:///logs/{date}.log"). Clients expand the template and read the resulting URI.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// URI template used to construct resource URIs
+    pub uri_template: String,
+    /// Name for the type of resource this template refers to
+    pub name: String,
+    /// Optional description of what this template is for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of all resources matching this template, if they share one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<Annotations>,
+}
+
+impl ResourceTemplate {
+    /// Creates a new ResourceTemplate with the given URI template and name
+    pub fn new<S: Into<String>, N: Into<String>>(uri_template: S, name: N) -> Self {
+        Self {
+            uri_template: uri_template.into(),
+            name: name.into(),
+            description: None,
+            mime_type: None,
+            annotations: None,
+        }
+    }
+
+    /// Sets the description of the template
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the MIME type of resources matching the template
+    pub fn with_mime_type<S: Into<String>>(mut self, mime_type: S) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+}
+
 fn default_mime_type() -> String {
     "text".to_string()
 }
@@ -252,6 +296,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_resource_template_serialization() {
+        let template = ResourceTemplate::new("file:///logs/{date}.log", "daily log")
+            .with_description("Application logs for a given day")
+            .with_mime_type("text/plain");
+
+        let value = serde_json::to_value(&template).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "uriTemplate": "file:///logs/{date}.log",
+                "name": "daily log",
+                "description": "Application logs for a given day",
+                "mimeType": "text/plain"
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<ResourceTemplate>(value).unwrap(),
+            template
+        );
+    }
+
     #[test]
     fn test_invalid_uri() {
         let result = Resource::new("not-a-uri", None, None);
@@ -1,7 +1,12 @@
 /// Tools represent a routine that a server can execute
 /// Tool calls represent requests from the client to execute one
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+
+use crate::content::Content;
+use crate::handler::{ToolError, ToolResult};
 
 /// A tool that can be used by a model.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -13,6 +18,12 @@ pub struct Tool {
     pub description: String,
     /// A JSON Schema object defining the expected parameters for the tool
     pub input_schema: Value,
+    /// Hints about how the tool behaves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotations: Option<ToolAnnotations>,
+    /// A JSON Schema object describing the structured content of the tool's results
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<Value>,
 }
 
 impl Tool {
@@ -26,6 +37,202 @@ impl Tool {
             name: name.into(),
             description: description.into(),
             input_schema,
+            annotations: None,
+            output_schema: None,
+        }
+    }
+
+    /// Declare that the tool returns structured content matching the given schema
+    pub fn with_output_schema(mut self, output_schema: Value) -> Self {
+        self.output_schema = Some(output_schema);
+        self
+    }
+
+    /// Describe how the tool behaves with the given annotations
+    pub fn with_annotations(mut self, annotations: ToolAnnotations) -> Self {
+        self.annotations = Some(annotations);
+        self
+    }
+
+    /// Whether the tool is annotated as not modifying its environment
+    pub fn is_read_only(&self) -> bool {
+        self.annotations
+            .as_ref()
+            .is_some_and(|annotations| annotations.read_only_hint)
+    }
+
+    /// Check the arguments of a call against the input schema, see [`validate_arguments`]
+    pub fn validate_arguments(&self, arguments: Value) -> ToolResult<Value> {
+        validate_arguments(&self.input_schema, arguments)
+    }
+}
+
+/// What a tool returns: content for the model and, for tools with an output schema, the
+/// same result as structured content
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ToolOutput {
+    pub content: Vec<Content>,
+    pub structured_content: Option<Value>,
+}
+
+impl ToolOutput {
+    /// A structured result, also serialized as text content for clients that do not read
+    /// structured content
+    pub fn structured(value: Value) -> Self {
+        Self {
+            content: vec![Content::text(value.to_string())],
+            structured_content: Some(value),
+        }
+    }
+}
+
+impl From<Vec<Content>> for ToolOutput {
+    fn from(content: Vec<Content>) -> Self {
+        Self {
+            content,
+            structured_content: None,
+        }
+    }
+}
+
+/// Check tool call arguments against the tool's input schema, filling in the defaults
+/// of missing properties first.
+///
+/// Compiles the schema for this one call, use an [`ArgumentValidator`] to check many.
+pub fn validate_arguments(schema: &Value, arguments: Value) -> ToolResult<Value> {
+    ArgumentValidator::new(schema.clone()).validate(arguments)
+}
+
+/// The input schema of a tool compiled once, to check the arguments of many calls. Compiled
+/// from an output schema it checks structured content instead, see
+/// [`ArgumentValidator::validate_output`].
+#[derive(Clone)]
+pub struct ArgumentValidator {
+    schema: Value,
+    // None if the schema can not be compiled
+    validator: Option<Arc<jsonschema::Validator>>,
+}
+
+impl ArgumentValidator {
+    pub fn new(schema: Value) -> Self {
+        let validator = jsonschema::validator_for(&schema).ok().map(Arc::new);
+        Self { schema, validator }
+    }
+
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// Check the arguments of a call, filling in the defaults of missing properties first.
+    ///
+    /// Returns the arguments with defaults applied, or an `InvalidParameters` error that lists
+    /// every violation so the caller can fix them all at once. A schema that can not be
+    /// compiled is left to the tool itself, the arguments are passed through unchecked.
+    pub fn validate(&self, arguments: Value) -> ToolResult<Value> {
+        // Models tend to send null rather than an empty object for tools without parameters
+        let mut arguments = match arguments {
+            Value::Null if self.schema.get("type").and_then(Value::as_str) == Some("object") => {
+                Value::Object(Map::new())
+            }
+            arguments => arguments,
+        };
+        apply_defaults(&self.schema, &mut arguments);
+
+        let violations = self.violations(&arguments);
+        if violations.is_empty() {
+            Ok(arguments)
+        } else {
+            Err(ToolError::InvalidParameters(format!(
+                "the arguments do not match the tool's input schema:\n{}",
+                violations.join("\n")
+            )))
+        }
+    }
+
+    /// Check the structured content of a result against the output schema, failing with an
+    /// `ExecutionError` that lists every violation. Defaults are not filled in.
+    pub fn validate_output(&self, structured_content: &Value) -> ToolResult<()> {
+        let violations = self.violations(structured_content);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ToolError::ExecutionError(format!(
+                "the structured content does not match the tool's output schema:\n{}",
+                violations.join("\n")
+            )))
+        }
+    }
+
+    /// Where and how `value` violates the schema, nothing if it could not be compiled
+    fn violations(&self, value: &Value) -> Vec<String> {
+        let Some(validator) = &self.validator else {
+            return Vec::new();
+        };
+        validator
+            .iter_errors(value)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                let path = if path.is_empty() { "/" } else { &path };
+                format!("- {}: {}", path, error)
+            })
+            .collect()
+    }
+}
+
+/// Insert the schema defaults of properties missing from objects, recursing into nested objects.
+/// Schemas give optional properties a `null` default, which is not applied.
+fn apply_defaults(schema: &Value, value: &mut Value) {
+    let (Some(properties), Some(object)) = (
+        schema.get("properties").and_then(Value::as_object),
+        value.as_object_mut(),
+    ) else {
+        return;
+    };
+
+    for (name, property) in properties {
+        match object.get_mut(name) {
+            Some(nested) => apply_defaults(property, nested),
+            None => {
+                if let Some(default) = property.get("default").filter(|d| !d.is_null()) {
+                    object.insert(name.clone(), default.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Hints about how a tool behaves, e.g. to decide whether calling it needs confirmation.
+/// They are not guaranteed, clients should only rely on them for servers they trust.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolAnnotations {
+    /// The tool does not modify its environment
+    #[serde(default)]
+    pub read_only_hint: bool,
+    /// The tool may perform destructive updates, only meaningful when it is not read-only
+    #[serde(default = "default_true")]
+    pub destructive_hint: bool,
+    /// Calling the tool again with the same arguments has no additional effect, only
+    /// meaningful when it is not read-only
+    #[serde(default)]
+    pub idempotent_hint: bool,
+    /// The tool may interact with external entities, e.g. the web, rather than a closed domain
+    #[serde(default = "default_true")]
+    pub open_world_hint: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for ToolAnnotations {
+    /// The defaults of the MCP specification, which assume the least about the tool
+    fn default() -> Self {
+        ToolAnnotations {
+            read_only_hint: false,
+            destructive_hint: true,
+            idempotent_hint: false,
+            open_world_hint: true,
         }
     }
 }
@@ -49,3 +256,118 @@ impl ToolCall {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_annotations_default_to_the_specification() {
+        let tool: Tool = serde_json::from_value(json!({
+            "name": "read",
+            "description": "Read a file",
+            "inputSchema": {"type": "object"},
+            "annotations": {"readOnlyHint": true}
+        }))
+        .unwrap();
+        assert!(tool.is_read_only());
+        assert_eq!(
+            tool.annotations,
+            Some(ToolAnnotations {
+                read_only_hint: true,
+                ..Default::default()
+            })
+        );
+
+        let tool: Tool = serde_json::from_value(json!({
+            "name": "write",
+            "description": "Write a file",
+            "inputSchema": {"type": "object"}
+        }))
+        .unwrap();
+        assert!(!tool.is_read_only());
+        assert!(!serde_json::to_value(&tool)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .contains_key("annotations"));
+    }
+
+    #[test]
+    fn test_validate_arguments() {
+        let tool = Tool::new(
+            "search",
+            "Search files",
+            json!({
+                "type": "object",
+                "required": ["query", "limit"],
+                "properties": {
+                    "query": {"type": "string"},
+                    "limit": {"type": "integer", "minimum": 1},
+                    "options": {
+                        "type": "object",
+                        "properties": {
+                            "case_sensitive": {"type": "boolean", "default": false}
+                        }
+                    },
+                    "hidden": {"type": "boolean", "default": false}
+                }
+            }),
+        );
+
+        // Defaults are applied, including in nested objects
+        let arguments = tool
+            .validate_arguments(json!({"query": "todo", "limit": 5, "options": {}}))
+            .unwrap();
+        assert_eq!(
+            arguments,
+            json!({
+                "query": "todo",
+                "limit": 5,
+                "options": {"case_sensitive": false},
+                "hidden": false
+            })
+        );
+
+        // Every violation is reported
+        let Err(ToolError::InvalidParameters(message)) =
+            tool.validate_arguments(json!({"query": 42, "limit": 0}))
+        else {
+            panic!("Expected invalid parameters");
+        };
+        assert!(message.contains("- /query:"), "{}", message);
+        assert!(message.contains("- /limit:"), "{}", message);
+
+        let Err(ToolError::InvalidParameters(message)) = tool.validate_arguments(Value::Null)
+        else {
+            panic!("Expected invalid parameters");
+        };
+        assert!(
+            message.contains("\"query\" is a required property"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_null_defaults_are_not_applied() {
+        // As in the schema of the developer extension's screen_capture
+        let validator = ArgumentValidator::new(json!({
+            "type": "object",
+            "properties": {
+                "display": {"type": "integer", "default": 0},
+                "window_title": {"type": "string", "default": null}
+            }
+        }));
+        assert_eq!(
+            validator.validate(json!({})).unwrap(),
+            json!({"display": 0})
+        );
+        assert_eq!(
+            validator.validate(Value::Null).unwrap(),
+            json!({"display": 0})
+        );
+        assert!(validator.validate(json!({"window_title": 1})).is_err());
+    }
+}
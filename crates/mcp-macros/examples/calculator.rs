@@ -24,30 +24,35 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[derive(serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+/// Perform basic arithmetic operations
 #[tool(
     name = "calculator",
-    description = "Perform basic arithmetic operations",
     params(
         x = "First number in the calculation",
         y = "Second number in the calculation",
-        operation = "The operation to perform (add, subtract, multiply, divide)"
+        operation = "The operation to perform"
     )
 )]
-async fn calculator(x: i32, y: i32, operation: String) -> Result<i32, ToolError> {
-    match operation.as_str() {
-        "add" => Ok(x + y),
-        "subtract" => Ok(x - y),
-        "multiply" => Ok(x * y),
-        "divide" => {
+fn calculator(x: i32, y: i32, operation: Operation) -> Result<i32, ToolError> {
+    match operation {
+        Operation::Add => Ok(x + y),
+        Operation::Subtract => Ok(x - y),
+        Operation::Multiply => Ok(x * y),
+        Operation::Divide => {
             if y == 0 {
                 Err(ToolError::ExecutionError("Division by zero".into()))
             } else {
                 Ok(x / y)
             }
         }
-        _ => Err(ToolError::InvalidParameters(format!(
-            "Unknown operation: {}",
-            operation
-        ))),
     }
 }
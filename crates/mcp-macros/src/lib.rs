@@ -1,39 +1,87 @@
 use convert_case::{Case, Casing};
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use std::collections::HashMap;
 use syn::{
-    parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Expr, ExprLit,
-    FnArg, ItemFn, Lit, Meta, Pat, PatType, Token,
+    parse::Parse, parse::ParseStream, parse_macro_input, punctuated::Punctuated, Attribute, Expr,
+    ExprLit, FnArg, GenericArgument, Ident, ImplItem, ItemFn, ItemImpl, Lit, Meta, Pat, PatType,
+    PathArguments, ReturnType, Signature, Token, Type,
 };
 
+#[derive(Default)]
 struct MacroArgs {
     name: Option<String>,
-    description: Option<String>,
+    description: Option<Expr>,
+    schema: Option<Expr>,
+    output_schema: Option<Expr>,
     param_descriptions: HashMap<String, String>,
+    // the hints of `mcp_core::tool::ToolAnnotations` given, e.g. `read_only_hint = true`
+    annotations: Vec<(Ident, bool)>,
 }
 
+const ANNOTATIONS: [&str; 4] = [
+    "read_only_hint",
+    "destructive_hint",
+    "idempotent_hint",
+    "open_world_hint",
+];
+
 impl Parse for MacroArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut name = None;
         let mut description = None;
+        let mut schema = None;
+        let mut output_schema = None;
         let mut param_descriptions = HashMap::new();
+        let mut annotations = Vec::new();
 
         let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(input)?;
 
         for meta in meta_list {
             match meta {
                 Meta::NameValue(nv) => {
-                    let ident = nv.path.get_ident().unwrap().to_string();
-                    if let Expr::Lit(ExprLit {
-                        lit: Lit::Str(lit_str),
-                        ..
-                    }) = nv.value
-                    {
-                        match ident.as_str() {
-                            "name" => name = Some(lit_str.value()),
-                            "description" => description = Some(lit_str.value()),
-                            _ => {}
+                    let ident = argument_name(&nv.path)?;
+                    match (ident.as_str(), nv.value) {
+                        (
+                            "name",
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Str(lit_str),
+                                ..
+                            }),
+                        ) => name = Some(lit_str.value()),
+                        // descriptions and schemas may be computed, e.g. to differ per OS
+                        ("description", value) => description = Some(value),
+                        ("schema", value) => schema = Some(value),
+                        ("output_schema", value) => output_schema = Some(value),
+                        (
+                            hint,
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Bool(lit_bool),
+                                ..
+                            }),
+                        ) if ANNOTATIONS.contains(&hint) => {
+                            annotations.push((format_ident!("{}", hint), lit_bool.value()))
+                        }
+                        ("name", value) => {
+                            return Err(syn::Error::new_spanned(value, "expected a string literal"))
+                        }
+                        (hint, value) if ANNOTATIONS.contains(&hint) => {
+                            return Err(syn::Error::new_spanned(
+                                value,
+                                "expected `true` or `false`",
+                            ))
+                        }
+                        _ => {
+                            return Err(syn::Error::new_spanned(
+                                &nv.path,
+                                format!(
+                                    "unknown #[tool] argument `{}`, expected name, description, \
+                                     schema, output_schema, params or {}",
+                                    ident,
+                                    ANNOTATIONS.join(", ")
+                                ),
+                            ))
                         }
                     }
                 }
@@ -42,77 +90,434 @@ impl Parse for MacroArgs {
                         list.parse_args_with(Punctuated::parse_terminated)?;
 
                     for meta in nested {
-                        if let Meta::NameValue(nv) = meta {
-                            if let Expr::Lit(ExprLit {
-                                lit: Lit::Str(lit_str),
-                                ..
-                            }) = nv.value
-                            {
-                                let param_name = nv.path.get_ident().unwrap().to_string();
-                                param_descriptions.insert(param_name, lit_str.value());
-                            }
-                        }
+                        let Meta::NameValue(nv) = meta else {
+                            return Err(syn::Error::new_spanned(
+                                meta,
+                                "expected a description, e.g. `name = \"...\"`",
+                            ));
+                        };
+                        let Expr::Lit(ExprLit {
+                            lit: Lit::Str(lit_str),
+                            ..
+                        }) = nv.value
+                        else {
+                            return Err(syn::Error::new_spanned(
+                                nv.value,
+                                "expected a string literal",
+                            ));
+                        };
+                        param_descriptions.insert(argument_name(&nv.path)?, lit_str.value());
                     }
                 }
-                _ => {}
+                meta => {
+                    return Err(syn::Error::new_spanned(
+                        meta,
+                        "expected `argument = value` or `params(...)`",
+                    ))
+                }
             }
         }
 
         Ok(MacroArgs {
             name,
             description,
+            schema,
+            output_schema,
             param_descriptions,
+            annotations,
         })
     }
 }
 
+/// The name of an argument of `#[tool]`, which has to be a plain identifier
+fn argument_name(path: &syn::Path) -> syn::Result<String> {
+    path.get_ident()
+        .map(Ident::to_string)
+        .ok_or_else(|| syn::Error::new_spanned(path, "expected an identifier"))
+}
+
+/// A parameter of a tool function
+struct ToolParam {
+    name: Ident,
+    ty: Type,
+    description: Option<String>,
+}
+
+/// A function exposed as a tool, with what we need to describe and call it
+struct ToolFn {
+    fn_name: Ident,
+    tool_name: String,
+    description: TokenStream2,
+    params: Vec<ToolParam>,
+    has_receiver: bool,
+    is_async: bool,
+    // a single `Value` parameter is handed the arguments as they are, with the given schema
+    raw_arguments: bool,
+    schema: Option<Expr>,
+    // the schema of the structured content of tools returning a `ToolOutput` themselves
+    given_output_schema: Option<Expr>,
+    annotations: Vec<(Ident, bool)>,
+    output: Output,
+}
+
+/// How the result of a tool function is turned into a tool result
+struct Output {
+    // the type returned on success
+    ty: Option<Type>,
+    fallible: bool,
+    // errors that already are `ToolError`s are passed on as they are
+    tool_error: bool,
+}
+
+/// The last segment of a type path, e.g. `Vec` for `std::vec::Vec<T>`
+fn last_segment(ty: &Type) -> Option<&syn::PathSegment> {
+    match ty {
+        Type::Path(path) => path.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn is_type(ty: &Type, name: &str) -> bool {
+    last_segment(ty).is_some_and(|segment| segment.ident == name)
+}
+
+fn generic_args(ty: &Type) -> Vec<&Type> {
+    match last_segment(ty).map(|segment| &segment.arguments) {
+        Some(PathArguments::AngleBracketed(args)) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+fn parse_output(output: &ReturnType) -> Output {
+    let ty = match output {
+        ReturnType::Default => {
+            return Output {
+                ty: None,
+                fallible: false,
+                tool_error: false,
+            }
+        }
+        ReturnType::Type(_, ty) => &**ty,
+    };
+
+    let args = generic_args(ty);
+    if is_type(ty, "ToolResult") && !args.is_empty() {
+        return Output {
+            ty: Some(args[0].clone()),
+            fallible: true,
+            tool_error: true,
+        };
+    }
+    if is_type(ty, "Result") && !args.is_empty() {
+        return Output {
+            ty: Some(args[0].clone()),
+            fallible: true,
+            tool_error: args.get(1).is_some_and(|err| is_type(err, "ToolError")),
+        };
+    }
+    Output {
+        ty: Some(ty.clone()),
+        fallible: false,
+        tool_error: false,
+    }
+}
+
+/// The doc comment of an item, which describes the tool when no description is given
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit_str),
+                    ..
+                }) => Some(lit_str.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+impl ToolFn {
+    fn new(args: MacroArgs, attrs: &[Attribute], sig: &Signature) -> syn::Result<Self> {
+        let fn_name = sig.ident.clone();
+        let tool_name = args.name.unwrap_or_else(|| fn_name.to_string());
+        let description = match (args.description, doc_comment(attrs)) {
+            (Some(description), _) => quote! { #description },
+            (None, Some(doc)) => quote! { #doc },
+            (None, None) => quote! { "" },
+        };
+
+        let mut has_receiver = false;
+        let mut params = Vec::new();
+        for arg in sig.inputs.iter() {
+            match arg {
+                FnArg::Receiver(receiver) => {
+                    if receiver.reference.is_none() || receiver.mutability.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            receiver,
+                            "tools can only take `&self`",
+                        ));
+                    }
+                    has_receiver = true;
+                }
+                FnArg::Typed(PatType { pat, ty, .. }) => {
+                    let Pat::Ident(param_ident) = &**pat else {
+                        return Err(syn::Error::new_spanned(
+                            pat,
+                            "tool parameters must be plain identifiers",
+                        ));
+                    };
+                    let name = param_ident.ident.clone();
+                    params.push(ToolParam {
+                        description: args.param_descriptions.get(&name.to_string()).cloned(),
+                        name,
+                        ty: (**ty).clone(),
+                    });
+                }
+            }
+        }
+
+        let raw_arguments = params.len() == 1 && is_type(&params[0].ty, "Value");
+        if raw_arguments && args.schema.is_none() {
+            return Err(syn::Error::new_spanned(
+                &sig.ident,
+                "tools taking the arguments as a `Value` need a `schema`",
+            ));
+        }
+
+        Ok(Self {
+            fn_name,
+            tool_name,
+            description,
+            params,
+            has_receiver,
+            is_async: sig.asyncness.is_some(),
+            raw_arguments,
+            schema: args.schema,
+            given_output_schema: args.output_schema,
+            annotations: args.annotations,
+            output: parse_output(&sig.output),
+        })
+    }
+
+    /// The `ToolAnnotations` of the tool, `None` if no hint was given
+    fn annotations(&self) -> Option<TokenStream2> {
+        if self.annotations.is_empty() {
+            return None;
+        }
+
+        let hints = self
+            .annotations
+            .iter()
+            .map(|(hint, value)| quote! { #hint: #value, });
+        Some(quote! {
+            mcp_core::tool::ToolAnnotations {
+                #(#hints)*
+                ..Default::default()
+            }
+        })
+    }
+
+    /// The schema of the structured content of the tool, for tools returning a value
+    /// rather than content
+    fn output_schema(&self) -> Option<TokenStream2> {
+        if let Some(output_schema) = &self.given_output_schema {
+            return Some(quote! { Some(#output_schema) });
+        }
+        let ty = self.output.ty.as_ref()?;
+        let contents = generic_args(ty);
+        let returns_content = (is_type(ty, "Vec")
+            && contents.first().is_some_and(|ty| is_type(ty, "Content")))
+            || is_type(ty, "Content")
+            || is_type(ty, "String")
+            || is_type(ty, "ToolOutput");
+        (!returns_content).then(|| quote! { mcp_core::handler::generate_output_schema::<#ty>() })
+    }
+
+    /// The struct the arguments are deserialized into, and generate the schema from
+    fn params_struct(&self, struct_name: &Ident) -> TokenStream2 {
+        let fields = self.params.iter().map(|param| {
+            let name = &param.name;
+            let ty = &param.ty;
+            let description = param
+                .description
+                .as_ref()
+                .map(|description| quote! { #[schemars(description = #description)] });
+            // schemars leaves options out of `required` and serde takes missing ones as `None`
+            quote! {
+                #description
+                #name: #ty
+            }
+        });
+
+        quote! {
+            #[derive(serde::Deserialize, schemars::JsonSchema)]
+            struct #struct_name {
+                #(#fields,)*
+            }
+        }
+    }
+
+    /// The schema of the parameters, as a `ToolResult` as generating it may fail
+    fn schema(&self, struct_name: &Ident) -> TokenStream2 {
+        match &self.schema {
+            Some(schema) => {
+                quote! { Ok::<serde_json::Value, mcp_core::handler::ToolError>(#schema) }
+            }
+            None => quote! { mcp_core::handler::generate_schema::<#struct_name>() },
+        }
+    }
+
+    /// Deserialize `params` and call the function through `target`, evaluating to the
+    /// `Result` it returned with the error converted into a `ToolError`
+    fn call(&self, target: TokenStream2, struct_name: &Ident) -> TokenStream2 {
+        let fn_name = &self.fn_name;
+        let (parse, args) = if self.raw_arguments {
+            (quote! {}, quote! { params })
+        } else {
+            let names = self.params.iter().map(|param| &param.name);
+            (
+                quote! {
+                    let params: #struct_name = serde_json::from_value(params)
+                        .map_err(|e| mcp_core::handler::ToolError::InvalidParameters(e.to_string()))?;
+                },
+                quote! { #(params.#names,)* },
+            )
+        };
+        let await_call = self.is_async.then(|| quote! { .await });
+
+        let result = if !self.output.fallible {
+            quote! { Ok::<_, mcp_core::handler::ToolError>(#target #fn_name(#args) #await_call) }
+        } else if self.output.tool_error {
+            quote! { #target #fn_name(#args) #await_call }
+        } else {
+            quote! {
+                #target #fn_name(#args) #await_call
+                    .map_err(|e| mcp_core::handler::ToolError::ExecutionError(e.to_string()))
+            }
+        };
+
+        quote! {
+            #parse
+            #result
+        }
+    }
+
+    /// Convert the successful `result` into the content of a tool result
+    fn to_output(&self) -> TokenStream2 {
+        let Some(ty) = &self.output.ty else {
+            return quote! { Ok(mcp_core::tool::ToolOutput::default()) };
+        };
+
+        let contents = generic_args(ty);
+        if is_type(ty, "ToolOutput") {
+            quote! { Ok(result) }
+        } else if is_type(ty, "Vec") && contents.first().is_some_and(|ty| is_type(ty, "Content")) {
+            quote! { Ok(mcp_core::tool::ToolOutput::from(result)) }
+        } else if is_type(ty, "Content") {
+            quote! { Ok(mcp_core::tool::ToolOutput::from(vec![result])) }
+        } else if is_type(ty, "String") {
+            quote! { Ok(mcp_core::tool::ToolOutput::from(vec![mcp_core::content::Content::text(result)])) }
+        } else {
+            // Objects are structured content, matching the output schema generated for them
+            quote! {
+                let value = serde_json::to_value(&result).map_err(|e| {
+                    mcp_core::handler::ToolError::ExecutionError(format!("Failed to serialize result: {}", e))
+                })?;
+                Ok(match value {
+                    serde_json::Value::Object(_) => mcp_core::tool::ToolOutput::structured(value),
+                    value => mcp_core::tool::ToolOutput::from(vec![mcp_core::content::Content::text(value.to_string())]),
+                })
+            }
+        }
+    }
+}
+
+/// Turn a function into a tool, generating a [`ToolHandler`](mcp_core::handler::ToolHandler)
+/// named after the function in PascalCase.
+///
+/// The description defaults to the doc comment of the function, parameters are described
+/// through `params(name = "...")` and `Option` parameters are not required. Functions may be
+/// sync or async and return any serializable value, or a `Result` of one. Inside a
+/// [`macro@router`] impl, methods taking `&self` can be tools too.
+///
+/// The hints of `mcp_core::tool::ToolAnnotations` are set by name, e.g.
+/// `#[tool(read_only_hint = true, open_world_hint = false)]`. Tools returning a value that
+/// serializes to an object, rather than content or a string, get an output schema generated
+/// from its type. Tools of a [`macro@router`] may also return a `mcp_core::tool::ToolOutput`
+/// to choose their structured content, described by `output_schema = json!({...})`.
 #[proc_macro_attribute]
 pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
     let args = parse_macro_input!(args as MacroArgs);
     let input_fn = parse_macro_input!(input as ItemFn);
 
-    // Extract function details
-    let fn_name = &input_fn.sig.ident;
-    let fn_name_str = fn_name.to_string();
+    let tool = match ToolFn::new(args, &input_fn.attrs, &input_fn.sig) {
+        Ok(tool) => tool,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    if tool.has_receiver {
+        return syn::Error::new_spanned(
+            &input_fn.sig,
+            "methods can only be tools inside a #[router] impl",
+        )
+        .to_compile_error()
+        .into();
+    }
+    if tool
+        .output
+        .ty
+        .as_ref()
+        .is_some_and(|ty| is_type(ty, "ToolOutput"))
+    {
+        return syn::Error::new_spanned(
+            &input_fn.sig.output,
+            "only tools inside a #[router] impl can return a ToolOutput",
+        )
+        .to_compile_error()
+        .into();
+    }
 
     // Generate PascalCase struct name from the function name
+    let fn_name_str = tool.fn_name.to_string();
     let struct_name = format_ident!("{}", { fn_name_str.to_case(Case::Pascal) });
+    let params_struct_name = format_ident!("{}Parameters", struct_name);
 
-    // Use provided name or function name as default
-    let tool_name = args.name.unwrap_or(fn_name_str);
-    let tool_description = args.description.unwrap_or_default();
-
-    // Extract parameter names, types, and descriptions
-    let mut param_defs = Vec::new();
-    let mut param_names = Vec::new();
-
-    for arg in input_fn.sig.inputs.iter() {
-        if let FnArg::Typed(PatType { pat, ty, .. }) = arg {
-            if let Pat::Ident(param_ident) = &**pat {
-                let param_name = &param_ident.ident;
-                let param_name_str = param_name.to_string();
-                let description = args
-                    .param_descriptions
-                    .get(&param_name_str)
-                    .map(|s| s.as_str())
-                    .unwrap_or("");
-
-                param_names.push(param_name);
-                param_defs.push(quote! {
-                    #[schemars(description = #description)]
-                    #param_name: #ty
-                });
+    let tool_name = &tool.tool_name;
+    let tool_description = &tool.description;
+    let params_struct = tool.params_struct(&params_struct_name);
+    let schema = tool.schema(&params_struct_name);
+    let call = tool.call(quote! {}, &params_struct_name);
+    let annotations = tool.annotations().map(|annotations| {
+        quote! {
+            fn annotations(&self) -> Option<mcp_core::tool::ToolAnnotations> {
+                Some(#annotations)
             }
         }
-    }
+    });
+    let output_schema = tool.output_schema().map(|output_schema| {
+        quote! {
+            fn output_schema(&self) -> Option<serde_json::Value> {
+                #output_schema
+            }
+        }
+    });
 
     // Generate the implementation
-    let params_struct_name = format_ident!("{}Parameters", struct_name);
     let expanded = quote! {
-        #[derive(serde::Deserialize, schemars::JsonSchema)]
-        struct #params_struct_name {
-            #(#param_defs,)*
-        }
+        #params_struct
 
         #input_fn
 
@@ -129,24 +534,213 @@ pub fn tool(args: TokenStream, input: TokenStream) -> TokenStream {
                 #tool_description
             }
 
+            // a schema that failed to generate accepts any object, `try_schema` tells why
             fn schema(&self) -> serde_json::Value {
-                mcp_core::handler::generate_schema::<#params_struct_name>()
-                    .expect("Failed to generate schema")
+                self.try_schema()
+                    .unwrap_or_else(|_| serde_json::json!({"type": "object"}))
             }
 
-            async fn call(&self, params: serde_json::Value) -> Result<serde_json::Value, mcp_core::handler::ToolError> {
-                let params: #params_struct_name = serde_json::from_value(params)
-                    .map_err(|e| mcp_core::handler::ToolError::InvalidParameters(e.to_string()))?;
+            fn try_schema(&self) -> Result<serde_json::Value, mcp_core::handler::ToolError> {
+                #schema
+            }
 
-                // Extract parameters and call the function
-                let result = #fn_name(#(params.#param_names,)*).await
-                    .map_err(|e| mcp_core::handler::ToolError::ExecutionError(e.to_string()))?;
+            #annotations
 
-                Ok(serde_json::to_value(result).expect("should serialize"))
+            #output_schema
 
+            async fn call(&self, params: serde_json::Value) -> Result<serde_json::Value, mcp_core::handler::ToolError> {
+                let result = { #call }?;
+                serde_json::to_value(result).map_err(|e| {
+                    mcp_core::handler::ToolError::ExecutionError(format!("Failed to serialize result: {}", e))
+                })
             }
         }
     };
 
     TokenStream::from(expanded)
 }
+
+/// Generate `list_tools`, `call_tool` and `call_tool_with_output` of an `mcp_server::Router`
+/// impl from the methods marked with [`macro@tool`] inside it. Tools returning objects give
+/// them as structured content too.
+///
+/// The tool methods are moved to an inherent impl of the router, which has to be `Clone`
+/// as tools run on a clone of it. Methods taking a single `Value` get the arguments as they
+/// are, described by the `schema` given to `#[tool]`. Tools whose parameter schema cannot be
+/// generated are left out of `list_tools`.
+#[proc_macro_attribute]
+pub fn router(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut item_impl = parse_macro_input!(input as ItemImpl);
+    match expand_router(&mut item_impl) {
+        Ok(expanded) => expanded.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_router(item_impl: &mut ItemImpl) -> syn::Result<TokenStream2> {
+    if item_impl.trait_.is_none() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[router] goes on the `impl Router for ...` block",
+        ));
+    }
+
+    let self_ty = item_impl.self_ty.clone();
+    let router_name = match last_segment(&self_ty) {
+        Some(segment) => segment.ident.to_string(),
+        None => "Router".to_string(),
+    };
+
+    let mut tools = Vec::new();
+    let mut tool_methods = Vec::new();
+    let mut items = Vec::new();
+    for item in std::mem::take(&mut item_impl.items) {
+        let ImplItem::Fn(mut method) = item else {
+            items.push(item);
+            continue;
+        };
+        if ["list_tools", "call_tool", "call_tool_with_output"]
+            .iter()
+            .any(|generated| method.sig.ident == generated)
+        {
+            return Err(syn::Error::new_spanned(
+                &method.sig.ident,
+                "#[router] generates list_tools, call_tool and call_tool_with_output from the #[tool] methods",
+            ));
+        }
+
+        let Some(position) = method
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("tool"))
+        else {
+            items.push(ImplItem::Fn(method));
+            continue;
+        };
+        let attr = method.attrs.remove(position);
+        let args = match attr.meta {
+            Meta::Path(_) => MacroArgs::default(),
+            _ => attr.parse_args::<MacroArgs>()?,
+        };
+        tools.push(ToolFn::new(args, &method.attrs, &method.sig)?);
+        tool_methods.push(method);
+    }
+    item_impl.items = items;
+
+    let mut params_structs = Vec::new();
+    let mut definitions = Vec::new();
+    let mut arms = Vec::new();
+    for tool in &tools {
+        let struct_name = format_ident!(
+            "{}{}Parameters",
+            router_name,
+            tool.fn_name.to_string().to_case(Case::Pascal)
+        );
+        if !tool.raw_arguments {
+            params_structs.push(tool.params_struct(&struct_name));
+        }
+
+        let tool_name = &tool.tool_name;
+        let description = &tool.description;
+        let schema = tool.schema(&struct_name);
+        let annotations = tool
+            .annotations()
+            .map(|annotations| quote! { .with_annotations(#annotations) });
+        let definition =
+            quote! { mcp_core::tool::Tool::new(#tool_name, #description, schema) #annotations };
+        let definition = match tool.output_schema() {
+            Some(output_schema) => quote! {
+                mcp_core::tool::Tool {
+                    output_schema: #output_schema,
+                    ..#definition
+                }
+            },
+            None => definition,
+        };
+        definitions.push(quote! { (#schema).map(|schema| #definition) });
+
+        let target = match tool.has_receiver {
+            true => quote! { this. },
+            false => quote! { <#self_ty>:: },
+        };
+        let call = tool.call(target, &struct_name);
+        let to_output = tool.to_output();
+        arms.push(quote! {
+            #tool_name => {
+                let result = { #call }?;
+                #to_output
+            }
+        });
+    }
+
+    // tools run on a clone of the router, so the future does not borrow it
+    let clone_self = tools
+        .iter()
+        .any(|tool| tool.has_receiver)
+        .then(|| quote! { let this = self.clone(); });
+
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    item_impl.items.push(syn::parse_quote! {
+        fn list_tools(&self) -> Vec<mcp_core::tool::Tool> {
+            // tools whose schema could not be generated cannot be described, so are left out
+            let tools: Vec<Result<mcp_core::tool::Tool, mcp_core::handler::ToolError>> =
+                vec![#(#definitions),*];
+            tools.into_iter().filter_map(Result::ok).collect()
+        }
+    });
+    item_impl.items.push(syn::parse_quote! {
+        fn call_tool(
+            &self,
+            tool_name: &str,
+            arguments: serde_json::Value,
+        ) -> std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                        Output = Result<Vec<mcp_core::content::Content>, mcp_core::handler::ToolError>,
+                    > + Send
+                    + 'static,
+            >,
+        > {
+            let call = self.call_tool_with_output(tool_name, arguments);
+            Box::pin(async move { call.await.map(|output| output.content) })
+        }
+    });
+    item_impl.items.push(syn::parse_quote! {
+        fn call_tool_with_output(
+            &self,
+            tool_name: &str,
+            arguments: serde_json::Value,
+        ) -> std::pin::Pin<
+            Box<
+                dyn std::future::Future<
+                        Output = Result<mcp_core::tool::ToolOutput, mcp_core::handler::ToolError>,
+                    > + Send
+                    + 'static,
+            >,
+        > {
+            #clone_self
+            let tool_name = tool_name.to_string();
+            Box::pin(async move {
+                #[allow(unused_variables)]
+                let params = arguments;
+                match tool_name.as_str() {
+                    #(#arms)*
+                    _ => Err(mcp_core::handler::ToolError::NotFound(format!(
+                        "Tool {} not found",
+                        tool_name
+                    ))),
+                }
+            })
+        }
+    });
+
+    Ok(quote! {
+        #(#params_structs)*
+
+        impl #impl_generics #self_ty #where_clause {
+            #(#tool_methods)*
+        }
+
+        #item_impl
+    })
+}
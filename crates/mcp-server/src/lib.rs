@@ -3,18 +3,36 @@ use std::{
     task::{Context, Poll},
 };
 
-use futures::{Future, Stream};
+use async_trait::async_trait;
+use futures::{Future, Stream, StreamExt};
 use mcp_core::protocol::{JsonRpcError, JsonRpcMessage, JsonRpcRequest, JsonRpcResponse};
 use pin_project::pin_project;
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
 use tower_service::Service;
 
 mod errors;
 pub use errors::{BoxError, RouterError, ServerError, TransportError};
 
+pub mod peer;
+pub use peer::Peer;
+
 pub mod router;
 pub use router::Router;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Carries JSON-RPC messages between a [`Server`] and its client
+#[async_trait]
+pub trait ServerTransport: Send {
+    /// Read the next message from the client, `None` once the client is gone
+    async fn read_message(&mut self) -> Option<Result<JsonRpcMessage, TransportError>>;
+
+    /// Write a message to the client
+    async fn write_message(&mut self, msg: JsonRpcMessage) -> Result<(), std::io::Error>;
+}
+
 /// A transport layer that handles JSON-RPC messages over byte
 #[pin_project]
 pub struct ByteTransport<R, W> {
@@ -116,9 +134,59 @@ where
     }
 }
 
+#[async_trait]
+impl<R, W> ServerTransport for ByteTransport<R, W>
+where
+    R: AsyncRead + Unpin + Send,
+    W: AsyncWrite + Unpin + Send,
+{
+    async fn read_message(&mut self) -> Option<Result<JsonRpcMessage, TransportError>> {
+        self.next().await
+    }
+
+    async fn write_message(&mut self, msg: JsonRpcMessage) -> Result<(), std::io::Error> {
+        ByteTransport::write_message(self, msg).await
+    }
+}
+
+/// A transport handing JSON-RPC messages over channels as they are, for a client
+/// running in the same process
+pub struct ChannelTransport {
+    receiver: mpsc::Receiver<JsonRpcMessage>,
+    sender: mpsc::Sender<JsonRpcMessage>,
+}
+
+impl ChannelTransport {
+    /// Read the client's messages from `receiver` and write ours to `sender`
+    pub fn new(
+        receiver: mpsc::Receiver<JsonRpcMessage>,
+        sender: mpsc::Sender<JsonRpcMessage>,
+    ) -> Self {
+        Self { receiver, sender }
+    }
+}
+
+#[async_trait]
+impl ServerTransport for ChannelTransport {
+    async fn read_message(&mut self) -> Option<Result<JsonRpcMessage, TransportError>> {
+        self.receiver.recv().await.map(Ok)
+    }
+
+    async fn write_message(&mut self, msg: JsonRpcMessage) -> Result<(), std::io::Error> {
+        self.sender.send(msg).await.map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                "Client is no longer connected",
+            )
+        })
+    }
+}
+
 /// The main server type that processes incoming requests
 pub struct Server<S> {
     service: S,
+    // The peer routers use to reach the client, and the server-initiated messages it queues
+    peer: Option<(Peer, mpsc::UnboundedReceiver<JsonRpcMessage>)>,
 }
 
 impl<S> Server<S>
@@ -128,21 +196,59 @@ where
     S::Future: Send,
 {
     pub fn new(service: S) -> Self {
-        Self { service }
+        Self {
+            service,
+            peer: None,
+        }
     }
 
-    // TODO transport trait instead of byte transport if we implement others
-    pub async fn run<R, W>(self, mut transport: ByteTransport<R, W>) -> Result<(), ServerError>
+    /// Forward the messages queued through `peer` to the client while running,
+    /// and hand the client's responses and notifications back to it
+    pub fn with_peer(
+        mut self,
+        peer: Peer,
+        receiver: mpsc::UnboundedReceiver<JsonRpcMessage>,
+    ) -> Self {
+        self.peer = Some((peer, receiver));
+        self
+    }
+
+    /// Serve the client connected through `transport` until it goes away
+    pub async fn run<T>(self, mut transport: T) -> Result<(), ServerError>
     where
-        R: AsyncRead + Unpin,
-        W: AsyncWrite + Unpin,
+        T: ServerTransport,
     {
-        use futures::StreamExt;
+        use futures::stream::FuturesUnordered;
         let mut service = self.service;
+        let (peer, mut notifications) = match self.peer {
+            Some((peer, receiver)) => (Some(peer), Some(receiver)),
+            None => (None, None),
+        };
+
+        // Requests are processed concurrently, so a handler waiting on the client
+        // (e.g. for `roots/list`) does not stop us from reading the client's answer
+        let mut in_flight = FuturesUnordered::new();
 
         tracing::info!("Server started");
-        while let Some(msg_result) = transport.next().await {
-            let _span = tracing::span!(tracing::Level::INFO, "message_processing").entered();
+        loop {
+            let msg_result = tokio::select! {
+                msg = transport.read_message() => match msg {
+                    Some(msg) => msg,
+                    None => break,
+                },
+                Some(notification) = next_notification(&mut notifications) => {
+                    if let Err(e) = transport.write_message(notification).await {
+                        return Err(ServerError::Transport(TransportError::Io(e)));
+                    }
+                    continue;
+                }
+                Some(response) = in_flight.next(), if !in_flight.is_empty() => {
+                    write_response(&mut transport, response).await?;
+                    continue;
+                }
+            };
+
+            let span = tracing::span!(tracing::Level::INFO, "message_processing").entered();
             match msg_result {
                 Ok(msg) => {
                     match msg {
@@ -160,46 +266,40 @@ where
                             );
 
                             // Process the request using our service
-                            let response = match service.call(request).await {
-                                Ok(resp) => resp,
-                                Err(e) => {
-                                    let error_msg = e.into().to_string();
-                                    tracing::error!(error = %error_msg, "Request processing failed");
-                                    JsonRpcResponse {
-                                        jsonrpc: "2.0".to_string(),
-                                        id,
-                                        result: None,
-                                        error: Some(mcp_core::protocol::ErrorData {
-                                            code: mcp_core::protocol::INTERNAL_ERROR,
-                                            message: error_msg,
-                                            data: None,
-                                        }),
+                            let future = service.call(request);
+                            // boxed, so the server stays Send for callers spawning it
+                            let response: Pin<Box<dyn Future<Output = JsonRpcResponse> + Send>> =
+                                Box::pin(async move {
+                                    match future.await {
+                                        Ok(resp) => resp,
+                                        Err(e) => {
+                                            let error_msg = e.into().to_string();
+                                            tracing::error!(error = %error_msg, "Request processing failed");
+                                            JsonRpcResponse {
+                                                jsonrpc: "2.0".to_string(),
+                                                id,
+                                                result: None,
+                                                error: Some(mcp_core::protocol::ErrorData {
+                                                    code: mcp_core::protocol::INTERNAL_ERROR,
+                                                    message: error_msg,
+                                                    data: None,
+                                                }),
+                                            }
+                                        }
                                     }
-                                }
-                            };
-
-                            // Serialize response for logging
-                            let response_json = serde_json::to_string(&response)
-                                .unwrap_or_else(|_| "Failed to serialize response".to_string());
-
-                            tracing::info!(
-                                response_id = ?response.id,
-                                json = %response_json,
-                                "Sending response"
-                            );
-                            // Send the response back
-                            if let Err(e) = transport
-                                .write_message(JsonRpcMessage::Response(response))
-                                .await
-                            {
-                                return Err(ServerError::Transport(TransportError::Io(e)));
-                            }
+                                });
+                            in_flight.push(response);
                         }
                         JsonRpcMessage::Response(_)
                         | JsonRpcMessage::Notification(_)
-                        | JsonRpcMessage::Nil
                         | JsonRpcMessage::Error(_) => {
-                            // Ignore responses, notifications and nil messages for now
+                            // Answers to requests sent through the peer, and client notifications
+                            if let Some(peer) = &peer {
+                                peer.handle_client_message(msg);
+                            }
+                        }
+                        JsonRpcMessage::Nil => {
+                            // Ignore nil messages
                             continue;
                         }
                     }
@@ -232,6 +332,8 @@ where
                         error,
                     });
 
+                    // an entered span is not Send, leave it before awaiting the write
+                    drop(span);
                     if let Err(e) = transport.write_message(error_response).await {
                         return Err(ServerError::Transport(TransportError::Io(e)));
                     }
@@ -239,8 +341,68 @@ where
             }
         }
 
+        // The client is gone, but finish what it asked for before shutting down
+        while let Some(response) = in_flight.next().await {
+            write_response(&mut transport, response).await?;
+        }
+
         Ok(())
     }
+
+    /// Serve a client of this process on a task of its own, over a [`ChannelTransport`]
+    ///
+    /// Returns the sender for the client's messages and the receiver for ours. The server
+    /// stops once the sender is dropped.
+    pub fn spawn(self) -> (mpsc::Sender<JsonRpcMessage>, mpsc::Receiver<JsonRpcMessage>)
+    where
+        S: 'static,
+    {
+        let (to_server, server_rx) = mpsc::channel(32);
+        let (server_tx, from_server) = mpsc::channel(32);
+        tokio::spawn(async move {
+            if let Err(e) = self.run(ChannelTransport::new(server_rx, server_tx)).await {
+                tracing::error!(error = ?e, "In-process server failed");
+            }
+        });
+        (to_server, from_server)
+    }
+}
+
+async fn write_response<T>(transport: &mut T, response: JsonRpcResponse) -> Result<(), ServerError>
+where
+    T: ServerTransport,
+{
+    // Serialize response for logging
+    let response_json = serde_json::to_string(&response)
+        .unwrap_or_else(|_| "Failed to serialize response".to_string());
+
+    tracing::info!(
+        response_id = ?response.id,
+        json = %response_json,
+        "Sending response"
+    );
+    // Send the response back
+    transport
+        .write_message(JsonRpcMessage::Response(response))
+        .await
+        .map_err(|e| ServerError::Transport(TransportError::Io(e)))
+}
+
+// Resolves to the next queued notification, or never if there is nothing left to forward
+async fn next_notification(
+    receiver: &mut Option<mpsc::UnboundedReceiver<JsonRpcMessage>>,
+) -> Option<JsonRpcMessage> {
+    match receiver {
+        Some(rx) => match rx.recv().await {
+            Some(message) => Some(message),
+            None => {
+                // every peer was dropped, stop polling the closed channel
+                *receiver = None;
+                futures::future::pending().await
+            }
+        },
+        None => futures::future::pending().await,
+    }
 }
 
 // Define a specific service implementation that we need for any
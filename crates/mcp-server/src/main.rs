@@ -2,9 +2,10 @@ use anyhow::Result;
 use mcp_core::content::Content;
 use mcp_core::handler::{PromptError, ResourceError};
 use mcp_core::prompt::{Prompt, PromptArgument};
+use mcp_core::protocol::LoggingLevel;
 use mcp_core::{handler::ToolError, protocol::ServerCapabilities, resource::Resource, tool::Tool};
 use mcp_server::router::{CapabilitiesBuilder, RouterService};
-use mcp_server::{ByteTransport, Router, Server};
+use mcp_server::{ByteTransport, Peer, Router, Server};
 use serde_json::Value;
 use std::{future::Future, pin::Pin, sync::Arc};
 use tokio::{
@@ -18,24 +19,36 @@ use tracing_subscriber::{self, EnvFilter};
 #[derive(Clone)]
 struct CounterRouter {
     counter: Arc<Mutex<i32>>,
+    peer: Peer,
 }
 
 impl CounterRouter {
-    fn new() -> Self {
+    fn new(peer: Peer) -> Self {
         Self {
             counter: Arc::new(Mutex::new(0)),
+            peer,
         }
     }
 
     async fn increment(&self) -> Result<i32, ToolError> {
         let mut counter = self.counter.lock().await;
         *counter += 1;
+        let _ = self.peer.log(
+            LoggingLevel::Debug,
+            Some("counter"),
+            serde_json::json!({"action": "increment", "value": *counter}),
+        );
         Ok(*counter)
     }
 
     async fn decrement(&self) -> Result<i32, ToolError> {
         let mut counter = self.counter.lock().await;
         *counter -= 1;
+        let _ = self.peer.log(
+            LoggingLevel::Debug,
+            Some("counter"),
+            serde_json::json!({"action": "decrement", "value": *counter}),
+        );
         Ok(*counter)
     }
 
@@ -63,9 +76,14 @@ impl Router for CounterRouter {
             .with_tools(false)
             .with_resources(false, false)
             .with_prompts(false)
+            .with_logging()
             .build()
     }
 
+    fn peer(&self) -> Option<Peer> {
+        Some(self.peer.clone())
+    }
+
     fn list_tools(&self) -> Vec<Tool> {
         vec![
             Tool::new(
@@ -205,11 +223,12 @@ async fn main() -> Result<()> {
 
     tracing::info!("Starting MCP server");
 
-    // Create an instance of our counter router
-    let router = RouterService(CounterRouter::new());
+    // Create an instance of our counter router, with a peer to send logs to the client
+    let (peer, notifications) = Peer::new();
+    let router = RouterService(CounterRouter::new(peer.clone()));
 
     // Create and run the server
-    let server = Server::new(router);
+    let server = Server::new(router).with_peer(peer, notifications);
     let transport = ByteTransport::new(stdin(), stdout());
 
     tracing::info!("Server initialized and ready to handle requests");
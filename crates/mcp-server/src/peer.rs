@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+use mcp_core::protocol::{
+    JsonRpcMessage, JsonRpcNotification, JsonRpcRequest, ListRootsResult, LoggingLevel,
+    LoggingMessageNotification, ResourceUpdatedNotification, Root, RootsCapability,
+};
+use serde_json::Value;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::ServerError;
+
+// How long to wait for the client to answer a request sent through the peer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A handle to the client connected to a server.
+///
+/// Routers hold on to a peer to push server-initiated messages, such as log
+/// notifications, to the client and to send it requests, such as `roots/list`.
+/// The peer and its matching receiver are handed to [`crate::Server::with_peer`]
+/// which writes the messages to the transport and routes the client's answers back.
+#[derive(Clone)]
+pub struct Peer {
+    sender: mpsc::UnboundedSender<JsonRpcMessage>,
+    next_id: Arc<AtomicU64>,
+    // Requests sent to the client that are waiting for its response, keyed by id
+    pending_requests: Arc<Mutex<HashMap<u64, oneshot::Sender<JsonRpcMessage>>>>,
+    // The roots capability the client declared in `initialize`, if any
+    roots_capability: Arc<RwLock<Option<RootsCapability>>>,
+    // The roots last listed by the client, dropped when it reports they changed
+    roots: Arc<RwLock<Option<Vec<Root>>>>,
+    // The minimum level requested by the client through `logging/setLevel`
+    logging_level: Arc<RwLock<LoggingLevel>>,
+    // Resource URIs the client asked to be notified about through `resources/subscribe`
+    subscriptions: Arc<RwLock<HashSet<String>>>,
+}
+
+impl Peer {
+    /// Create a peer and the receiver its outgoing messages are delivered to
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<JsonRpcMessage>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let peer = Self {
+            sender,
+            next_id: Arc::new(AtomicU64::new(0)),
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            roots_capability: Arc::new(RwLock::new(None)),
+            roots: Arc::new(RwLock::new(None)),
+            logging_level: Arc::new(RwLock::new(LoggingLevel::Info)),
+            subscriptions: Arc::new(RwLock::new(HashSet::new())),
+        };
+        (peer, receiver)
+    }
+
+    /// Send a notification to the client
+    pub fn notify(&self, method: &str, params: Option<Value>) -> Result<(), ServerError> {
+        self.sender
+            .send(JsonRpcMessage::Notification(JsonRpcNotification {
+                jsonrpc: "2.0".to_string(),
+                method: method.to_string(),
+                params,
+            }))
+            .map_err(|_| ServerError::Internal("Client is no longer connected".to_string()))
+    }
+
+    /// Send a request to the client and wait for the result
+    pub async fn request(&self, method: &str, params: Option<Value>) -> Result<Value, ServerError> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.lock().unwrap().insert(id, tx);
+
+        let sent = self.sender.send(JsonRpcMessage::Request(JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(id),
+            method: method.to_string(),
+            params,
+        }));
+        if sent.is_err() {
+            self.pending_requests.lock().unwrap().remove(&id);
+            return Err(ServerError::Internal(
+                "Client is no longer connected".to_string(),
+            ));
+        }
+
+        let response = tokio::time::timeout(REQUEST_TIMEOUT, rx).await;
+        self.pending_requests.lock().unwrap().remove(&id);
+
+        match response {
+            Ok(Ok(JsonRpcMessage::Response(response))) => {
+                if let Some(error) = response.error {
+                    return Err(ServerError::Service(error.message));
+                }
+                Ok(response.result.unwrap_or(Value::Null))
+            }
+            Ok(Ok(JsonRpcMessage::Error(error))) => Err(ServerError::Service(error.error.message)),
+            Ok(Ok(_)) => Err(ServerError::Internal(
+                "Unexpected response from client".to_string(),
+            )),
+            Ok(Err(_)) => Err(ServerError::Internal(
+                "Client is no longer connected".to_string(),
+            )),
+            Err(_) => Err(ServerError::Internal(format!(
+                "Client did not answer '{}' in time",
+                method
+            ))),
+        }
+    }
+
+    /// Handle a message the client sent that is not a request: responses to
+    /// [`Peer::request`] and notifications about the client's state
+    pub fn handle_client_message(&self, message: JsonRpcMessage) {
+        match message {
+            JsonRpcMessage::Response(ref response) => {
+                if let Some(id) = response.id {
+                    self.respond(id, message);
+                }
+            }
+            JsonRpcMessage::Error(ref error) => {
+                if let Some(id) = error.id {
+                    self.respond(id, message);
+                }
+            }
+            JsonRpcMessage::Notification(notification) => {
+                if notification.method == "notifications/roots/list_changed" {
+                    *self.roots.write().unwrap() = None;
+                }
+            }
+            JsonRpcMessage::Request(_) | JsonRpcMessage::Nil => {}
+        }
+    }
+
+    fn respond(&self, id: u64, message: JsonRpcMessage) {
+        if let Some(tx) = self.pending_requests.lock().unwrap().remove(&id) {
+            let _ = tx.send(message);
+        }
+    }
+
+    /// Record the roots capability the client declared when initializing
+    pub fn set_roots_capability(&self, capability: Option<RootsCapability>) {
+        *self.roots_capability.write().unwrap() = capability;
+        *self.roots.write().unwrap() = None;
+    }
+
+    /// Whether the client can tell us which directories we should operate in
+    pub fn supports_roots(&self) -> bool {
+        self.roots_capability.read().unwrap().is_some()
+    }
+
+    /// The roots the client exposes, asked with `roots/list` once and again
+    /// after the client reports they changed
+    pub async fn list_roots(&self) -> Result<Vec<Root>, ServerError> {
+        if !self.supports_roots() {
+            return Err(ServerError::Internal(
+                "Client does not support roots".to_string(),
+            ));
+        }
+
+        if let Some(roots) = self.roots.read().unwrap().clone() {
+            return Ok(roots);
+        }
+
+        let result = self.request("roots/list", None).await?;
+        let result: ListRootsResult = serde_json::from_value(result)
+            .map_err(|e| ServerError::Internal(format!("Invalid roots/list result: {}", e)))?;
+        *self.roots.write().unwrap() = Some(result.roots.clone());
+        Ok(result.roots)
+    }
+
+    /// The minimum level of log messages forwarded to the client
+    pub fn logging_level(&self) -> LoggingLevel {
+        *self.logging_level.read().unwrap()
+    }
+
+    pub fn set_logging_level(&self, level: LoggingLevel) {
+        *self.logging_level.write().unwrap() = level;
+    }
+
+    /// Send a `notifications/message` log to the client if it is at or above the requested level
+    pub fn log<S: Into<String>>(
+        &self,
+        level: LoggingLevel,
+        logger: Option<S>,
+        data: Value,
+    ) -> Result<(), ServerError> {
+        if level < self.logging_level() {
+            return Ok(());
+        }
+
+        let params = LoggingMessageNotification {
+            level,
+            logger: logger.map(Into::into),
+            data,
+        };
+        let params = serde_json::to_value(params)
+            .map_err(|e| ServerError::Internal(format!("JSON serialization error: {}", e)))?;
+        self.notify("notifications/message", Some(params))
+    }
+
+    pub fn subscribe(&self, uri: &str) {
+        self.subscriptions.write().unwrap().insert(uri.to_string());
+    }
+
+    pub fn unsubscribe(&self, uri: &str) {
+        self.subscriptions.write().unwrap().remove(uri);
+    }
+
+    /// Whether the client subscribed to updates of the resource
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        self.subscriptions.read().unwrap().contains(uri)
+    }
+
+    /// Tell the client a resource changed, if it subscribed to it
+    pub fn notify_resource_updated(&self, uri: &str) -> Result<(), ServerError> {
+        if !self.is_subscribed(uri) {
+            return Ok(());
+        }
+
+        let params = serde_json::to_value(ResourceUpdatedNotification {
+            uri: uri.to_string(),
+        })
+        .map_err(|e| ServerError::Internal(format!("JSON serialization error: {}", e)))?;
+        self.notify("notifications/resources/updated", Some(params))
+    }
+
+    /// Tell the client the list of available resources changed
+    pub fn notify_resource_list_changed(&self) -> Result<(), ServerError> {
+        self.notify("notifications/resources/list_changed", None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_log_respects_level() {
+        let (peer, mut receiver) = Peer::new();
+
+        peer.log(LoggingLevel::Debug, None::<String>, json!("dropped"))
+            .unwrap();
+        peer.log(LoggingLevel::Error, Some("db"), json!("kept"))
+            .unwrap();
+
+        match receiver.recv().await.unwrap() {
+            JsonRpcMessage::Notification(notification) => {
+                assert_eq!(notification.method, "notifications/message");
+                assert_eq!(
+                    notification.params.unwrap(),
+                    json!({"level": "error", "logger": "db", "data": "kept"})
+                );
+            }
+            _ => panic!("Expected Notification"),
+        }
+        assert!(receiver.try_recv().is_err());
+
+        peer.set_logging_level(LoggingLevel::Debug);
+        peer.log(LoggingLevel::Debug, None::<String>, json!("now sent"))
+            .unwrap();
+        assert!(receiver.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_list_roots_is_cached_until_changed() {
+        let (peer, mut receiver) = Peer::new();
+        assert!(peer.list_roots().await.is_err());
+
+        peer.set_roots_capability(Some(RootsCapability {
+            list_changed: Some(true),
+        }));
+
+        // Answer every roots/list request like a client would
+        let client = peer.clone();
+        let answered = Arc::new(AtomicU64::new(0));
+        let count = Arc::clone(&answered);
+        tokio::spawn(async move {
+            while let Some(JsonRpcMessage::Request(request)) = receiver.recv().await {
+                assert_eq!(request.method, "roots/list");
+                count.fetch_add(1, Ordering::SeqCst);
+                client.handle_client_message(JsonRpcMessage::Response(
+                    mcp_core::protocol::JsonRpcResponse {
+                        jsonrpc: "2.0".to_string(),
+                        id: request.id,
+                        result: Some(json!({"roots": [{"uri": "file:///project"}]})),
+                        error: None,
+                    },
+                ));
+            }
+        });
+
+        let roots = peer.list_roots().await.unwrap();
+        assert_eq!(roots[0].uri, "file:///project");
+        peer.list_roots().await.unwrap();
+        assert_eq!(answered.load(Ordering::SeqCst), 1);
+
+        peer.handle_client_message(JsonRpcMessage::Notification(JsonRpcNotification {
+            jsonrpc: "2.0".to_string(),
+            method: "notifications/roots/list_changed".to_string(),
+            params: None,
+        }));
+        peer.list_roots().await.unwrap();
+        assert_eq!(answered.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_resource_updated_requires_subscription() {
+        let (peer, mut receiver) = Peer::new();
+
+        peer.notify_resource_updated("file:///a.txt").unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        peer.subscribe("file:///a.txt");
+        peer.notify_resource_updated("file:///a.txt").unwrap();
+        match receiver.recv().await.unwrap() {
+            JsonRpcMessage::Notification(notification) => {
+                assert_eq!(notification.method, "notifications/resources/updated");
+                assert_eq!(
+                    notification.params.unwrap(),
+                    json!({"uri": "file:///a.txt"})
+                );
+            }
+            _ => panic!("Expected Notification"),
+        }
+
+        peer.unsubscribe("file:///a.txt");
+        peer.notify_resource_updated("file:///a.txt").unwrap();
+        assert!(receiver.try_recv().is_err());
+    }
+}
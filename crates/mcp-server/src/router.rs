@@ -5,29 +5,36 @@ use std::{
 };
 
 type PromptFuture = Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>>;
+type CompletionFuture =
+    Pin<Box<dyn Future<Output = Result<Vec<String>, CompletionError>> + Send + 'static>>;
 
 use mcp_core::{
     content::Content,
-    handler::{PromptError, ResourceError, ToolError},
+    handler::{CompletionError, PromptError, ResourceError, ToolError},
     prompt::{Prompt, PromptMessage, PromptMessageRole},
     protocol::{
-        CallToolResult, GetPromptResult, Implementation, InitializeResult, JsonRpcRequest,
-        JsonRpcResponse, ListPromptsResult, ListResourcesResult, ListToolsResult,
+        CallToolResult, CompleteParams, CompleteResult, Completion, CompletionArgument,
+        CompletionReference, CompletionsCapability, EmptyResult, GetPromptResult, Implementation,
+        InitializeResult, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
+        ListResourceTemplatesResult, ListResourcesResult, ListToolsResult, LoggingCapability,
         PromptsCapability, ReadResourceResult, ResourcesCapability, ServerCapabilities,
-        ToolsCapability,
+        SetLevelParams, ToolsCapability, LATEST_PROTOCOL_VERSION, SUPPORTED_PROTOCOL_VERSIONS,
     },
-    ResourceContents,
+    tool::{ArgumentValidator, ToolOutput},
+    ResourceContents, ResourceTemplate,
 };
 use serde_json::Value;
 use tower_service::Service;
 
-use crate::{BoxError, RouterError};
+use crate::{BoxError, Peer, RouterError};
 
 /// Builder for configuring and constructing capabilities
 pub struct CapabilitiesBuilder {
     tools: Option<ToolsCapability>,
     prompts: Option<PromptsCapability>,
     resources: Option<ResourcesCapability>,
+    logging: Option<LoggingCapability>,
+    completions: Option<CompletionsCapability>,
 }
 
 impl Default for CapabilitiesBuilder {
@@ -42,6 +49,8 @@ impl CapabilitiesBuilder {
             tools: None,
             prompts: None,
             resources: None,
+            logging: None,
+            completions: None,
         }
     }
 
@@ -61,7 +70,8 @@ impl CapabilitiesBuilder {
         self
     }
 
-    /// Enable resources capability
+    /// Enable resources capability. Routers that enable `subscribe` must provide a
+    /// [`Peer`] through [`Router::peer`] and notify it when subscribed resources change.
     pub fn with_resources(mut self, subscribe: bool, list_changed: bool) -> Self {
         self.resources = Some(ResourcesCapability {
             subscribe: Some(subscribe),
@@ -70,6 +80,18 @@ impl CapabilitiesBuilder {
         self
     }
 
+    /// Enable logging capability, letting the client set a level and receive log notifications
+    pub fn with_logging(mut self) -> Self {
+        self.logging = Some(LoggingCapability::default());
+        self
+    }
+
+    /// Enable completions capability, letting the client ask for argument suggestions
+    pub fn with_completions(mut self) -> Self {
+        self.completions = Some(CompletionsCapability::default());
+        self
+    }
+
     /// Build the router with automatic capability inference
     pub fn build(self) -> ServerCapabilities {
         // Create capabilities based on what's configured
@@ -77,6 +99,8 @@ impl CapabilitiesBuilder {
             tools: self.tools,
             prompts: self.prompts,
             resources: self.resources,
+            logging: self.logging,
+            completions: self.completions,
         }
     }
 }
@@ -92,6 +116,18 @@ pub trait Router: Send + Sync + 'static {
         tool_name: &str,
         arguments: Value,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>;
+
+    /// Call a tool, with the structured content of its result if it has an output schema.
+    /// The result of `call_tool` without structured content by default.
+    fn call_tool_with_output(
+        &self,
+        tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, ToolError>> + Send + 'static>> {
+        let call = self.call_tool(tool_name, arguments);
+        Box::pin(async move { call.await.map(ToolOutput::from) })
+    }
+
     fn list_resources(&self) -> Vec<mcp_core::resource::Resource>;
     fn read_resource(
         &self,
@@ -100,6 +136,27 @@ pub trait Router: Send + Sync + 'static {
     fn list_prompts(&self) -> Vec<Prompt>;
     fn get_prompt(&self, prompt_name: &str) -> PromptFuture;
 
+    /// Parameterized resources, read through `read_resource` once the client expands the template
+    fn list_resource_templates(&self) -> Vec<ResourceTemplate> {
+        vec![]
+    }
+
+    /// Suggest values for an argument of a prompt or resource template, given what was typed so far
+    fn complete(
+        &self,
+        _reference: &CompletionReference,
+        _argument: &CompletionArgument,
+    ) -> CompletionFuture {
+        Box::pin(async { Ok(vec![]) })
+    }
+
+    /// The connected client, for routers that send notifications such as logs or
+    /// query the client's roots. Routers created with a [`Peer`] should return it here
+    /// so protocol requests like `initialize` and `logging/setLevel` can update it.
+    fn peer(&self) -> Option<Peer> {
+        None
+    }
+
     // Helper method to create base response
     fn create_response(&self, id: Option<u64>) -> JsonRpcResponse {
         JsonRpcResponse {
@@ -115,8 +172,31 @@ pub trait Router: Send + Sync + 'static {
         req: JsonRpcRequest,
     ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
         async move {
+            // Remember whether the client can tell us its roots
+            if let Some(peer) = self.peer() {
+                let roots = req
+                    .params
+                    .as_ref()
+                    .and_then(|params| params.get("capabilities"))
+                    .and_then(|capabilities| capabilities.get("roots"))
+                    .and_then(|roots| serde_json::from_value(roots.clone()).ok());
+                peer.set_roots_capability(roots);
+            }
+
+            // Answer with the version the client asked for if we speak it, and otherwise with
+            // ours, leaving it to the client to disconnect if it cannot speak that one
+            let requested = req
+                .params
+                .as_ref()
+                .and_then(|params| params.get("protocolVersion"))
+                .and_then(|version| version.as_str());
+            let protocol_version = match requested {
+                Some(version) if SUPPORTED_PROTOCOL_VERSIONS.contains(&version) => version,
+                _ => LATEST_PROTOCOL_VERSION,
+            };
+
             let result = InitializeResult {
-                protocol_version: "2024-11-05".to_string(),
+                protocol_version: protocol_version.to_string(),
                 capabilities: self.capabilities().clone(),
                 server_info: Implementation {
                     name: self.name(),
@@ -135,6 +215,20 @@ pub trait Router: Send + Sync + 'static {
         }
     }
 
+    fn handle_ping(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(EmptyResult {}).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+            Ok(response)
+        }
+    }
+
     fn handle_tools_list(
         &self,
         req: JsonRpcRequest,
@@ -172,14 +266,50 @@ pub trait Router: Send + Sync + 'static {
 
             let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
 
-            let result = match self.call_tool(name, arguments).await {
-                Ok(result) => CallToolResult {
-                    content: result,
+            let tool = self.list_tools().into_iter().find(|tool| tool.name == name);
+
+            // Reject malformed arguments before they reach the tool, with every violation listed
+            let validated = match &tool {
+                Some(tool) => ArgumentValidator::new(tool.input_schema.clone()).validate(arguments),
+                None => Ok(arguments),
+            };
+
+            let outcome = match validated {
+                Ok(arguments) => self.call_tool_with_output(name, arguments).await,
+                Err(err) => Err(err),
+            };
+
+            // Structured content must match the output schema, tools without one have none
+            let output_validator = tool
+                .and_then(|tool| tool.output_schema)
+                .map(ArgumentValidator::new);
+            if output_validator.is_some()
+                && matches!(&outcome, Ok(output) if output.structured_content.is_none())
+            {
+                // a tool declaring an output schema has to give structured content
+                return Err(RouterError::Internal(format!(
+                    "Tool {} has an output schema but returned no structured content",
+                    name
+                )));
+            }
+            let outcome = outcome.and_then(|mut output| {
+                match (&output_validator, &output.structured_content) {
+                    (Some(validator), Some(structured)) => validator.validate_output(structured)?,
+                    _ => output.structured_content = None,
+                }
+                Ok(output)
+            });
+
+            let result = match outcome {
+                Ok(output) => CallToolResult {
+                    content: output.content,
                     is_error: None,
+                    structured_content: output.structured_content,
                 },
                 Err(err) => CallToolResult {
                     content: vec![Content::text(err.to_string())],
                     is_error: Some(true),
+                    structured_content: None,
                 },
             };
 
@@ -248,6 +378,145 @@ pub trait Router: Send + Sync + 'static {
         }
     }
 
+    fn handle_logging_set_level(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            if self.capabilities().logging.is_none() {
+                return Err(RouterError::MethodNotFound(req.method));
+            }
+
+            let params = req
+                .params
+                .ok_or_else(|| RouterError::InvalidParams("Missing parameters".into()))?;
+
+            let SetLevelParams { level } = serde_json::from_value(params)
+                .map_err(|e| RouterError::InvalidParams(format!("Invalid logging level: {}", e)))?;
+
+            if let Some(peer) = self.peer() {
+                peer.set_logging_level(level);
+            }
+
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(EmptyResult {}).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+
+            Ok(response)
+        }
+    }
+
+    fn handle_resources_templates_list(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let resource_templates = self.list_resource_templates();
+
+            let result = ListResourceTemplatesResult {
+                resource_templates,
+                next_cursor: None,
+            };
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(result).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+
+            Ok(response)
+        }
+    }
+
+    fn handle_resources_subscribe(
+        &self,
+        req: JsonRpcRequest,
+        subscribe: bool,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            let supports_subscribe = self
+                .capabilities()
+                .resources
+                .and_then(|r| r.subscribe)
+                .unwrap_or(false);
+            if !supports_subscribe {
+                return Err(RouterError::MethodNotFound(req.method));
+            }
+
+            let params = req
+                .params
+                .ok_or_else(|| RouterError::InvalidParams("Missing parameters".into()))?;
+
+            let uri = params
+                .get("uri")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RouterError::InvalidParams("Missing resource URI".into()))?;
+
+            let peer = self.peer().ok_or_else(|| {
+                RouterError::Internal("Router has no peer to deliver resource updates".into())
+            })?;
+
+            if subscribe {
+                peer.subscribe(uri);
+            } else {
+                peer.unsubscribe(uri);
+            }
+
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(EmptyResult {}).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+
+            Ok(response)
+        }
+    }
+
+    fn handle_completion_complete(
+        &self,
+        req: JsonRpcRequest,
+    ) -> impl Future<Output = Result<JsonRpcResponse, RouterError>> + Send {
+        async move {
+            if self.capabilities().completions.is_none() {
+                return Err(RouterError::MethodNotFound(req.method));
+            }
+
+            let params = req
+                .params
+                .ok_or_else(|| RouterError::InvalidParams("Missing parameters".into()))?;
+
+            let params: CompleteParams = serde_json::from_value(params)
+                .map_err(|e| RouterError::InvalidParams(format!("Invalid parameters: {}", e)))?;
+
+            let values = self
+                .complete(&params.reference, &params.argument)
+                .await
+                .map_err(|e| match e {
+                    CompletionError::InvalidParameters(msg) => RouterError::InvalidParams(msg),
+                    CompletionError::InternalError(msg) => RouterError::Internal(msg),
+                })?;
+
+            // The protocol caps a response at 100 values
+            let total = values.len();
+            let result = CompleteResult {
+                completion: Completion {
+                    values: values.into_iter().take(100).collect(),
+                    total: Some(total as u32),
+                    has_more: Some(total > 100),
+                },
+            };
+
+            let mut response = self.create_response(req.id);
+            response.result =
+                Some(serde_json::to_value(result).map_err(|e| {
+                    RouterError::Internal(format!("JSON serialization error: {}", e))
+                })?);
+
+            Ok(response)
+        }
+    }
+
     fn handle_prompts_list(
         &self,
         req: JsonRpcRequest,
@@ -412,12 +681,18 @@ where
         Box::pin(async move {
             let result = match req.method.as_str() {
                 "initialize" => this.handle_initialize(req).await,
+                "ping" => this.handle_ping(req).await,
                 "tools/list" => this.handle_tools_list(req).await,
                 "tools/call" => this.handle_tools_call(req).await,
                 "resources/list" => this.handle_resources_list(req).await,
                 "resources/read" => this.handle_resources_read(req).await,
+                "completion/complete" => this.handle_completion_complete(req).await,
+                "resources/templates/list" => this.handle_resources_templates_list(req).await,
+                "resources/subscribe" => this.handle_resources_subscribe(req, true).await,
+                "resources/unsubscribe" => this.handle_resources_subscribe(req, false).await,
                 "prompts/list" => this.handle_prompts_list(req).await,
                 "prompts/get" => this.handle_prompts_get(req).await,
+                "logging/setLevel" => this.handle_logging_set_level(req).await,
                 _ => {
                     let mut response = this.create_response(req.id);
                     response.error = Some(RouterError::MethodNotFound(req.method).into());
@@ -429,3 +704,376 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_core::tool::{Tool, ToolAnnotations};
+    use mcp_macros::router;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[serde(rename_all = "snake_case")]
+    enum Operation {
+        Add,
+        Multiply,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    struct Division {
+        quotient: i64,
+        remainder: i64,
+    }
+
+    #[derive(Clone)]
+    struct CalculatorRouter;
+
+    #[router]
+    impl Router for CalculatorRouter {
+        fn name(&self) -> String {
+            "calculator".to_string()
+        }
+
+        fn instructions(&self) -> String {
+            String::new()
+        }
+
+        fn capabilities(&self) -> ServerCapabilities {
+            CapabilitiesBuilder::new().with_tools(false).build()
+        }
+
+        fn list_resources(&self) -> Vec<mcp_core::resource::Resource> {
+            vec![]
+        }
+
+        fn read_resource(
+            &self,
+            uri: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+            let uri = uri.to_string();
+            Box::pin(async move { Err(ResourceError::NotFound(uri)) })
+        }
+
+        fn list_prompts(&self) -> Vec<Prompt> {
+            vec![]
+        }
+
+        fn get_prompt(&self, prompt_name: &str) -> PromptFuture {
+            let prompt_name = prompt_name.to_string();
+            Box::pin(async move { Err(PromptError::NotFound(prompt_name)) })
+        }
+
+        /// Apply an operation to two numbers
+        #[tool(
+            params(x = "The first number", y = "The second number"),
+            read_only_hint = true,
+            open_world_hint = false
+        )]
+        fn calculate(&self, x: i64, y: i64, operation: Operation) -> i64 {
+            match operation {
+                Operation::Add => x + y,
+                Operation::Multiply => x * y,
+            }
+        }
+
+        /// Greet someone, politely unless told otherwise
+        #[tool]
+        async fn greet(&self, name: String, polite: Option<bool>) -> Result<String, ToolError> {
+            if name.is_empty() {
+                return Err(ToolError::InvalidParameters("Name is empty".to_string()));
+            }
+            match polite.unwrap_or(true) {
+                true => Ok(format!("Hello, {}!", name)),
+                false => Ok(format!("Hey {}", name)),
+            }
+        }
+
+        /// Divide two numbers with remainder
+        #[tool]
+        fn divide(&self, x: i64, y: i64) -> Result<Division, ToolError> {
+            if y == 0 {
+                return Err(ToolError::InvalidParameters("Division by zero".to_string()));
+            }
+            Ok(Division {
+                quotient: x / y,
+                remainder: x % y,
+            })
+        }
+
+        #[tool(name = "echo", description = "Echo the arguments", schema = json!({"type": "object"}))]
+        fn echo_arguments(arguments: Value) -> Vec<Content> {
+            vec![Content::text(arguments.to_string())]
+        }
+
+        /// Sum some numbers
+        #[tool(output_schema = json!({"type": "object", "required": ["sum"]}))]
+        fn sum(&self, numbers: Vec<i64>) -> ToolOutput {
+            ToolOutput::structured(json!({"sum": numbers.iter().sum::<i64>()}))
+        }
+    }
+
+    #[test]
+    fn test_router_lists_tools() {
+        let tools: Vec<Tool> = CalculatorRouter.list_tools();
+        let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+        assert_eq!(names, vec!["calculate", "greet", "divide", "echo", "sum"]);
+
+        let calculate = &tools[0];
+        assert_eq!(calculate.description, "Apply an operation to two numbers");
+        let schema = &calculate.input_schema;
+        assert_eq!(schema["required"], json!(["operation", "x", "y"]));
+        assert_eq!(
+            schema["properties"]["x"]["description"],
+            json!("The first number")
+        );
+        assert_eq!(
+            schema["properties"]["operation"]["enum"],
+            json!(["add", "multiply"])
+        );
+
+        assert_eq!(
+            calculate.annotations,
+            Some(ToolAnnotations {
+                read_only_hint: true,
+                open_world_hint: false,
+                ..Default::default()
+            })
+        );
+
+        // options are not required, and have no default
+        assert_eq!(tools[1].input_schema["required"], json!(["name"]));
+        assert_eq!(
+            tools[1].input_schema["properties"]["polite"].get("default"),
+            None
+        );
+        assert_eq!(tools[1].annotations, None);
+        assert_eq!(tools[3].input_schema, json!({"type": "object"}));
+
+        // only tools returning objects have an output schema
+        assert_eq!(calculate.output_schema, None);
+        assert_eq!(tools[1].output_schema, None);
+        let output_schema = tools[2].output_schema.as_ref().unwrap();
+        assert_eq!(output_schema["required"], json!(["quotient", "remainder"]));
+        assert_eq!(
+            tools[4].output_schema,
+            Some(json!({"type": "object", "required": ["sum"]}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_router_dispatches_tools() {
+        let router = CalculatorRouter;
+
+        let result = router
+            .call_tool(
+                "calculate",
+                json!({"x": 2, "y": 3, "operation": "multiply"}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result, vec![Content::text("6")]);
+
+        let result = router
+            .call_tool("greet", json!({"name": "goose"}))
+            .await
+            .unwrap();
+        assert_eq!(result, vec![Content::text("Hello, goose!")]);
+
+        let result = router.call_tool("echo", json!({"a": 1})).await.unwrap();
+        assert_eq!(result, vec![Content::text(r#"{"a":1}"#)]);
+
+        let output = router
+            .call_tool_with_output("sum", json!({"numbers": [1, 2, 3]}))
+            .await
+            .unwrap();
+        assert_eq!(output.structured_content, Some(json!({"sum": 6})));
+
+        // errors of the tool are passed on, bad arguments and unknown tools are reported
+        let error = router
+            .call_tool("greet", json!({"name": ""}))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error,
+            ToolError::InvalidParameters("Name is empty".to_string())
+        );
+        let error = router
+            .call_tool("calculate", json!({"x": 2, "operation": "divide"}))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ToolError::InvalidParameters(_)));
+        let error = router.call_tool("missing", json!({})).await.unwrap_err();
+        assert!(matches!(error, ToolError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_router_validates_tool_arguments() {
+        let request = |arguments: Value| JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(1),
+            method: "tools/call".to_string(),
+            params: Some(json!({"name": "calculate", "arguments": arguments})),
+        };
+
+        let response = CalculatorRouter
+            .handle_tools_call(request(json!({"x": "2", "operation": "divide"})))
+            .await
+            .unwrap();
+        let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(result.is_error, Some(true));
+        let message = result.content[0].as_text().unwrap();
+        assert!(message.contains("- /:"), "{}", message);
+        assert!(message.contains("- /x:"), "{}", message);
+        assert!(message.contains("- /operation:"), "{}", message);
+
+        let response = CalculatorRouter
+            .handle_tools_call(request(json!({"x": 2, "y": 3, "operation": "add"})))
+            .await
+            .unwrap();
+        let result: CallToolResult = serde_json::from_value(response.result.unwrap()).unwrap();
+        assert_eq!(result.content, vec![Content::text("5")]);
+        assert_eq!(result.structured_content, None);
+    }
+
+    #[tokio::test]
+    async fn test_router_returns_structured_content() {
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(1),
+            method: "tools/call".to_string(),
+            params: Some(json!({"name": "divide", "arguments": {"x": 7, "y": 2}})),
+        };
+
+        let response = CalculatorRouter.handle_tools_call(request).await.unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(
+            result["structuredContent"],
+            json!({"quotient": 3, "remainder": 1})
+        );
+        // the serialized content is kept for clients without structured content support
+        assert_eq!(
+            result["content"][0]["text"],
+            json!(r#"{"quotient":3,"remainder":1}"#)
+        );
+    }
+
+    // Returns its arguments as the result of `structured`, or as text from `text`
+    #[derive(Clone)]
+    struct StructuredRouter;
+
+    impl Router for StructuredRouter {
+        fn name(&self) -> String {
+            "structured".to_string()
+        }
+
+        fn instructions(&self) -> String {
+            String::new()
+        }
+
+        fn capabilities(&self) -> ServerCapabilities {
+            CapabilitiesBuilder::new().with_tools(false).build()
+        }
+
+        fn list_tools(&self) -> Vec<Tool> {
+            let output_schema = json!({
+                "type": "object",
+                "properties": {"count": {"type": "integer"}},
+                "required": ["count"]
+            });
+            ["structured", "text"]
+                .into_iter()
+                .map(|name| {
+                    Tool::new(name, "Return the arguments", json!({"type": "object"}))
+                        .with_output_schema(output_schema.clone())
+                })
+                .collect()
+        }
+
+        fn call_tool(
+            &self,
+            _tool_name: &str,
+            arguments: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>>
+        {
+            Box::pin(async move { Ok(vec![Content::text(arguments.to_string())]) })
+        }
+
+        fn call_tool_with_output(
+            &self,
+            tool_name: &str,
+            arguments: Value,
+        ) -> Pin<Box<dyn Future<Output = Result<ToolOutput, ToolError>> + Send + 'static>> {
+            match tool_name {
+                "structured" => Box::pin(async move { Ok(ToolOutput::structured(arguments)) }),
+                _ => {
+                    let call = self.call_tool(tool_name, arguments);
+                    Box::pin(async move { call.await.map(ToolOutput::from) })
+                }
+            }
+        }
+
+        fn list_resources(&self) -> Vec<mcp_core::resource::Resource> {
+            vec![]
+        }
+
+        fn read_resource(
+            &self,
+            uri: &str,
+        ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+            let uri = uri.to_string();
+            Box::pin(async move { Err(ResourceError::NotFound(uri)) })
+        }
+
+        fn list_prompts(&self) -> Vec<Prompt> {
+            vec![]
+        }
+
+        fn get_prompt(&self, prompt_name: &str) -> PromptFuture {
+            let prompt_name = prompt_name.to_string();
+            Box::pin(async move { Err(PromptError::NotFound(prompt_name)) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_router_validates_structured_content() {
+        let call = |name: &str, arguments: Value| {
+            let request = JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                id: Some(1),
+                method: "tools/call".to_string(),
+                params: Some(json!({"name": name, "arguments": arguments})),
+            };
+            async move {
+                let response = StructuredRouter.handle_tools_call(request).await.unwrap();
+                serde_json::from_value::<CallToolResult>(response.result.unwrap()).unwrap()
+            }
+        };
+
+        let result = call("structured", json!({"count": 2})).await;
+        assert_eq!(result.structured_content, Some(json!({"count": 2})));
+
+        let result = call("structured", json!({"count": "two"})).await;
+        assert_eq!(result.is_error, Some(true));
+        assert_eq!(result.structured_content, None);
+        let message = result.content[0].as_text().unwrap();
+        assert!(message.contains("output schema"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn test_router_requires_structured_content_for_output_schema() {
+        // text that happens to be JSON is not taken for structured content
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            id: Some(1),
+            method: "tools/call".to_string(),
+            params: Some(json!({"name": "text", "arguments": {"count": 2}})),
+        };
+        let error = StructuredRouter
+            .handle_tools_call(request)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, RouterError::Internal(_)), "{}", error);
+    }
+}
@@ -0,0 +1,74 @@
+//! Routers for the tests of crates serving or connecting to MCP servers, enabled through
+//! the `testing` feature
+
+use std::{future::Future, pin::Pin};
+
+use mcp_core::{
+    handler::{PromptError, ResourceError, ToolError},
+    prompt::Prompt,
+    protocol::ServerCapabilities,
+    resource::Resource,
+    Content, Tool,
+};
+use serde_json::{json, Value};
+
+use crate::router::CapabilitiesBuilder;
+use crate::Router;
+
+/// A server with a single `echo` tool, which answers with its arguments as JSON text
+#[derive(Clone)]
+pub struct EchoRouter;
+
+impl Router for EchoRouter {
+    fn name(&self) -> String {
+        "echo".to_string()
+    }
+
+    fn instructions(&self) -> String {
+        "Echoes its input".to_string()
+    }
+
+    fn capabilities(&self) -> ServerCapabilities {
+        CapabilitiesBuilder::new().with_tools(false).build()
+    }
+
+    fn list_tools(&self) -> Vec<Tool> {
+        vec![Tool::new(
+            "echo",
+            "Echo the arguments",
+            json!({"type": "object"}),
+        )]
+    }
+
+    fn call_tool(
+        &self,
+        _tool_name: &str,
+        arguments: Value,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<Content>, ToolError>> + Send + 'static>> {
+        Box::pin(async move { Ok(vec![Content::text(arguments.to_string())]) })
+    }
+
+    fn list_resources(&self) -> Vec<Resource> {
+        vec![]
+    }
+
+    fn read_resource(
+        &self,
+        uri: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, ResourceError>> + Send + 'static>> {
+        let uri = uri.to_string();
+        Box::pin(async move { Err(ResourceError::NotFound(uri)) })
+    }
+
+    fn list_prompts(&self) -> Vec<Prompt> {
+        vec![]
+    }
+
+    fn get_prompt(
+        &self,
+        prompt_name: &str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, PromptError>> + Send + 'static>> {
+        let prompt_name = prompt_name.to_string();
+        Box::pin(async move { Err(PromptError::NotFound(prompt_name)) })
+    }
+}
@@ -5,6 +5,11 @@ export interface SessionMetadata {
   message_count: number;
   total_tokens: number | null;
   working_dir: string; // Required in type, but may be missing in old sessions
+  schema_version?: number;
+  created_at?: string;
+  updated_at?: string;
+  tags?: string[];
+  archived?: boolean;
 }
 
 // Helper function to ensure working directory is set